@@ -188,6 +188,10 @@ pub struct PromptRecord {
     pub agent_id: AgentId,
     pub human_author: Option<String>,
     pub messages: Vec<Message>,
+    /// Blob id of the transcript in the prompt store (`refs/notes/ai-prompts`). When set,
+    /// `messages` is left empty in the note and resolved from the store on read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub messages_ref: Option<String>,
     #[serde(default)]
     pub total_additions: u32,
     #[serde(default)]
@@ -196,4 +200,11 @@ pub struct PromptRecord {
     pub accepted_lines: u32,
     #[serde(default)]
     pub overriden_lines: u32,
+    /// Number of secrets/PII matches masked out of `messages` before storage
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub redactions: u32,
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
 }
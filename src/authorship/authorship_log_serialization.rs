@@ -1,5 +1,7 @@
 use crate::authorship::authorship_log::{Author, LineRange, PromptRecord};
+use crate::authorship::redaction;
 use crate::config;
+use crate::git::signing::is_signature_header;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::{BTreeMap, HashMap};
@@ -7,7 +9,20 @@ use std::fmt;
 use std::io::{BufRead, Write};
 
 /// Authorship log format version identifier
-pub const AUTHORSHIP_LOG_VERSION: &str = "authorship/3.0.0";
+pub const AUTHORSHIP_LOG_VERSION: &str = "authorship/4.0.0";
+
+/// Previous format: attestation entries carry only a prompt hash and line ranges
+pub const AUTHORSHIP_LOG_VERSION_V3: &str = "authorship/3.0.0";
+
+/// Versions `deserialize_from_string` can read. Older versions are upgraded in memory and
+/// written back in the current format.
+pub const SUPPORTED_AUTHORSHIP_LOG_VERSIONS: &[&str] =
+    &[AUTHORSHIP_LOG_VERSION_V3, AUTHORSHIP_LOG_VERSION];
+
+/// Header of the free-text AI% block older versions appended after the JSON metadata.
+/// Everything from this line on is ignored when reading a note, as is a signature block
+/// (see `git::signing`).
+const LEGACY_AI_SUMMARY_HEADER: &str = "--- AI Contribution Metadata ---";
 
 /// Metadata section that goes below the divider as JSON
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -15,6 +30,9 @@ pub struct AuthorshipMetadata {
     pub schema_version: String,
     pub base_commit_sha: String,
     pub prompts: BTreeMap<String, PromptRecord>,
+    /// AI contribution totals, recorded once the commit has landed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<AiContributionSummary>,
 }
 
 impl AuthorshipMetadata {
@@ -23,10 +41,24 @@ impl AuthorshipMetadata {
             schema_version: AUTHORSHIP_LOG_VERSION.to_string(),
             base_commit_sha: String::new(),
             prompts: BTreeMap::new(),
+            summary: None,
         }
     }
 }
 
+/// Line counts behind a commit's AI percentage, so they can be shown without re-diffing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AiContributionSummary {
+    /// Commit the numbers were computed for. Notes copied onto rewritten commits keep the
+    /// old summary, which is then recognisably stale.
+    pub commit_sha: String,
+    pub ai_percentage: u32,
+    pub ai_lines: u32,
+    pub human_lines: u32,
+    pub mixed_lines: u32,
+    pub total_lines: u32,
+}
+
 impl Default for AuthorshipMetadata {
     fn default() -> Self {
         Self::new()
@@ -43,11 +75,33 @@ pub struct AttestationEntry {
     pub hash: String,
     /// Line ranges that this prompt is responsible for
     pub line_ranges: Vec<LineRange>,
+    /// Unix time of the latest checkpoint that produced these lines (v4, None if unknown)
+    pub timestamp: Option<u64>,
+    /// Content fingerprint of each attributed line in ascending line order (v4).
+    /// Derived from the commit contents when the note is written; cleared when ranges move.
+    pub fingerprints: Vec<String>,
 }
 
 impl AttestationEntry {
     pub fn new(hash: String, line_ranges: Vec<LineRange>) -> Self {
-        Self { hash, line_ranges }
+        Self {
+            hash,
+            line_ranges,
+            timestamp: None,
+            fingerprints: Vec::new(),
+        }
+    }
+
+    /// Attributed line numbers in ascending order (the order fingerprints are stored in)
+    pub fn lines(&self) -> Vec<u32> {
+        let mut lines: Vec<u32> = self
+            .line_ranges
+            .iter()
+            .flat_map(|range| range.expand())
+            .collect();
+        lines.sort_unstable();
+        lines.dedup();
+        lines
     }
 
     pub fn remove_line_ranges(&mut self, to_remove: &[LineRange]) {
@@ -62,6 +116,7 @@ impl AttestationEntry {
         }
 
         self.line_ranges = current_ranges;
+        self.fingerprints.clear();
     }
 
     /// Shift line ranges by a given offset starting at insertion_point
@@ -73,6 +128,7 @@ impl AttestationEntry {
             }
         }
         self.line_ranges = shifted_ranges;
+        self.fingerprints.clear();
     }
 }
 
@@ -278,22 +334,27 @@ impl AuthorshipLog {
         let session_id_opt = match (&checkpoint.agent_id, &checkpoint.transcript) {
             (Some(agent), Some(transcript)) => {
                 let session_id = generate_short_hash(&agent.id, &agent.tool);
+                // Mask secrets and PII before the transcript lands in the (shared) notes
+                let (messages, redactions) = redaction::redact_messages(transcript.messages());
                 // Insert or update the prompt session transcript
-                let entry =
-                    self.metadata
-                        .prompts
-                        .entry(session_id.clone())
-                        .or_insert(PromptRecord {
-                            agent_id: agent.clone(),
-                            human_author: human_author.map(|s| s.to_string()),
-                            messages: transcript.messages().to_vec(),
-                            total_additions: 0,
-                            total_deletions: 0,
-                            accepted_lines: 0,
-                            overriden_lines: 0,
-                        });
-                if entry.messages.len() < transcript.messages().len() {
-                    entry.messages = transcript.messages().to_vec();
+                let entry = self
+                    .metadata
+                    .prompts
+                    .entry(session_id.clone())
+                    .or_insert_with(|| PromptRecord {
+                        agent_id: agent.clone(),
+                        human_author: human_author.map(|s| s.to_string()),
+                        messages: Vec::new(),
+                        messages_ref: None,
+                        total_additions: 0,
+                        total_deletions: 0,
+                        accepted_lines: 0,
+                        overriden_lines: 0,
+                        redactions: 0,
+                    });
+                if entry.messages.len() < messages.len() {
+                    entry.messages = messages;
+                    entry.redactions = redactions;
                 }
                 Some(session_id)
             }
@@ -396,7 +457,8 @@ impl AuthorshipLog {
                 // Only process AI-generated content (entries with prompt_session_id)
                 if let Some(session_id) = session_id_opt.clone() {
                     // Add new attestation entry for the AI-added lines
-                    let entry = AttestationEntry::new(session_id, new_line_ranges);
+                    let mut entry = AttestationEntry::new(session_id, new_line_ranges);
+                    entry.timestamp = Some(checkpoint.timestamp);
                     file_attestation.add_entry(entry);
                 }
             }
@@ -433,27 +495,34 @@ impl AuthorshipLog {
             let mut consolidated_entries = Vec::new();
             let mut current_hash: Option<String> = None;
             let mut current_ranges: Vec<LineRange> = Vec::new();
+            let mut current_timestamp: Option<u64> = None;
 
             for entry in &file_attestation.entries {
                 if current_hash.as_ref() == Some(&entry.hash) {
-                    // Same hash, accumulate line ranges
+                    // Same hash, accumulate line ranges and keep the latest timestamp
                     current_ranges.extend(entry.line_ranges.clone());
+                    current_timestamp = current_timestamp.max(entry.timestamp);
                 } else {
                     // Different hash, save previous entry and start new one
                     if let Some(hash) = current_hash.take() {
                         // Merge overlapping and adjacent ranges before adding
                         let merged_ranges = Self::merge_line_ranges(&current_ranges);
-                        consolidated_entries.push(AttestationEntry::new(hash, merged_ranges));
+                        let mut consolidated = AttestationEntry::new(hash, merged_ranges);
+                        consolidated.timestamp = current_timestamp;
+                        consolidated_entries.push(consolidated);
                     }
                     current_hash = Some(entry.hash.clone());
                     current_ranges = entry.line_ranges.clone();
+                    current_timestamp = entry.timestamp;
                 }
             }
 
             // Don't forget the last entry
             if let Some(hash) = current_hash {
                 let merged_ranges = Self::merge_line_ranges(&current_ranges);
-                consolidated_entries.push(AttestationEntry::new(hash, merged_ranges));
+                let mut consolidated = AttestationEntry::new(hash, merged_ranges);
+                consolidated.timestamp = current_timestamp;
+                consolidated_entries.push(consolidated);
             }
 
             file_attestation.entries = consolidated_entries;
@@ -555,6 +624,91 @@ impl AuthorshipLog {
         }
     }
 
+    /// Move attestations recorded under an old path to the new path (old path -> new path),
+    /// merging them into any attestations that already exist for the new path
+    pub fn rename_files(&mut self, renames: &HashMap<String, String>) {
+        let mut renamed_attestations = Vec::new();
+        self.attestations.retain(|file_attestation| {
+            match renames.get(&file_attestation.file_path) {
+                Some(new_path) => {
+                    renamed_attestations.push((new_path.clone(), file_attestation.entries.clone()));
+                    false
+                }
+                None => true,
+            }
+        });
+
+        for (new_path, entries) in renamed_attestations {
+            let file_attestation = self.get_or_create_file(&new_path);
+            file_attestation.entries.extend(entries);
+        }
+    }
+
+    /// Union of two notes written for the same commit. Prompts from both sides are kept;
+    /// a line attributed on both sides goes to the attestation with the later timestamp
+    /// (then the greater hash), so `merge(a, b)` and `merge(b, a)` produce the same note.
+    /// Fingerprints are dropped and recomputed when the note is written.
+    pub fn merge(ours: &AuthorshipLog, theirs: &AuthorshipLog) -> AuthorshipLog {
+        let mut merged = AuthorshipLog::new();
+        merged.metadata.base_commit_sha = std::cmp::max(
+            &ours.metadata.base_commit_sha,
+            &theirs.metadata.base_commit_sha,
+        )
+        .clone();
+
+        merged.metadata.prompts = ours.metadata.prompts.clone();
+        for (hash, their_prompt) in &theirs.metadata.prompts {
+            let prompt = match merged.metadata.prompts.get(hash) {
+                Some(our_prompt) => more_complete_prompt(our_prompt, their_prompt),
+                None => their_prompt,
+            };
+            merged.metadata.prompts.insert(hash.clone(), prompt.clone());
+        }
+
+        merged.attestations = merge_attestations(&[ours, theirs]);
+
+        // A stored summary only stays valid if the merge left its side's attributions as-is
+        let summaries: Vec<&AiContributionSummary> = [ours, theirs]
+            .into_iter()
+            .filter(|log| merge_attestations(&[log]) == merged.attestations)
+            .filter_map(|log| log.metadata.summary.as_ref())
+            .collect();
+        if let Some(first) = summaries.first()
+            && summaries.iter().all(|summary| summary == first)
+        {
+            merged.metadata.summary = Some((*first).clone());
+        }
+
+        merged
+    }
+
+    /// Recompute the fingerprint of every attributed line from the file contents it
+    /// attests to. Entries whose lines fall outside the file are left without fingerprints.
+    pub fn compute_fingerprints<F>(&mut self, mut read_file: F)
+    where
+        F: FnMut(&str) -> Option<String>,
+    {
+        for file_attestation in &mut self.attestations {
+            let content = read_file(&file_attestation.file_path);
+            let file_lines: Vec<&str> = content
+                .as_deref()
+                .map(|content| content.lines().collect())
+                .unwrap_or_default();
+            for entry in &mut file_attestation.entries {
+                entry.fingerprints = entry
+                    .lines()
+                    .iter()
+                    .map(|line| {
+                        file_lines
+                            .get((*line as usize).wrapping_sub(1))
+                            .map(|text| line_fingerprint(text))
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .unwrap_or_default();
+            }
+        }
+    }
+
     pub fn get_or_create_file(&mut self, file: &str) -> &mut FileAttestation {
         // Check if file already exists
         let exists = self.attestations.iter().any(|f| f.file_path == file);
@@ -591,6 +745,15 @@ impl AuthorshipLog {
                 output.push_str(&entry.hash);
                 output.push(' ');
                 output.push_str(&format_line_ranges(&entry.line_ranges));
+                if let Some(timestamp) = entry.timestamp {
+                    output.push_str(&format!(" t={}", timestamp));
+                }
+                // Stale fingerprints (ranges changed since they were computed) are dropped
+                if !entry.fingerprints.is_empty() && entry.fingerprints.len() == entry.lines().len()
+                {
+                    output.push_str(" fp=");
+                    output.push_str(&entry.fingerprints.join(","));
+                }
                 output.push('\n');
             }
         }
@@ -598,8 +761,10 @@ impl AuthorshipLog {
         // Write divider
         output.push_str("---\n");
 
-        // Write JSON metadata section
-        let json_str = serde_json::to_string_pretty(&self.metadata).map_err(|_| fmt::Error)?;
+        // Write JSON metadata section (always in the current schema)
+        let mut metadata = self.metadata.clone();
+        metadata.schema_version = AUTHORSHIP_LOG_VERSION.to_string();
+        let json_str = serde_json::to_string_pretty(&metadata).map_err(|_| fmt::Error)?;
         output.push_str(&json_str);
 
         Ok(output)
@@ -624,14 +789,27 @@ impl AuthorshipLog {
             .position(|&line| line == "---")
             .ok_or("Missing divider '---' in authorship log")?;
 
-        // Parse attestation section (before divider)
-        let attestation_lines = &lines[..divider_pos];
-        let attestations = parse_attestation_section(attestation_lines)?;
-
-        // Parse JSON metadata section (after divider)
-        let json_lines = &lines[divider_pos + 1..];
+        // Parse JSON metadata section (after divider) first: its schema version decides
+        // how the attestation section is read
+        let json_end = lines[divider_pos + 1..]
+            .iter()
+            .position(|&line| line == LEGACY_AI_SUMMARY_HEADER || is_signature_header(line))
+            .map_or(lines.len(), |pos| divider_pos + 1 + pos);
+        let json_lines = &lines[divider_pos + 1..json_end];
         let json_content = json_lines.join("\n");
         let metadata: AuthorshipMetadata = serde_json::from_str(&json_content)?;
+        if !SUPPORTED_AUTHORSHIP_LOG_VERSIONS.contains(&metadata.schema_version.as_str()) {
+            return Err(format!(
+                "Unsupported authorship log version: {}",
+                metadata.schema_version
+            )
+            .into());
+        }
+        let entry_fields = metadata.schema_version != AUTHORSHIP_LOG_VERSION_V3;
+
+        // Parse attestation section (before divider)
+        let attestation_lines = &lines[..divider_pos];
+        let attestations = parse_attestation_section(attestation_lines, entry_fields)?;
 
         Ok(Self {
             attestations,
@@ -678,6 +856,22 @@ impl AuthorshipLog {
         None
     }
 
+    /// Returns the prompt hash that authored a line, if it was AI-authored
+    pub fn get_line_prompt_hash(&self, file: &str, line: u32) -> Option<&str> {
+        let file_attestation = self.attestations.iter().find(|f| f.file_path == file)?;
+
+        // Check entries in reverse order (latest wins)
+        file_attestation
+            .entries
+            .iter()
+            .rev()
+            .find(|entry| {
+                self.metadata.prompts.contains_key(&entry.hash)
+                    && entry.line_ranges.iter().any(|range| range.contains(line))
+            })
+            .map(|entry| entry.hash.as_str())
+    }
+
     /// Convert authorship log to working log checkpoints for merge --squash
     ///
     /// Creates one checkpoint per file per session that touched that file. This ensures that:
@@ -871,9 +1065,11 @@ fn parse_line_ranges(input: &str) -> Result<Vec<LineRange>, Box<dyn std::error::
     Ok(ranges)
 }
 
-/// Parse the attestation section (before the divider)
+/// Parse the attestation section (before the divider). `entry_fields` enables the v4
+/// `t=<timestamp>` and `fp=<fingerprints>` fields after the line ranges.
 fn parse_attestation_section(
     lines: &[&str],
+    entry_fields: bool,
 ) -> Result<Vec<FileAttestation>, Box<dyn std::error::Error>> {
     let mut attestations = Vec::new();
     let mut current_file: Option<FileAttestation> = None;
@@ -892,10 +1088,23 @@ fn parse_attestation_section(
             // Split on first space to separate hash from line ranges
             if let Some(space_pos) = entry_line.find(' ') {
                 let hash = entry_line[..space_pos].to_string();
-                let ranges_str = &entry_line[space_pos + 1..];
-                let line_ranges = parse_line_ranges(ranges_str)?;
-
-                let entry = AttestationEntry::new(hash, line_ranges);
+                let mut fields = entry_line[space_pos + 1..].split(' ');
+                let line_ranges = parse_line_ranges(fields.next().unwrap_or(""))?;
+
+                let mut entry = AttestationEntry::new(hash, line_ranges);
+                for field in fields {
+                    if !entry_fields {
+                        return Err(
+                            format!("Invalid attestation entry format: {}", entry_line).into()
+                        );
+                    }
+                    // Unknown fields are skipped so minor v4 additions stay readable
+                    if let Some(timestamp) = field.strip_prefix("t=") {
+                        entry.timestamp = Some(timestamp.parse()?);
+                    } else if let Some(fingerprints) = field.strip_prefix("fp=") {
+                        entry.fingerprints = fingerprints.split(',').map(String::from).collect();
+                    }
+                }
 
                 if let Some(ref mut file_attestation) = current_file {
                     file_attestation.add_entry(entry);
@@ -936,6 +1145,77 @@ fn parse_attestation_section(
     Ok(attestations)
 }
 
+/// Line-by-line union of the attestations in `logs`, sorted by file and prompt hash. A
+/// line attributed more than once goes to the latest timestamp, then the greatest hash.
+fn merge_attestations(logs: &[&AuthorshipLog]) -> Vec<FileAttestation> {
+    // file -> line -> (timestamp, hash) of the winning attestation
+    let mut winners: BTreeMap<&str, BTreeMap<u32, (Option<u64>, &str)>> = BTreeMap::new();
+    for log in logs {
+        for file_attestation in &log.attestations {
+            let lines = winners
+                .entry(file_attestation.file_path.as_str())
+                .or_default();
+            for entry in &file_attestation.entries {
+                let candidate = (entry.timestamp, entry.hash.as_str());
+                for line in entry.lines() {
+                    let winner = lines.entry(line).or_insert(candidate);
+                    *winner = std::cmp::max(*winner, candidate);
+                }
+            }
+        }
+    }
+
+    let mut attestations = Vec::new();
+    for (file_path, lines) in winners {
+        let mut by_hash: BTreeMap<&str, (Option<u64>, Vec<u32>)> = BTreeMap::new();
+        for (line, (timestamp, hash)) in lines {
+            let (latest, hash_lines) = by_hash.entry(hash).or_default();
+            *latest = std::cmp::max(*latest, timestamp);
+            hash_lines.push(line);
+        }
+
+        let mut file_attestation = FileAttestation::new(file_path.to_string());
+        for (hash, (timestamp, lines)) in by_hash {
+            let mut entry =
+                AttestationEntry::new(hash.to_string(), LineRange::compress_lines(&lines));
+            entry.timestamp = timestamp;
+            file_attestation.add_entry(entry);
+        }
+        if !file_attestation.entries.is_empty() {
+            attestations.push(file_attestation);
+        }
+    }
+    attestations
+}
+
+/// The record that carries more of the session when both notes have the same prompt,
+/// falling back to a content comparison so the choice doesn't depend on argument order
+fn more_complete_prompt<'a>(a: &'a PromptRecord, b: &'a PromptRecord) -> &'a PromptRecord {
+    let rank = |prompt: &PromptRecord| {
+        (
+            prompt.messages_ref.is_some() || !prompt.messages.is_empty(),
+            prompt.messages.len(),
+            prompt.accepted_lines,
+            prompt.total_additions,
+        )
+    };
+    match rank(a).cmp(&rank(b)) {
+        std::cmp::Ordering::Greater => a,
+        std::cmp::Ordering::Less => b,
+        std::cmp::Ordering::Equal => {
+            let content = |prompt: &PromptRecord| serde_json::to_string(prompt).unwrap_or_default();
+            if content(a) >= content(b) { a } else { b }
+        }
+    }
+}
+
+/// Short content fingerprint of a single line (trailing whitespace and CR are ignored)
+pub fn line_fingerprint(line: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(line.trim_end().as_bytes());
+    format!("{:x}", hasher.finalize())[..8].to_string()
+}
+
 /// Check if a file path needs quoting (contains spaces or whitespace)
 fn needs_quoting(path: &str) -> bool {
     path.contains(' ') || path.contains('\t') || path.contains('\n')
@@ -1102,10 +1382,12 @@ mod tests {
                 agent_id: agent_id,
                 human_author: None,
                 messages: vec![],
+                messages_ref: None,
                 total_additions: 0,
                 total_deletions: 0,
                 accepted_lines: 0,
                 overriden_lines: 0,
+                redactions: 0,
             },
         );
 
@@ -1168,10 +1450,12 @@ mod tests {
                 agent_id: agent_id,
                 human_author: None,
                 messages: vec![],
+                messages_ref: None,
                 total_additions: 0,
                 total_deletions: 0,
                 accepted_lines: 0,
                 overriden_lines: 0,
+                redactions: 0,
             },
         );
 
@@ -1216,10 +1500,12 @@ mod tests {
                 agent_id: agent_id,
                 human_author: None,
                 messages: vec![],
+                messages_ref: None,
                 total_additions: 0,
                 total_deletions: 0,
                 accepted_lines: 0,
                 overriden_lines: 0,
+                redactions: 0,
             },
         );
 
@@ -1357,10 +1643,12 @@ mod tests {
                 agent_id: agent_id.clone(),
                 human_author: Some("alice@example.com".to_string()),
                 messages: transcript.messages().to_vec(),
+                messages_ref: None,
                 total_additions: 15,
                 total_deletions: 3,
                 accepted_lines: 12,
                 overriden_lines: 0,
+                redactions: 0,
             },
         );
 
@@ -1482,10 +1770,12 @@ mod tests {
                 agent_id: agent1,
                 human_author: Some("bob@example.com".to_string()),
                 messages: transcript1.messages().to_vec(),
+                messages_ref: None,
                 total_additions: 10,
                 total_deletions: 0,
                 accepted_lines: 10,
                 overriden_lines: 0,
+                redactions: 0,
             },
         );
 
@@ -1505,10 +1795,12 @@ mod tests {
                 agent_id: agent2,
                 human_author: Some("bob@example.com".to_string()),
                 messages: transcript2.messages().to_vec(),
+                messages_ref: None,
                 total_additions: 20,
                 total_deletions: 0,
                 accepted_lines: 20,
                 overriden_lines: 0,
+                redactions: 0,
             },
         );
 
@@ -1546,4 +1838,174 @@ mod tests {
             ai_checkpoints[1].agent_id.as_ref().unwrap().id
         );
     }
+
+    #[test]
+    fn test_deserialize_v3_note_is_upgraded_on_write() {
+        let v3 = "src/a.rs\n  abc1234 1-3\n---\n{\n  \"schema_version\": \"authorship/3.0.0\",\n  \"base_commit_sha\": \"\",\n  \"prompts\": {}\n}";
+        let log = AuthorshipLog::deserialize_from_string(v3).unwrap();
+        assert_eq!(log.metadata.schema_version, AUTHORSHIP_LOG_VERSION_V3);
+        assert_eq!(
+            log.attestations[0].entries[0],
+            AttestationEntry::new("abc1234".to_string(), vec![LineRange::Range(1, 3)])
+        );
+
+        let rewritten = log.serialize_to_string().unwrap();
+        assert!(rewritten.contains(AUTHORSHIP_LOG_VERSION));
+        assert!(!rewritten.contains(AUTHORSHIP_LOG_VERSION_V3));
+    }
+
+    #[test]
+    fn test_v3_note_rejects_v4_entry_fields() {
+        let v3 = "src/a.rs\n  abc1234 1-3 t=100\n---\n{\n  \"schema_version\": \"authorship/3.0.0\",\n  \"base_commit_sha\": \"\",\n  \"prompts\": {}\n}";
+        assert!(AuthorshipLog::deserialize_from_string(v3).is_err());
+    }
+
+    #[test]
+    fn test_unsupported_schema_version_is_rejected() {
+        let future = "---\n{\n  \"schema_version\": \"authorship/9.0.0\",\n  \"base_commit_sha\": \"\",\n  \"prompts\": {}\n}";
+        let err = AuthorshipLog::deserialize_from_string(future).unwrap_err();
+        assert!(err.to_string().contains("authorship/9.0.0"));
+    }
+
+    #[test]
+    fn test_v4_entry_timestamps_and_fingerprints_roundtrip() {
+        let mut log = AuthorshipLog::new();
+        let mut file = FileAttestation::new("src/a.rs".to_string());
+        let mut entry = AttestationEntry::new("abc1234".to_string(), vec![LineRange::Range(1, 3)]);
+        entry.timestamp = Some(1_700_000_000);
+        file.add_entry(entry);
+        log.attestations.push(file);
+
+        log.compute_fingerprints(|_| Some("fn a() {\n    1\n}  \n".to_string()));
+        let entry = &log.attestations[0].entries[0];
+        assert_eq!(
+            entry.fingerprints,
+            vec![
+                line_fingerprint("fn a() {"),
+                line_fingerprint("    1"),
+                line_fingerprint("}"),
+            ]
+        );
+
+        let serialized = log.serialize_to_string().unwrap();
+        assert!(serialized.contains(&format!(
+            "  abc1234 1-3 t=1700000000 fp={}\n",
+            entry.fingerprints.join(",")
+        )));
+        let deserialized = AuthorshipLog::deserialize_from_string(&serialized).unwrap();
+        assert_eq!(deserialized, log);
+
+        // Moving the lines invalidates the fingerprints, which are then left out
+        let mut moved = log.clone();
+        moved.attestations[0].entries[0].shift_line_ranges(1, 5);
+        let serialized = moved.serialize_to_string().unwrap();
+        assert!(serialized.contains("  abc1234 6-8 t=1700000000\n"));
+    }
+
+    #[test]
+    fn test_legacy_ai_summary_block_is_ignored() {
+        let note = "src/a.rs\n  abc1234 1-3\n---\n{\n  \"schema_version\": \"authorship/3.0.0\",\n  \"base_commit_sha\": \"\",\n  \"prompts\": {}\n}\n\n--- AI Contribution Metadata ---\nAI-Percentage: 100%\nAI-Lines: 3/3\nHuman-Lines: 0/3\nMixed-Lines: 0\nGenerated: 2025-10-15T12:34:56Z";
+        let log = AuthorshipLog::deserialize_from_string(note).unwrap();
+        assert_eq!(log.attestations.len(), 1);
+        assert_eq!(log.metadata.summary, None);
+
+        let rewritten = log.serialize_to_string().unwrap();
+        assert!(!rewritten.contains("AI Contribution Metadata"));
+    }
+
+    #[test]
+    fn test_summary_roundtrip() {
+        let mut log = AuthorshipLog::new();
+        log.metadata.summary = Some(AiContributionSummary {
+            commit_sha: "abc123".to_string(),
+            ai_percentage: 67,
+            ai_lines: 2,
+            human_lines: 1,
+            mixed_lines: 0,
+            total_lines: 3,
+        });
+
+        let serialized = log.serialize_to_string().unwrap();
+        let deserialized = AuthorshipLog::deserialize_from_string(&serialized).unwrap();
+        assert_eq!(deserialized, log);
+    }
+
+    #[test]
+    fn test_merge_is_a_symmetric_union() {
+        let prompt =
+            |id: &str, accepted_lines: u32| crate::authorship::authorship_log::PromptRecord {
+                agent_id: crate::authorship::working_log::AgentId {
+                    tool: "cursor".to_string(),
+                    id: id.to_string(),
+                    model: "claude-3-sonnet".to_string(),
+                },
+                human_author: None,
+                messages: vec![],
+                messages_ref: None,
+                total_additions: 0,
+                total_deletions: 0,
+                accepted_lines,
+                overriden_lines: 0,
+                redactions: 0,
+            };
+        let entry = |hash: &str, ranges: Vec<LineRange>, timestamp: u64| {
+            let mut entry = AttestationEntry::new(hash.to_string(), ranges);
+            entry.timestamp = Some(timestamp);
+            entry
+        };
+        let summary = AiContributionSummary {
+            commit_sha: "abc123".to_string(),
+            ai_percentage: 100,
+            ai_lines: 5,
+            human_lines: 0,
+            mixed_lines: 0,
+            total_lines: 5,
+        };
+
+        let mut ours = AuthorshipLog::new();
+        ours.metadata.base_commit_sha = "abc123".to_string();
+        ours.metadata
+            .prompts
+            .insert("aaaaaaa".to_string(), prompt("a", 5));
+        let mut file = FileAttestation::new("src/a.rs".to_string());
+        file.add_entry(entry("aaaaaaa", vec![LineRange::Range(1, 5)], 100));
+        ours.attestations.push(file);
+        ours.metadata.summary = Some(summary.clone());
+
+        // The other clone re-attributed lines 4-5 later and added a prompt for another file
+        let mut theirs = AuthorshipLog::new();
+        theirs.metadata.base_commit_sha = "abc123".to_string();
+        theirs
+            .metadata
+            .prompts
+            .insert("aaaaaaa".to_string(), prompt("a", 3));
+        theirs
+            .metadata
+            .prompts
+            .insert("bbbbbbb".to_string(), prompt("b", 4));
+        let mut file = FileAttestation::new("src/b.rs".to_string());
+        file.add_entry(entry("bbbbbbb", vec![LineRange::Range(1, 2)], 200));
+        theirs.attestations.push(file);
+        let mut file = FileAttestation::new("src/a.rs".to_string());
+        file.add_entry(entry("aaaaaaa", vec![LineRange::Range(1, 3)], 100));
+        file.add_entry(entry("bbbbbbb", vec![LineRange::Range(4, 5)], 200));
+        theirs.attestations.push(file);
+
+        let merged = AuthorshipLog::merge(&ours, &theirs);
+        assert_eq!(merged, AuthorshipLog::merge(&theirs, &ours));
+
+        assert_eq!(merged.metadata.base_commit_sha, "abc123");
+        assert_eq!(merged.metadata.prompts["aaaaaaa"].accepted_lines, 5);
+        assert!(merged.metadata.prompts.contains_key("bbbbbbb"));
+        assert_eq!(merged.get_line_prompt_hash("src/a.rs", 3), Some("aaaaaaa"));
+        assert_eq!(merged.get_line_prompt_hash("src/a.rs", 4), Some("bbbbbbb"));
+        assert_eq!(merged.get_line_prompt_hash("src/b.rs", 2), Some("bbbbbbb"));
+        // Our summary no longer matches the merged attributions
+        assert_eq!(merged.metadata.summary, None);
+
+        // Merging a note with itself changes nothing that matters
+        let merged = AuthorshipLog::merge(&ours, &ours);
+        assert_eq!(merged.attestations, ours.attestations);
+        assert_eq!(merged.metadata.summary, Some(summary));
+    }
 }
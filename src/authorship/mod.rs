@@ -3,6 +3,8 @@ pub mod authorship_log_serialization;
 pub mod post_commit;
 pub mod pre_commit;
 pub mod rebase_authorship;
+pub mod redaction;
+pub mod stash_authorship;
 pub mod stats;
 pub mod transcript;
 pub mod working_log;
@@ -1,11 +1,14 @@
 use crate::authorship::authorship_log::LineRange;
 use crate::authorship::authorship_log_serialization::AuthorshipLog;
-use crate::authorship::stats::{stats_for_commit_stats, write_stats_to_terminal};
+use crate::authorship::stats::{
+    add_contribution_summary, stats_for_commit_stats, write_stats_to_terminal,
+};
 use crate::authorship::working_log::Checkpoint;
 use crate::commands::checkpoint_agent::agent_preset::CursorPreset;
 use crate::error::GitAiError;
-use crate::git::refs::notes_add;
+use crate::git::refs::notes_add_authorship;
 use crate::git::repository::Repository;
+use crate::utils::debug_log;
 use std::collections::{HashMap, HashSet};
 
 pub fn post_commit(
@@ -25,7 +28,18 @@ pub fn post_commit(
 
     // Pull all working log entries from the parent commit
 
-    let parent_working_log = working_log.read_all_checkpoints()?;
+    let mut parent_working_log = working_log.read_all_checkpoints()?;
+
+    // Follow renames (e.g. `git mv`) so attribution recorded under the old path
+    // carries over to the path in the new commit
+    let renames = if parent_sha != "initial" {
+        repo.renamed_files(&parent_sha, &commit_sha)?
+    } else {
+        HashMap::new()
+    };
+    for checkpoint in &mut parent_working_log {
+        checkpoint.rename_files(&renames);
+    }
 
     // debug_log(&format!(
     //     "edited files: {:?}",
@@ -75,12 +89,21 @@ pub fn post_commit(
         false
     };
 
-    // Serialize the authorship log
-    let authorship_json = authorship_log
-        .serialize_to_string()
-        .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
+    // Record the AI% summary in the log so the note is written once, with it
+    let stats = match add_contribution_summary(repo, &commit_sha, &mut authorship_log) {
+        Ok(stats) => Some(stats),
+        Err(e) => {
+            // Don't fail the commit if this fails, just warn
+            debug_log(&format!(
+                "Warning: Failed to compute AI contribution summary: {}",
+                e
+            ));
+            None
+        }
+    };
 
-    notes_add(repo, &commit_sha, &authorship_json)?;
+    // Serialize the authorship log (transcripts go to the prompt store)
+    notes_add_authorship(repo, &commit_sha, &authorship_log)?;
 
     // Only delete the working log if there are no unstaged AI-authored lines
     // If there are unstaged AI lines, filter and transfer the working log to the new commit
@@ -96,6 +119,8 @@ pub fn post_commit(
         let new_working_log = repo_storage.working_log_for_base_commit(&commit_sha);
 
         for mut checkpoint in parent_checkpoints {
+            checkpoint.rename_files(&renames);
+
             // Filter entries to only include unstaged lines
             for entry in &mut checkpoint.entries {
                 if let Some(unstaged_ranges) = unstaged_hunks.get(&entry.file) {
@@ -140,8 +165,13 @@ pub fn post_commit(
     }
 
     if !supress_output {
-        let refname = repo.head()?.name().unwrap().to_string();
-        let stats = stats_for_commit_stats(repo, &commit_sha, &refname)?;
+        let stats = match stats {
+            Some(stats) => stats,
+            None => {
+                let refname = repo.head()?.name().unwrap().to_string();
+                stats_for_commit_stats(repo, &commit_sha, &refname)?
+            }
+        };
         write_stats_to_terminal(&stats);
     }
     Ok((commit_sha.to_string(), authorship_log))
@@ -1,7 +1,9 @@
 use crate::authorship::authorship_log_serialization::AuthorshipLog;
 use crate::authorship::post_commit;
+use crate::authorship::stats::add_contribution_summary;
 use crate::commands::blame::GitAiBlameOptions;
 use crate::error::GitAiError;
+use crate::git::diff_tree_to_tree::DiffStatus;
 use crate::git::refs::get_reference_as_authorship_log_v3;
 use crate::git::repository::{Commit, Repository};
 use crate::git::rewrite_log::RewriteLogEvent;
@@ -179,11 +181,7 @@ pub fn rewrite_authorship_after_squash_or_rebase(
 
     if !dry_run {
         // Step (Save): Save the authorship log with the new sha as its id
-        let authorship_json = new_authorship_log
-            .serialize_to_string()
-            .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
-
-        crate::git::refs::notes_add(repo, &new_sha, &authorship_json)?;
+        crate::git::refs::notes_add_authorship(repo, &new_sha, &new_authorship_log)?;
 
         println!("Authorship log saved to notes/ai/{}", new_sha);
     }
@@ -572,7 +570,7 @@ pub fn rewrite_authorship_after_cherry_pick(
 ///
 /// Fast path: If trees are identical, just copy the authorship log
 /// Slow path: If trees differ, reconstruct via blame in hanging commit context
-fn rewrite_single_commit_authorship(
+pub fn rewrite_single_commit_authorship(
     repo: &Repository,
     old_sha: &str,
     new_sha: &str,
@@ -601,11 +599,7 @@ fn rewrite_single_commit_authorship(
     let new_authorship_log = reconstruct_authorship_for_commit(repo, old_sha, new_sha)?;
 
     // Save the reconstructed log
-    let authorship_json = new_authorship_log
-        .serialize_to_string()
-        .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
-
-    crate::git::refs::notes_add(repo, new_sha, &authorship_json)?;
+    crate::git::refs::notes_add_authorship(repo, new_sha, &new_authorship_log)?;
 
     Ok(())
 }
@@ -626,11 +620,7 @@ fn copy_authorship_log(repo: &Repository, from_sha: &str, to_sha: &str) -> Resul
             log.metadata.base_commit_sha = to_sha.to_string();
 
             // Save to the new commit
-            let authorship_json = log.serialize_to_string().map_err(|_| {
-                GitAiError::Generic("Failed to serialize authorship log".to_string())
-            })?;
-
-            crate::git::refs::notes_add(repo, to_sha, &authorship_json)?;
+            crate::git::refs::notes_add_authorship(repo, to_sha, &log)?;
             Ok(())
         }
         Err(_) => {
@@ -697,10 +687,14 @@ pub fn rewrite_authorship_after_commit_amend(
         }
     };
 
+    // Follow files renamed by the amend so existing attribution moves to the new path
+    let renames = repo.renamed_files(original_commit, amended_commit)?;
+    authorship_log.rename_files(&renames);
+
     // Step 2: Load the working log for the original commit (if exists)
     let repo_storage = &repo.storage;
     let working_log = repo_storage.working_log_for_base_commit(original_commit);
-    let checkpoints = match working_log.read_all_checkpoints() {
+    let mut checkpoints = match working_log.read_all_checkpoints() {
         Ok(checkpoints) => checkpoints,
         Err(_) => {
             // No working log found - just return the existing authorship log with updated commit SHA
@@ -710,6 +704,10 @@ pub fn rewrite_authorship_after_commit_amend(
         }
     };
 
+    for checkpoint in &mut checkpoints {
+        checkpoint.rename_files(&renames);
+    }
+
     // Step 3: Apply all checkpoints from the working log to the authorship log
     let mut session_additions = std::collections::HashMap::new();
     let mut session_deletions = std::collections::HashMap::new();
@@ -729,12 +727,16 @@ pub fn rewrite_authorship_after_commit_amend(
     // Update the base_commit_sha to the amended commit
     authorship_log.metadata.base_commit_sha = amended_commit.to_string();
 
-    // Step 4: Save the authorship log with the amended commit SHA
-    let authorship_json = authorship_log
-        .serialize_to_string()
-        .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
+    // Record the AI% summary for the amended commit before the note is written
+    if let Err(e) = add_contribution_summary(repo, amended_commit, &mut authorship_log) {
+        debug_log(&format!(
+            "Warning: Failed to compute AI contribution summary: {}",
+            e
+        ));
+    }
 
-    crate::git::refs::notes_add(repo, amended_commit, &authorship_json)?;
+    // Step 4: Save the authorship log with the amended commit SHA
+    crate::git::refs::notes_add_authorship(repo, amended_commit, &authorship_log)?;
 
     // Step 5: Delete the working log for the original commit
     repo_storage.delete_working_log_for_base_commit(original_commit)?;
@@ -866,9 +868,18 @@ fn reconstruct_authorship_from_diff(
 
         let file_path_str = file_path.to_string_lossy().to_string();
 
+        // For renames, the parent content lives under the old path
+        let old_file_path_str = if delta.status() == DiffStatus::Renamed {
+            old_file_path
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_else(|| file_path_str.clone())
+        } else {
+            file_path_str.clone()
+        };
+
         // Get the content of the file from both trees
         let old_content =
-            if let Ok(entry) = parent_tree.get_path(std::path::Path::new(&file_path_str)) {
+            if let Ok(entry) = parent_tree.get_path(std::path::Path::new(&old_file_path_str)) {
                 if let Ok(blob) = repo.find_blob(entry.id()) {
                     let content = blob.content()?;
                     String::from_utf8_lossy(&content).to_string()
@@ -1192,8 +1203,9 @@ fn run_blame_in_context(
         // Use the ORIGINAL line number from the blamed commit, not the current line number
         let orig_line_to_lookup = hunk.orig_range.0;
 
+        // Use the path the file had in the blamed commit, which differs after a rename
         if let Some((author, prompt)) =
-            authorship_log.get_line_attribution(file_path, orig_line_to_lookup)
+            authorship_log.get_line_attribution(&hunk.orig_file_path, orig_line_to_lookup)
         {
             Ok(Some((author.clone(), prompt.map(|p| (p.clone(), 0)))))
         } else {
@@ -1553,11 +1565,7 @@ fn create_authorship_log_for_hanging_commit(
     authorship_log.metadata.base_commit_sha = hanging_commit_sha.to_string();
 
     // Step 5: Save the authorship log to the hanging commit
-    let authorship_json = authorship_log
-        .serialize_to_string()
-        .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
-
-    crate::git::refs::notes_add(repo, hanging_commit_sha, &authorship_json)?;
+    crate::git::refs::notes_add_authorship(repo, hanging_commit_sha, &authorship_log)?;
 
     debug_log(&format!(
         "Attached authorship log to hanging commit {} with {} attestations",
@@ -0,0 +1,278 @@
+use crate::authorship::authorship_log::LineRange;
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::working_log::Checkpoint;
+use crate::error::GitAiError;
+use crate::git::repository::{Repository, exec_git};
+use crate::utils::debug_log;
+use sha2::{Digest, Sha256};
+use similar::{ChangeTag, TextDiff};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+/// Move the working log entries for the files captured by a stash into a snapshot
+/// keyed by the stash commit.
+///
+/// The snapshot keeps the checkpoints exactly as they were recorded (same layout as a
+/// regular working log) so they can be replayed when the stash is popped or applied.
+/// Unless `keep_working_log` is set (e.g. `git stash push --keep-index`), the stashed
+/// entries are removed from the working log of `base_commit` since those changes are
+/// no longer in the working directory.
+///
+/// Returns the list of files captured by the stash.
+pub fn save_working_log_for_stash(
+    repo: &Repository,
+    base_commit: &str,
+    stash_sha: &str,
+    keep_working_log: bool,
+) -> Result<Vec<String>, GitAiError> {
+    let stashed_files = list_stashed_files(repo, stash_sha)?;
+    let stashed_set: HashSet<&str> = stashed_files.iter().map(|f| f.as_str()).collect();
+
+    let working_log = repo.storage.working_log_for_base_commit(base_commit);
+    let checkpoints = working_log.read_all_checkpoints()?;
+
+    let mut stashed_checkpoints = Vec::new();
+    let mut remaining_checkpoints = Vec::new();
+    for checkpoint in checkpoints {
+        let (stashed_entries, remaining_entries): (Vec<_>, Vec<_>) = checkpoint
+            .entries
+            .iter()
+            .cloned()
+            .partition(|entry| stashed_set.contains(entry.file.as_str()));
+
+        if !stashed_entries.is_empty() {
+            let mut stashed = checkpoint.clone();
+            stashed.entries = stashed_entries;
+            stashed_checkpoints.push(stashed);
+        }
+        if !remaining_entries.is_empty() {
+            let mut remaining = checkpoint;
+            remaining.entries = remaining_entries;
+            remaining_checkpoints.push(remaining);
+        }
+    }
+
+    if stashed_checkpoints.is_empty() {
+        debug_log(&format!(
+            "No working log entries for stashed files, nothing to snapshot for {}",
+            stash_sha
+        ));
+        return Ok(stashed_files);
+    }
+
+    // Write the snapshot, including the file versions the checkpoints refer to
+    let stash_log = repo.storage.stash_log_for_commit(stash_sha);
+    stash_log.reset_working_log()?;
+    for checkpoint in &stashed_checkpoints {
+        for entry in &checkpoint.entries {
+            if entry.blob_sha.is_empty() {
+                continue;
+            }
+            if let Ok(content) = working_log.get_file_version(&entry.blob_sha) {
+                stash_log.persist_file_version(&content)?;
+            }
+        }
+        stash_log.append_checkpoint(checkpoint)?;
+    }
+
+    if !keep_working_log {
+        working_log.write_all_checkpoints(&remaining_checkpoints)?;
+    }
+
+    debug_log(&format!(
+        "Saved {} checkpoints for stash {} ({} checkpoints remain in working log)",
+        stashed_checkpoints.len(),
+        stash_sha,
+        remaining_checkpoints.len()
+    ));
+
+    Ok(stashed_files)
+}
+
+/// Re-apply the working log snapshot saved for `stash_sha` on top of the working log
+/// for `base_commit` (the HEAD the stash was applied to).
+///
+/// The snapshot's line numbers refer to the files as they were stashed. Because the
+/// stash may be applied onto a different commit, every attributed line is mapped
+/// through a diff between the stashed content and the current working directory
+/// content. Lines that no longer exist are dropped.
+///
+/// Returns the number of checkpoints appended to the working log.
+pub fn restore_working_log_from_stash(
+    repo: &Repository,
+    stash_sha: &str,
+    base_commit: &str,
+    human_author: &str,
+) -> Result<usize, GitAiError> {
+    if !repo.storage.has_stash_log(stash_sha) {
+        debug_log(&format!("No saved working log for stash {}", stash_sha));
+        return Ok(0);
+    }
+
+    let stash_log = repo.storage.stash_log_for_commit(stash_sha);
+    let stashed_checkpoints = stash_log.read_all_checkpoints()?;
+    if stashed_checkpoints.is_empty() {
+        return Ok(0);
+    }
+
+    let mut authorship_log = AuthorshipLog::from_working_log_with_base_commit_and_human_author(
+        &stashed_checkpoints,
+        base_commit,
+        Some(human_author),
+    );
+
+    let workdir = repo.workdir()?;
+    for file_attestation in &mut authorship_log.attestations {
+        let stashed_content = read_stashed_file(repo, stash_sha, &file_attestation.file_path);
+        let current_content =
+            fs::read_to_string(workdir.join(&file_attestation.file_path)).unwrap_or_default();
+        let line_map = map_unchanged_lines(&stashed_content, &current_content);
+
+        for entry in &mut file_attestation.entries {
+            let mut lines: Vec<u32> = entry
+                .line_ranges
+                .iter()
+                .flat_map(|range| range.expand())
+                .filter_map(|line| line_map.get(&line).copied())
+                .collect();
+            lines.sort_unstable();
+            lines.dedup();
+            entry.line_ranges = LineRange::compress_lines(&lines);
+        }
+        file_attestation
+            .entries
+            .retain(|entry| !entry.line_ranges.is_empty());
+    }
+    authorship_log
+        .attestations
+        .retain(|file_attestation| !file_attestation.entries.is_empty());
+
+    let mut checkpoints = authorship_log
+        .convert_to_checkpoints_for_squash(human_author)
+        .map_err(|e| {
+            GitAiError::Generic(format!(
+                "Failed to convert stashed authorship to checkpoints: {}",
+                e
+            ))
+        })?;
+    checkpoints.retain(|checkpoint| checkpoint.agent_id.is_some());
+
+    let working_log = repo.storage.working_log_for_base_commit(base_commit);
+    for checkpoint in &mut checkpoints {
+        persist_working_directory_blobs(repo, checkpoint, base_commit)?;
+        working_log.append_checkpoint(checkpoint)?;
+    }
+
+    debug_log(&format!(
+        "Restored {} checkpoints from stash {} onto {}",
+        checkpoints.len(),
+        stash_sha,
+        base_commit
+    ));
+
+    Ok(checkpoints.len())
+}
+
+/// Files captured by a stash commit: tracked changes (stash^1..stash) plus
+/// untracked files stored in the third parent when `--include-untracked` was used.
+fn list_stashed_files(repo: &Repository, stash_sha: &str) -> Result<Vec<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("diff".to_string());
+    args.push("--name-only".to_string());
+    args.push("--no-renames".to_string());
+    args.push(format!("{}^1", stash_sha));
+    args.push(stash_sha.to_string());
+    let output = exec_git(&args)?;
+    let mut files: Vec<String> = String::from_utf8(output.stdout)?
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.to_string())
+        .collect();
+
+    let mut args = repo.global_args_for_exec();
+    args.push("ls-tree".to_string());
+    args.push("-r".to_string());
+    args.push("--name-only".to_string());
+    args.push(format!("{}^3", stash_sha));
+    if let Ok(output) = exec_git(&args) {
+        for line in String::from_utf8(output.stdout)?.lines() {
+            if !line.is_empty() && !files.iter().any(|f| f == line) {
+                files.push(line.to_string());
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// Read a file as it was captured by the stash, looking in the untracked-files
+/// parent if it is not part of the stash's working tree.
+fn read_stashed_file(repo: &Repository, stash_sha: &str, file_path: &str) -> String {
+    for rev in [stash_sha.to_string(), format!("{}^3", stash_sha)] {
+        let mut args = repo.global_args_for_exec();
+        args.push("show".to_string());
+        args.push(format!("{}:{}", rev, file_path));
+        if let Ok(output) = exec_git(&args) {
+            return String::from_utf8_lossy(&output.stdout).to_string();
+        }
+    }
+    String::new()
+}
+
+/// Map 1-indexed line numbers in `old_content` to their position in `new_content`
+/// for every line that is unchanged between the two.
+fn map_unchanged_lines(old_content: &str, new_content: &str) -> HashMap<u32, u32> {
+    // Normalize trailing newlines to avoid spurious changes on the last line
+    let old_norm = if old_content.ends_with('\n') {
+        old_content.to_string()
+    } else {
+        format!("{}\n", old_content)
+    };
+    let new_norm = if new_content.ends_with('\n') {
+        new_content.to_string()
+    } else {
+        format!("{}\n", new_content)
+    };
+
+    let mut line_map = HashMap::new();
+    let diff = TextDiff::from_lines(&old_norm, &new_norm);
+    for change in diff.iter_all_changes() {
+        if change.tag() != ChangeTag::Equal {
+            continue;
+        }
+        if let (Some(old_index), Some(new_index)) = (change.old_index(), change.new_index()) {
+            line_map.insert(old_index as u32 + 1, new_index as u32 + 1);
+        }
+    }
+    line_map
+}
+
+/// Persist the current working directory version of each file in the checkpoint so
+/// that subsequent checkpoints diff against the restored state.
+fn persist_working_directory_blobs(
+    repo: &Repository,
+    checkpoint: &mut Checkpoint,
+    base_commit: &str,
+) -> Result<(), GitAiError> {
+    let working_log = repo.storage.working_log_for_base_commit(base_commit);
+    let mut file_hashes = Vec::new();
+
+    for entry in &mut checkpoint.entries {
+        let content = fs::read(working_log.repo_root.join(&entry.file))
+            .map(|bytes| String::from_utf8_lossy(&bytes).to_string())
+            .unwrap_or_default();
+        let blob_sha = working_log.persist_file_version(&content)?;
+        entry.blob_sha = blob_sha.clone();
+        file_hashes.push((entry.file.clone(), blob_sha));
+    }
+
+    file_hashes.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut combined_hasher = Sha256::new();
+    for (file_path, hash) in &file_hashes {
+        combined_hasher.update(file_path.as_bytes());
+        combined_hasher.update(hash.as_bytes());
+    }
+    checkpoint.diff = format!("{:x}", combined_hasher.finalize());
+
+    Ok(())
+}
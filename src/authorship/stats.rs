@@ -1,10 +1,12 @@
-use crate::authorship::authorship_log::LineRange;
-use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::authorship_log::{LineRange, PromptRecord};
+use crate::authorship::authorship_log_serialization::{AiContributionSummary, AuthorshipLog};
 use crate::authorship::transcript::Message;
+use crate::commands::blame::GitAiBlameOptions;
 use crate::error::GitAiError;
-use crate::git::refs::get_authorship;
-use crate::git::repository::Repository;
+use crate::git::refs::{get_authorship, show_authorship_note};
+use crate::git::repository::{Repository, exec_git};
 use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
 #[derive(Debug, Clone, Serialize)]
 pub struct CommitStats {
@@ -15,12 +17,45 @@ pub struct CommitStats {
     pub time_waiting_for_ai: u64, // seconds
     pub git_diff_deleted_lines: u32,
     pub git_diff_added_lines: u32,
+    // Per-path breakdown, only serialized when one was requested
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<PathStats>,
+    pub by_tool: Vec<AgentStats>,
+    pub by_model: Vec<AgentStats>,
+}
+
+/// AI additions attributed to a single tool (e.g. cursor) or model
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct AgentStats {
+    pub name: String,
+    pub ai_additions: u32,    // Accepted without human edits
+    pub mixed_additions: u32, // Overridden by humans
+}
+
+/// Additions for a single file, or for a directory when grouped with `--by-dir`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct PathStats {
+    pub path: String,
+    pub human_additions: u32, // Includes mixed lines, like CommitStats
+    pub mixed_additions: u32,
+    pub ai_additions: u32,
+    pub git_diff_deleted_lines: u32,
+    pub git_diff_added_lines: u32,
+}
+
+/// How to break stats down by path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathBreakdown {
+    File,
+    /// Group by the first N directory components
+    Dir(usize),
 }
 
 pub fn stats_command(
     repo: &Repository,
     commit_sha: Option<&str>,
     json: bool,
+    breakdown: Option<PathBreakdown>,
 ) -> Result<(), GitAiError> {
     let (target, refname) = if let Some(sha) = commit_sha {
         // Validate that the commit exists using revparse_single
@@ -48,18 +83,169 @@ pub fn stats_command(
         target, refname
     );
 
-    let stats = stats_for_commit_stats(repo, &target, &refname)?;
+    let mut stats = stats_for_commit_stats(repo, &target, &refname)?;
+    apply_path_breakdown(&mut stats, breakdown);
 
     if json {
         let json_str = serde_json::to_string(&stats)?;
         println!("{}", json_str);
     } else {
         write_stats_to_terminal(&stats);
+        write_agent_stats_to_terminal(&stats);
+        if breakdown.is_some() {
+            write_path_stats_to_terminal(&stats.files);
+        }
     }
 
     Ok(())
 }
 
+/// Sum (name, ai, mixed) rows per name, sorted by AI additions (most first)
+pub fn group_agent_stats<'a>(
+    rows: impl IntoIterator<Item = (&'a str, u32, u32)>,
+) -> Vec<AgentStats> {
+    let mut grouped: BTreeMap<&str, AgentStats> = BTreeMap::new();
+    for (name, ai_additions, mixed_additions) in rows {
+        let entry = grouped.entry(name).or_insert_with(|| AgentStats {
+            name: name.to_string(),
+            ..Default::default()
+        });
+        entry.ai_additions += ai_additions;
+        entry.mixed_additions += mixed_additions;
+    }
+
+    let mut grouped: Vec<AgentStats> = grouped.into_values().collect();
+    grouped.sort_by(|a, b| {
+        b.ai_additions
+            .cmp(&a.ai_additions)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    grouped
+}
+
+/// Print the per-tool and per-model breakdown, if any AI lines were attributed
+pub fn write_agent_stats_to_terminal(stats: &CommitStats) -> String {
+    let mut output = String::new();
+
+    for (label, agents) in [("tool", &stats.by_tool), ("model", &stats.by_model)] {
+        if agents.is_empty() {
+            continue;
+        }
+        let line = format!(
+            "     \x1b[90mby {}: {}\x1b[0m",
+            label,
+            agents
+                .iter()
+                .map(|agent| format!(
+                    "{} {} ai, {} mixed",
+                    agent.name, agent.ai_additions, agent.mixed_additions
+                ))
+                .collect::<Vec<_>>()
+                .join(" | ")
+        );
+        output.push_str(&line);
+        output.push('\n');
+        println!("{}", line);
+    }
+
+    output
+}
+
+/// Replace the per-file stats with the requested breakdown, or drop them if none was requested
+pub fn apply_path_breakdown(stats: &mut CommitStats, breakdown: Option<PathBreakdown>) {
+    stats.files = match breakdown {
+        Some(breakdown) => group_path_stats(&stats.files, breakdown),
+        None => Vec::new(),
+    };
+}
+
+/// Group per-file stats by the requested breakdown, sorted by AI additions (most first)
+pub fn group_path_stats(files: &[PathStats], breakdown: PathBreakdown) -> Vec<PathStats> {
+    let mut grouped: BTreeMap<String, PathStats> = BTreeMap::new();
+    for file in files {
+        let key = match breakdown {
+            PathBreakdown::File => file.path.clone(),
+            PathBreakdown::Dir(depth) => dir_prefix(&file.path, depth),
+        };
+        let entry = grouped.entry(key.clone()).or_insert_with(|| PathStats {
+            path: key,
+            ..Default::default()
+        });
+        entry.human_additions += file.human_additions;
+        entry.mixed_additions += file.mixed_additions;
+        entry.ai_additions += file.ai_additions;
+        entry.git_diff_deleted_lines += file.git_diff_deleted_lines;
+        entry.git_diff_added_lines += file.git_diff_added_lines;
+    }
+
+    let mut grouped: Vec<PathStats> = grouped.into_values().collect();
+    grouped.sort_by(|a, b| {
+        b.ai_additions
+            .cmp(&a.ai_additions)
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    grouped
+}
+
+/// The first `depth` directory components of a file path ("." for top-level files)
+fn dir_prefix(path: &str, depth: usize) -> String {
+    let components: Vec<&str> = path.split('/').collect();
+    let dirs = &components[..components.len() - 1];
+    if dirs.is_empty() || depth == 0 {
+        ".".to_string()
+    } else {
+        dirs[..depth.min(dirs.len())].join("/")
+    }
+}
+
+pub fn write_path_stats_to_terminal(files: &[PathStats]) -> String {
+    let mut output = String::new();
+
+    let path_width = files
+        .iter()
+        .map(|f| f.path.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("path".len());
+
+    let header = format!(
+        "{:<width$}  {:>6}  {:>6}  {:>6}  {:>6}  {:>4}",
+        "path",
+        "ai",
+        "mixed",
+        "human",
+        "added",
+        "ai%",
+        width = path_width
+    );
+    output.push_str(&header);
+    output.push('\n');
+    println!("{}", header);
+
+    for file in files {
+        let ai_percentage = if file.git_diff_added_lines > 0 {
+            ((file.ai_additions as f64 / file.git_diff_added_lines as f64) * 100.0).round() as u32
+        } else {
+            0
+        };
+        let line = format!(
+            "{:<width$}  {:>6}  {:>6}  {:>6}  {:>6}  {:>3}%",
+            file.path,
+            file.ai_additions,
+            file.mixed_additions,
+            file.human_additions.saturating_sub(file.mixed_additions),
+            file.git_diff_added_lines,
+            ai_percentage,
+            width = path_width
+        );
+        output.push_str(&line);
+        output.push('\n');
+        println!("{}", line);
+    }
+
+    output
+}
+
 pub fn write_stats_to_terminal(stats: &CommitStats) -> String {
     let mut output = String::new();
 
@@ -257,16 +443,24 @@ pub fn stats_for_commit_stats(
     repo: &Repository,
     commit_sha: &str,
     _refname: &str,
+) -> Result<CommitStats, GitAiError> {
+    let authorship_log = get_authorship(repo, &commit_sha);
+    stats_for_commit_with_authorship(repo, commit_sha, authorship_log.as_ref())
+}
+
+/// Stats for a commit from the given authorship log, which may not be in its note yet
+pub fn stats_for_commit_with_authorship(
+    repo: &Repository,
+    commit_sha: &str,
+    authorship_log: Option<&AuthorshipLog>,
 ) -> Result<CommitStats, GitAiError> {
     // Step 1: get the diff between this commit and its parent ON refname (if more than one parent)
     // If initial than everything is additions
     // We want the count here git shows +111 -55
-    let (git_diff_added_lines, git_diff_deleted_lines) = get_git_diff_stats(repo, commit_sha)?;
+    let numstat = get_git_diff_stats(repo, commit_sha)?;
+    let (git_diff_added_lines, git_diff_deleted_lines) = sum_numstat(&numstat);
 
-    // Step 2: get the authorship log for this commit
-    let authorship_log = get_authorship(repo, &commit_sha);
-
-    // Step 3: For prompts with > 1 messages, sum all the time between user messages and AI messages.
+    // Step 2: For prompts with > 1 messages, sum all the time between user messages and AI messages.
     // if the last message is a human message, don't count anything
     let (
         authorship_human_additions,
@@ -274,7 +468,7 @@ pub fn stats_for_commit_stats(
         ai_additions,
         ai_accepted,
         time_waiting_for_ai,
-    ) = if let Some(log) = &authorship_log {
+    ) = if let Some(log) = authorship_log {
         analyze_authorship_log(log)?
     } else {
         // No authorship log means no AI-authored lines
@@ -289,6 +483,34 @@ pub fn stats_for_commit_stats(
         authorship_human_additions
     };
 
+    let ai_by_file = authorship_log
+        .map(analyze_authorship_log_by_file)
+        .unwrap_or_default();
+    let files = path_stats_from_numstat(&numstat, &ai_by_file);
+
+    let (by_tool, by_model) = match authorship_log {
+        Some(log) => {
+            let by_prompt = analyze_authorship_log_by_prompt(log);
+            let rows: Vec<(&PromptRecord, u32, u32)> = by_prompt
+                .iter()
+                .filter_map(|(hash, (ai, mixed))| {
+                    Some((log.metadata.prompts.get(hash)?, *ai, *mixed))
+                })
+                .collect();
+            (
+                group_agent_stats(
+                    rows.iter()
+                        .map(|(p, ai, mixed)| (p.agent_id.tool.as_str(), *ai, *mixed)),
+                ),
+                group_agent_stats(
+                    rows.iter()
+                        .map(|(p, ai, mixed)| (p.agent_id.model.as_str(), *ai, *mixed)),
+                ),
+            )
+        }
+        None => (Vec::new(), Vec::new()),
+    };
+
     Ok(CommitStats {
         human_additions,
         mixed_additions,
@@ -297,11 +519,414 @@ pub fn stats_for_commit_stats(
         time_waiting_for_ai,
         git_diff_deleted_lines,
         git_diff_added_lines,
+        files,
+        by_tool,
+        by_model,
+    })
+}
+
+/// Summarize a commit's stats as stored in its authorship note
+pub fn contribution_summary(commit_sha: &str, stats: &CommitStats) -> AiContributionSummary {
+    let total_lines = stats.git_diff_added_lines;
+    let ai_percentage = if total_lines > 0 {
+        ((stats.ai_additions as f64 / total_lines as f64) * 100.0).round() as u32
+    } else {
+        0
+    };
+
+    AiContributionSummary {
+        commit_sha: commit_sha.to_string(),
+        ai_percentage,
+        ai_lines: stats.ai_additions,
+        human_lines: stats.human_additions,
+        mixed_lines: stats.mixed_additions,
+        total_lines,
+    }
+}
+
+/// Compute the commit's contribution summary from its authorship log and store it in the
+/// log's metadata, before the log is written to the commit's note. Returns the commit's stats.
+pub fn add_contribution_summary(
+    repo: &Repository,
+    commit_sha: &str,
+    authorship_log: &mut AuthorshipLog,
+) -> Result<CommitStats, GitAiError> {
+    let stats = stats_for_commit_with_authorship(repo, commit_sha, Some(authorship_log))?;
+    authorship_log.metadata.summary = Some(contribution_summary(commit_sha, &stats));
+    Ok(stats)
+}
+
+/// The summary stored in the commit's note, if there is one and it was computed for this commit
+pub fn stored_contribution_summary(
+    repo: &Repository,
+    commit_sha: &str,
+) -> Option<AiContributionSummary> {
+    let content = show_authorship_note(repo, commit_sha)?;
+    AuthorshipLog::deserialize_from_string(&content)
+        .ok()?
+        .metadata
+        .summary
+        .filter(|summary| summary.commit_sha == commit_sha)
+}
+
+/// Build per-file stats from numstat rows and (ai, mixed) additions per file
+fn path_stats_from_numstat(
+    numstat: &[(String, u32, u32)],
+    ai_by_file: &HashMap<String, (u32, u32)>,
+) -> Vec<PathStats> {
+    numstat
+        .iter()
+        .map(|(path, added, deleted)| {
+            let (ai, mixed) = ai_by_file.get(path).copied().unwrap_or((0, 0));
+            PathStats {
+                path: path.clone(),
+                human_additions: added.saturating_sub(ai),
+                mixed_additions: mixed,
+                ai_additions: ai,
+                git_diff_deleted_lines: *deleted,
+                git_diff_added_lines: *added,
+            }
+        })
+        .collect()
+}
+
+/// AI involvement of a single prompt session within a revision range
+#[derive(Debug, Clone, Serialize)]
+pub struct RangePromptStats {
+    pub id: String,
+    pub tool: String,
+    pub model: String,
+    pub ai_additions: u32,
+    pub mixed_additions: u32,
+}
+
+/// Aggregate authorship statistics for the net diff of a revision range (e.g. a pull request)
+#[derive(Debug, Clone, Serialize)]
+pub struct RangeStats {
+    pub base: String,
+    pub head: String,
+    pub commits: u32,
+    #[serde(flatten)]
+    pub totals: CommitStats,
+    pub prompts: Vec<RangePromptStats>,
+    pub models: Vec<String>,
+}
+
+pub fn stats_range_command(
+    repo: &Repository,
+    range: &str,
+    json: bool,
+    markdown: bool,
+    breakdown: Option<PathBreakdown>,
+) -> Result<(), GitAiError> {
+    let (base_sha, head_sha) = resolve_range(repo, range)?;
+    let mut stats = stats_for_range(repo, &base_sha, &head_sha)?;
+    apply_path_breakdown(&mut stats.totals, breakdown);
+
+    if json {
+        let json_str = serde_json::to_string(&stats)?;
+        println!("{}", json_str);
+    } else if markdown {
+        print!("{}", write_range_stats_markdown(range, &stats));
+    } else {
+        println!(
+            "{} ({} commits, {} prompts)",
+            range,
+            stats.commits,
+            stats.prompts.len()
+        );
+        write_stats_to_terminal(&stats.totals);
+        write_agent_stats_to_terminal(&stats.totals);
+        if breakdown.is_some() {
+            write_path_stats_to_terminal(&stats.totals.files);
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolve `<base>..<head>` (or `<base>...<head>`, which diffs from the merge base) to
+/// commit SHAs. A missing head, or a single revision, means HEAD.
+pub fn resolve_range(repo: &Repository, range: &str) -> Result<(String, String), GitAiError> {
+    let (base, head, symmetric) = if let Some((base, head)) = range.split_once("...") {
+        (base, head, true)
+    } else if let Some((base, head)) = range.split_once("..") {
+        (base, head, false)
+    } else {
+        (range, "", false)
+    };
+    let head = if head.is_empty() { "HEAD" } else { head };
+
+    let resolve = |rev: &str| match repo.revparse_single(rev) {
+        Ok(obj) => Ok(obj.peel_to_commit()?.id()),
+        Err(GitAiError::GitCliError { .. }) => {
+            Err(GitAiError::Generic(format!("No commit found: {}", rev)))
+        }
+        Err(e) => Err(e),
+    };
+
+    let base_sha = resolve(base)?;
+    let head_sha = resolve(head)?;
+
+    if symmetric {
+        let mut args = repo.global_args_for_exec();
+        args.push("merge-base".to_string());
+        args.push(base_sha);
+        args.push(head_sha.clone());
+        let output = exec_git(&args)?;
+        let merge_base = String::from_utf8(output.stdout)?.trim().to_string();
+        return Ok((merge_base, head_sha));
+    }
+
+    Ok((base_sha, head_sha))
+}
+
+/// Compute authorship statistics for the lines added between `base_sha` and `head_sha`.
+///
+/// The range is diffed once; every line that survives in `head_sha` is traced back to the
+/// commit that introduced it with blame and attributed through that commit's authorship
+/// log. Lines from commits without an authorship log count as human.
+pub fn stats_for_range(
+    repo: &Repository,
+    base_sha: &str,
+    head_sha: &str,
+) -> Result<RangeStats, GitAiError> {
+    let numstat = get_git_diff_stats_for_range(repo, base_sha, head_sha)?;
+    let (git_diff_added_lines, git_diff_deleted_lines) = sum_numstat(&numstat);
+    let added_lines = repo.diff_added_lines(base_sha, head_sha, None)?;
+
+    let blame_options = GitAiBlameOptions {
+        newest_commit: Some(head_sha.to_string()),
+        ..Default::default()
+    };
+
+    // Lines attributed to each prompt, per introducing commit (overridden line counts are
+    // recorded per commit) and per file in head
+    let mut prompt_lines: HashMap<(String, String, String), u32> = HashMap::new();
+    let mut authorship_logs: HashMap<String, Option<AuthorshipLog>> = HashMap::new();
+
+    for (file_path, lines) in &added_lines {
+        for range in LineRange::compress_lines(lines) {
+            let (start, end) = match range {
+                LineRange::Single(line) => (line, line),
+                LineRange::Range(start, end) => (start, end),
+            };
+            let hunks = repo.blame_hunks(file_path, start, end, &blame_options)?;
+
+            for hunk in hunks {
+                let authorship_log = authorship_logs
+                    .entry(hunk.commit_sha.clone())
+                    .or_insert_with(|| get_authorship(repo, &hunk.commit_sha));
+                let Some(authorship_log) = authorship_log else {
+                    continue;
+                };
+
+                for offset in 0..=(hunk.range.1 - hunk.range.0) {
+                    let orig_line = hunk.orig_range.0 + offset;
+                    if let Some(hash) =
+                        authorship_log.get_line_prompt_hash(&hunk.orig_file_path, orig_line)
+                    {
+                        *prompt_lines
+                            .entry((hunk.commit_sha.clone(), hash.to_string(), file_path.clone()))
+                            .or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    let mut prompts: BTreeMap<String, RangePromptStats> = BTreeMap::new();
+    let mut ai_by_file: HashMap<String, (u32, u32)> = HashMap::new();
+    let mut counted_transcripts: HashSet<(&str, &str)> = HashSet::new();
+    let mut time_waiting_for_ai = 0u64;
+    for ((commit_sha, hash, file_path), lines) in &prompt_lines {
+        let Some(Some(authorship_log)) = authorship_logs.get(commit_sha) else {
+            continue;
+        };
+        let Some(prompt_record) = authorship_log.metadata.prompts.get(hash) else {
+            continue;
+        };
+
+        let mixed = std::cmp::min(prompt_record.overriden_lines, *lines);
+        let prompt_stats = prompts
+            .entry(hash.clone())
+            .or_insert_with(|| RangePromptStats {
+                id: hash.clone(),
+                tool: prompt_record.agent_id.tool.clone(),
+                model: prompt_record.agent_id.model.clone(),
+                ai_additions: 0,
+                mixed_additions: 0,
+            });
+        prompt_stats.ai_additions += lines - mixed;
+        prompt_stats.mixed_additions += mixed;
+
+        let file_stats = ai_by_file.entry(file_path.clone()).or_insert((0, 0));
+        file_stats.0 += lines - mixed;
+        file_stats.1 += mixed;
+
+        if counted_transcripts.insert((commit_sha, hash)) {
+            let transcript = crate::authorship::transcript::AiTranscript {
+                messages: prompt_record.messages.clone(),
+            };
+            time_waiting_for_ai += calculate_waiting_time(&transcript);
+        }
+    }
+
+    let prompts: Vec<RangePromptStats> = prompts.into_values().collect();
+    let ai_additions: u32 = prompts.iter().map(|p| p.ai_additions).sum();
+    let mixed_additions: u32 = prompts.iter().map(|p| p.mixed_additions).sum();
+    let by_tool = group_agent_stats(
+        prompts
+            .iter()
+            .map(|p| (p.tool.as_str(), p.ai_additions, p.mixed_additions)),
+    );
+    let by_model = group_agent_stats(
+        prompts
+            .iter()
+            .map(|p| (p.model.as_str(), p.ai_additions, p.mixed_additions)),
+    );
+    let models: Vec<String> = prompts
+        .iter()
+        .map(|p| p.model.clone())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    Ok(RangeStats {
+        base: base_sha.to_string(),
+        head: head_sha.to_string(),
+        commits: count_commits_in_range(repo, base_sha, head_sha)?,
+        totals: CommitStats {
+            // Matches stats_for_commit_stats: human additions include mixed lines
+            human_additions: git_diff_added_lines.saturating_sub(ai_additions),
+            mixed_additions,
+            ai_additions,
+            ai_accepted: ai_additions,
+            time_waiting_for_ai,
+            git_diff_deleted_lines,
+            git_diff_added_lines,
+            files: path_stats_from_numstat(&numstat, &ai_by_file),
+            by_tool,
+            by_model,
+        },
+        prompts,
+        models,
     })
 }
 
+fn count_commits_in_range(
+    repo: &Repository,
+    base_sha: &str,
+    head_sha: &str,
+) -> Result<u32, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("rev-list".to_string());
+    args.push("--count".to_string());
+    args.push(format!("{}..{}", base_sha, head_sha));
+
+    let output = exec_git(&args)?;
+    Ok(String::from_utf8(output.stdout)?
+        .trim()
+        .parse()
+        .unwrap_or(0))
+}
+
+/// Render range stats as a Markdown summary suitable for a pull request comment
+pub fn write_range_stats_markdown(range: &str, stats: &RangeStats) -> String {
+    let totals = &stats.totals;
+    let total = totals.git_diff_added_lines;
+    let percentage = |lines: u32| {
+        if total > 0 {
+            ((lines as f64 / total as f64) * 100.0).round() as u32
+        } else {
+            0
+        }
+    };
+    let pure_human = totals
+        .human_additions
+        .saturating_sub(totals.mixed_additions);
+
+    let mut output = String::new();
+    output.push_str(&format!("### AI authorship for `{}`\n\n", range));
+    output.push_str("| Author | Lines | Share |\n");
+    output.push_str("| --- | ---: | ---: |\n");
+    output.push_str(&format!(
+        "| AI | {} | {}% |\n",
+        totals.ai_additions,
+        percentage(totals.ai_additions)
+    ));
+    output.push_str(&format!(
+        "| Mixed | {} | {}% |\n",
+        totals.mixed_additions,
+        percentage(totals.mixed_additions)
+    ));
+    output.push_str(&format!(
+        "| Human | {} | {}% |\n",
+        pure_human,
+        percentage(pure_human)
+    ));
+    output.push_str(&format!("| **Total added** | {} | |\n\n", total));
+
+    output.push_str(&format!(
+        "{} commits, {} lines deleted, {} prompts involved.",
+        stats.commits,
+        totals.git_diff_deleted_lines,
+        stats.prompts.len()
+    ));
+    if !stats.models.is_empty() {
+        output.push_str(&format!(" Models: {}.", stats.models.join(", ")));
+    }
+    output.push('\n');
+
+    if !totals.by_tool.is_empty() {
+        output.push_str("\n| Tool / model | AI lines | Mixed lines |\n");
+        output.push_str("| --- | ---: | ---: |\n");
+        for agent in totals.by_tool.iter().chain(totals.by_model.iter()) {
+            output.push_str(&format!(
+                "| {} | {} | {} |\n",
+                agent.name, agent.ai_additions, agent.mixed_additions
+            ));
+        }
+    }
+
+    if !stats.prompts.is_empty() {
+        output.push_str("\n<details><summary>Prompts</summary>\n\n");
+        output.push_str("| Prompt | Tool | Model | AI lines | Mixed lines |\n");
+        output.push_str("| --- | --- | --- | ---: | ---: |\n");
+        for prompt in &stats.prompts {
+            output.push_str(&format!(
+                "| `{}` | {} | {} | {} | {} |\n",
+                prompt.id, prompt.tool, prompt.model, prompt.ai_additions, prompt.mixed_additions
+            ));
+        }
+        output.push_str("\n</details>\n");
+    }
+
+    if !totals.files.is_empty() {
+        output.push_str("\n<details><summary>By path</summary>\n\n");
+        output.push_str("| Path | AI | Mixed | Human | Added |\n");
+        output.push_str("| --- | ---: | ---: | ---: | ---: |\n");
+        for file in &totals.files {
+            output.push_str(&format!(
+                "| `{}` | {} | {} | {} | {} |\n",
+                file.path,
+                file.ai_additions,
+                file.mixed_additions,
+                file.human_additions.saturating_sub(file.mixed_additions),
+                file.git_diff_added_lines
+            ));
+        }
+        output.push_str("\n</details>\n");
+    }
+
+    output
+}
+
 /// Get git diff statistics between commit and its parent
-fn get_git_diff_stats(repo: &Repository, commit_sha: &str) -> Result<(u32, u32), GitAiError> {
+fn get_git_diff_stats(
+    repo: &Repository,
+    commit_sha: &str,
+) -> Result<Vec<(String, u32, u32)>, GitAiError> {
     // Use git show --numstat to get diff statistics
     let mut args = repo.global_args_for_exec();
     args.push("show".to_string());
@@ -309,11 +934,33 @@ fn get_git_diff_stats(repo: &Repository, commit_sha: &str) -> Result<(u32, u32),
     args.push("--format=".to_string()); // No format, just the numstat
     args.push(commit_sha.to_string());
 
-    let output = crate::git::repository::exec_git(&args)?;
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8(output.stdout)?;
+
+    Ok(parse_numstat(&stdout))
+}
+
+/// Get git diff statistics between two commits
+fn get_git_diff_stats_for_range(
+    repo: &Repository,
+    base_sha: &str,
+    head_sha: &str,
+) -> Result<Vec<(String, u32, u32)>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("diff".to_string());
+    args.push("--numstat".to_string());
+    args.push(base_sha.to_string());
+    args.push(head_sha.to_string());
+
+    let output = exec_git(&args)?;
     let stdout = String::from_utf8(output.stdout)?;
 
-    let mut added_lines = 0u32;
-    let mut deleted_lines = 0u32;
+    Ok(parse_numstat(&stdout))
+}
+
+/// Parse `--numstat` output into (path, added, deleted) rows
+fn parse_numstat(stdout: &str) -> Vec<(String, u32, u32)> {
+    let mut rows = Vec::new();
 
     // Parse numstat output
     for line in stdout.lines() {
@@ -327,23 +974,46 @@ fn get_git_diff_stats(repo: &Repository, commit_sha: &str) -> Result<(u32, u32),
         }
 
         // Parse numstat format: "added\tdeleted\tfilename"
-        let parts: Vec<&str> = line.split('\t').collect();
+        let parts: Vec<&str> = line.splitn(3, '\t').collect();
         if parts.len() >= 2 {
             // Parse added lines
-            if let Ok(added) = parts[0].parse::<u32>() {
-                added_lines += added;
-            }
+            let added = parts[0].parse::<u32>().unwrap_or(0);
 
             // Parse deleted lines (handle "-" for binary files)
-            if parts[1] != "-" {
-                if let Ok(deleted) = parts[1].parse::<u32>() {
-                    deleted_lines += deleted;
-                }
-            }
+            let deleted = if parts[1] != "-" {
+                parts[1].parse::<u32>().unwrap_or(0)
+            } else {
+                0
+            };
+
+            let path = parts.get(2).map(|p| numstat_path(p)).unwrap_or_default();
+            rows.push((path, added, deleted));
         }
     }
 
-    Ok((added_lines, deleted_lines))
+    rows
+}
+
+/// Total (added, deleted) lines across numstat rows
+fn sum_numstat(rows: &[(String, u32, u32)]) -> (u32, u32) {
+    rows.iter().fold((0, 0), |(added, deleted), (_, a, d)| {
+        (added + a, deleted + d)
+    })
+}
+
+/// Resolve the destination path of a numstat entry, which git prints as
+/// `old => new` or `dir/{old => new}/file` for renames
+fn numstat_path(path: &str) -> String {
+    if let (Some(open), Some(close)) = (path.find('{'), path.find('}'))
+        && let Some((_, new)) = path[open + 1..close].split_once(" => ")
+    {
+        let joined = format!("{}{}{}", &path[..open], new, &path[close + 1..]);
+        return joined.replace("//", "/");
+    }
+    match path.split_once(" => ") {
+        Some((_, new)) => new.to_string(),
+        None => path.to_string(),
+    }
 }
 
 /// Analyze authorship log to extract statistics
@@ -411,6 +1081,57 @@ fn analyze_authorship_log(
     ))
 }
 
+/// (ai, mixed) additions per prompt hash, counted the same way as `analyze_authorship_log`
+fn analyze_authorship_log_by_prompt(authorship_log: &AuthorshipLog) -> HashMap<String, (u32, u32)> {
+    let mut by_prompt: HashMap<String, (u32, u32)> = HashMap::new();
+
+    for file_attestation in &authorship_log.attestations {
+        for entry in &file_attestation.entries {
+            let Some(prompt_record) = authorship_log.metadata.prompts.get(&entry.hash) else {
+                continue;
+            };
+            let lines_in_entry: u32 = entry.line_ranges.iter().map(line_range_len).sum();
+            let overriden_lines = std::cmp::min(prompt_record.overriden_lines, lines_in_entry);
+            let prompt_stats = by_prompt.entry(entry.hash.clone()).or_insert((0, 0));
+            prompt_stats.0 += lines_in_entry - overriden_lines;
+            prompt_stats.1 += overriden_lines;
+        }
+    }
+
+    by_prompt
+}
+
+fn line_range_len(range: &LineRange) -> u32 {
+    match range {
+        LineRange::Single(_) => 1,
+        LineRange::Range(start, end) => end - start + 1,
+    }
+}
+
+/// (ai, mixed) additions per file, counted the same way as `analyze_authorship_log`
+fn analyze_authorship_log_by_file(authorship_log: &AuthorshipLog) -> HashMap<String, (u32, u32)> {
+    let mut by_file = HashMap::new();
+
+    for file_attestation in &authorship_log.attestations {
+        let (mut ai_additions, mut mixed_additions) = (0u32, 0u32);
+        for entry in &file_attestation.entries {
+            let Some(prompt_record) = authorship_log.metadata.prompts.get(&entry.hash) else {
+                continue;
+            };
+            let lines_in_entry: u32 = entry.line_ranges.iter().map(line_range_len).sum();
+            let overriden_lines = std::cmp::min(prompt_record.overriden_lines, lines_in_entry);
+            mixed_additions += overriden_lines;
+            ai_additions += lines_in_entry - overriden_lines;
+        }
+        by_file.insert(
+            file_attestation.file_path.clone(),
+            (ai_additions, mixed_additions),
+        );
+    }
+
+    by_file
+}
+
 /// Calculate time waiting for AI from transcript messages
 fn calculate_waiting_time(transcript: &crate::authorship::transcript::AiTranscript) -> u64 {
     let mut total_waiting_time = 0u64;
@@ -478,6 +1199,9 @@ mod tests {
             time_waiting_for_ai: 72009, // 1 minute 30 seconds
             git_diff_deleted_lines: 15,
             git_diff_added_lines: 80,
+            files: Vec::new(),
+            by_tool: Vec::new(),
+            by_model: Vec::new(),
         };
 
         let mixed_output = write_stats_to_terminal(&stats);
@@ -492,6 +1216,9 @@ mod tests {
             time_waiting_for_ai: 45,
             git_diff_deleted_lines: 0,
             git_diff_added_lines: 100,
+            files: Vec::new(),
+            by_tool: Vec::new(),
+            by_model: Vec::new(),
         };
 
         let ai_only_output = write_stats_to_terminal(&ai_stats);
@@ -506,6 +1233,9 @@ mod tests {
             time_waiting_for_ai: 0,
             git_diff_deleted_lines: 10,
             git_diff_added_lines: 75,
+            files: Vec::new(),
+            by_tool: Vec::new(),
+            by_model: Vec::new(),
         };
 
         let human_only_output = write_stats_to_terminal(&human_stats);
@@ -520,6 +1250,9 @@ mod tests {
             time_waiting_for_ai: 30,
             git_diff_deleted_lines: 0,
             git_diff_added_lines: 102,
+            files: Vec::new(),
+            by_tool: Vec::new(),
+            by_model: Vec::new(),
         };
 
         let minimal_human_output = write_stats_to_terminal(&minimal_human_stats);
@@ -534,6 +1267,9 @@ mod tests {
             time_waiting_for_ai: 0,
             git_diff_deleted_lines: 25,
             git_diff_added_lines: 0,
+            files: Vec::new(),
+            by_tool: Vec::new(),
+            by_model: Vec::new(),
         };
 
         let deletion_only_output = write_stats_to_terminal(&deletion_only_stats);
@@ -666,4 +1402,284 @@ mod tests {
             "Git diff shows 0 deleted lines"
         );
     }
+
+    #[test]
+    fn test_stats_for_range() {
+        let tmp_repo = TmpRepo::new().unwrap();
+
+        let mut file = tmp_repo
+            .write_file("test.txt", "Base line\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let base_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        // AI adds lines in one commit
+        file.append("AI line 1\nAI line 2\nAI line 3\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("AI adds lines").unwrap();
+
+        // Human adds lines and removes one of the AI lines in a later commit
+        file.replace_range(3, 4, "Human line 1\n").unwrap();
+        file.append("Human line 2\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Human edits").unwrap();
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let stats = stats_for_range(tmp_repo.gitai_repo(), &base_sha, &head_sha).unwrap();
+
+        assert_eq!(stats.commits, 2);
+        assert_eq!(stats.totals.git_diff_added_lines, 4);
+        assert_eq!(
+            stats.totals.ai_additions, 2,
+            "Only AI lines that survive to head are counted"
+        );
+        assert_eq!(stats.totals.human_additions, 2);
+        assert_eq!(stats.prompts.len(), 1);
+        assert_eq!(stats.prompts[0].tool, "cursor");
+        assert_eq!(stats.prompts[0].ai_additions, 2);
+        assert_eq!(stats.models, vec!["claude-3-sonnet".to_string()]);
+        assert_eq!(stats.totals.files.len(), 1);
+        assert_eq!(stats.totals.files[0].path, "test.txt");
+        assert_eq!(stats.totals.files[0].ai_additions, 2);
+        assert_eq!(stats.totals.files[0].human_additions, 2);
+        assert_eq!(
+            stats.totals.by_tool,
+            vec![AgentStats {
+                name: "cursor".to_string(),
+                ai_additions: 2,
+                mixed_additions: 0,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_resolve_range() {
+        let tmp_repo = TmpRepo::new().unwrap();
+
+        tmp_repo.write_file("test.txt", "Line1\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let base_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        tmp_repo.write_file("other.txt", "Line1\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Second commit").unwrap();
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        let range = format!("{}..", base_sha);
+        assert_eq!(
+            resolve_range(repo, &range).unwrap(),
+            (base_sha.clone(), head_sha.clone())
+        );
+        assert_eq!(
+            resolve_range(repo, "HEAD~1...HEAD").unwrap(),
+            (base_sha.clone(), head_sha.clone())
+        );
+        assert!(resolve_range(repo, "does-not-exist..HEAD").is_err());
+    }
+
+    #[test]
+    fn test_range_stats_markdown() {
+        let stats = RangeStats {
+            base: "base".to_string(),
+            head: "head".to_string(),
+            commits: 3,
+            totals: CommitStats {
+                human_additions: 6,
+                mixed_additions: 2,
+                ai_additions: 4,
+                ai_accepted: 4,
+                time_waiting_for_ai: 0,
+                git_diff_deleted_lines: 1,
+                git_diff_added_lines: 10,
+                files: Vec::new(),
+                by_tool: vec![AgentStats {
+                    name: "cursor".to_string(),
+                    ai_additions: 4,
+                    mixed_additions: 2,
+                }],
+                by_model: vec![AgentStats {
+                    name: "gpt-4o".to_string(),
+                    ai_additions: 4,
+                    mixed_additions: 2,
+                }],
+            },
+            prompts: vec![RangePromptStats {
+                id: "abc1234".to_string(),
+                tool: "cursor".to_string(),
+                model: "gpt-4o".to_string(),
+                ai_additions: 4,
+                mixed_additions: 2,
+            }],
+            models: vec!["gpt-4o".to_string()],
+        };
+
+        let markdown = write_range_stats_markdown("main..feature", &stats);
+
+        assert!(markdown.starts_with("### AI authorship for `main..feature`"));
+        assert!(markdown.contains("| AI | 4 | 40% |"));
+        assert!(markdown.contains("| Mixed | 2 | 20% |"));
+        assert!(markdown.contains("| Human | 4 | 40% |"));
+        assert!(
+            markdown.contains("3 commits, 1 lines deleted, 1 prompts involved. Models: gpt-4o.")
+        );
+        assert!(markdown.contains("| `abc1234` | cursor | gpt-4o | 4 | 2 |"));
+        assert!(markdown.contains("| cursor | 4 | 2 |"));
+        assert!(markdown.contains("| gpt-4o | 4 | 2 |"));
+    }
+
+    #[test]
+    fn test_stats_by_file_and_dir() {
+        let tmp_repo = TmpRepo::new().unwrap();
+
+        tmp_repo.write_file("README.md", "Readme\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+
+        tmp_repo
+            .write_file("src/ai/gen.rs", "AI 1\nAI 2\nAI 3\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo
+            .write_file("src/main.rs", "Human 1\nHuman 2\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Mixed commit").unwrap();
+
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+        let stats = stats_for_commit_stats(tmp_repo.gitai_repo(), &head_sha, "HEAD").unwrap();
+
+        let by_file = group_path_stats(&stats.files, PathBreakdown::File);
+        assert_eq!(by_file.len(), 2);
+        assert_eq!(by_file[0].path, "src/ai/gen.rs");
+        assert_eq!(by_file[0].ai_additions, 3);
+        assert_eq!(by_file[0].human_additions, 0);
+        assert_eq!(by_file[1].path, "src/main.rs");
+        assert_eq!(by_file[1].ai_additions, 0);
+        assert_eq!(by_file[1].human_additions, 2);
+
+        let by_dir = group_path_stats(&stats.files, PathBreakdown::Dir(1));
+        assert_eq!(by_dir.len(), 1);
+        assert_eq!(by_dir[0].path, "src");
+        assert_eq!(by_dir[0].ai_additions, 3);
+        assert_eq!(by_dir[0].git_diff_added_lines, 5);
+
+        let mut stats = stats;
+        apply_path_breakdown(&mut stats, None);
+        let json = serde_json::to_string(&stats).unwrap();
+        assert!(
+            !json.contains("\"files\""),
+            "files are only serialized on request"
+        );
+    }
+
+    #[test]
+    fn test_dir_prefix_and_numstat_paths() {
+        assert_eq!(dir_prefix("README.md", 2), ".");
+        assert_eq!(dir_prefix("src/main.rs", 2), "src");
+        assert_eq!(dir_prefix("src/a/b/c.rs", 2), "src/a");
+        assert_eq!(dir_prefix("src/a/b/c.rs", 0), ".");
+
+        assert_eq!(numstat_path("src/lib.rs"), "src/lib.rs");
+        assert_eq!(numstat_path("old.rs => new.rs"), "new.rs");
+        assert_eq!(numstat_path("src/{old => new}/lib.rs"), "src/new/lib.rs");
+        assert_eq!(numstat_path("src/{ => nested}/lib.rs"), "src/nested/lib.rs");
+        assert_eq!(numstat_path("src/{nested => }/lib.rs"), "src/lib.rs");
+    }
+
+    #[test]
+    fn test_stats_by_tool_and_model() {
+        let tmp_repo = TmpRepo::new().unwrap();
+
+        let mut file = tmp_repo.write_file("test.txt", "Base\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+
+        file.append("Cursor 1\nCursor 2\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("ai_session_1", Some("gpt-4o"), Some("cursor"))
+            .unwrap();
+        file.append("Claude 1\nClaude 2\nClaude 3\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("ai_session_2", Some("claude-sonnet"), Some("claude"))
+            .unwrap();
+        file.append("Cursor 3\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("ai_session_3", Some("claude-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("Two assistants").unwrap();
+
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+        let stats = stats_for_commit_stats(tmp_repo.gitai_repo(), &head_sha, "HEAD").unwrap();
+
+        let by_tool: Vec<(&str, u32)> = stats
+            .by_tool
+            .iter()
+            .map(|a| (a.name.as_str(), a.ai_additions))
+            .collect();
+        assert_eq!(by_tool, vec![("claude", 3), ("cursor", 3)]);
+
+        let by_model: Vec<(&str, u32)> = stats
+            .by_model
+            .iter()
+            .map(|a| (a.name.as_str(), a.ai_additions))
+            .collect();
+        assert_eq!(by_model, vec![("claude-sonnet", 4), ("gpt-4o", 2)]);
+    }
+
+    #[test]
+    fn test_post_commit_records_contribution_summary() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let mut file = tmp_repo.write_file("test.txt", "Line1\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+
+        file.append("Line 2\nLine 3\nLine 4\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        let committed_log = tmp_repo.commit_with_message("AI adds lines").unwrap();
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+        let repo = tmp_repo.gitai_repo();
+
+        // The note is still a plain authorship log, with the summary in its metadata
+        let note = show_authorship_note(repo, &head_sha).unwrap();
+        let log = AuthorshipLog::deserialize_from_string(&note).unwrap();
+        let expected = AiContributionSummary {
+            commit_sha: head_sha.clone(),
+            ai_percentage: 100,
+            ai_lines: 3,
+            human_lines: 0,
+            mixed_lines: 0,
+            total_lines: 3,
+        };
+        assert_eq!(log.metadata.summary, Some(expected.clone()));
+        assert_eq!(committed_log.metadata.summary, Some(expected.clone()));
+        assert_eq!(stored_contribution_summary(repo, &head_sha), Some(expected));
+
+        // A summary copied from another commit is ignored
+        assert_eq!(stored_contribution_summary(repo, "0000000"), None);
+    }
 }
@@ -1,5 +1,6 @@
 use crate::authorship::transcript::AiTranscript;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fmt;
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -157,6 +158,19 @@ impl Checkpoint {
             allow_reset_to_checkpoint: false,
         }
     }
+
+    /// Move entries recorded under an old path to the new path (old path -> new path).
+    /// Returns true if any entry was renamed.
+    pub fn rename_files(&mut self, renames: &HashMap<String, String>) -> bool {
+        let mut renamed = false;
+        for entry in &mut self.entries {
+            if let Some(new_path) = renames.get(&entry.file) {
+                entry.file = new_path.clone();
+                renamed = true;
+            }
+        }
+        renamed
+    }
 }
 
 #[cfg(test)]
@@ -37,6 +37,9 @@ pub struct BlameHunk {
     pub committer_tz: String,
     /// Whether this is a boundary commit
     pub is_boundary: bool,
+    /// Path of the file in the commit that introduced this hunk (differs from the
+    /// blamed path when the file was renamed since)
+    pub orig_file_path: String,
 }
 
 #[derive(Debug, Clone)]
@@ -220,7 +223,7 @@ impl Repository {
         }
 
         // Step 2: Overlay AI authorship information
-        let line_authors = overlay_ai_authorship(self, &all_blame_hunks, &relative_file_path)?;
+        let line_authors = overlay_ai_authorship(self, &all_blame_hunks)?;
 
         // Output based on format
         if options.porcelain || options.line_porcelain {
@@ -309,6 +312,7 @@ impl Repository {
             committer_time: i64,
             committer_tz: String,
             boundary: bool,
+            filename: String,
         }
 
         let mut hunks: Vec<BlameHunk> = Vec::new();
@@ -378,6 +382,10 @@ impl Repository {
                 cur_meta.boundary = true;
                 continue;
             }
+            if let Some(rest) = line.strip_prefix("filename ") {
+                cur_meta.filename = rest.to_string();
+                continue;
+            }
 
             // Header line: either 4 fields (new hunk) or 3 fields (continuation)
             let mut parts = line.split_whitespace();
@@ -436,6 +444,11 @@ impl Repository {
                         committer_time: cur_meta.committer_time,
                         committer_tz: cur_meta.committer_tz.clone(),
                         is_boundary: cur_meta.boundary,
+                        orig_file_path: if cur_meta.filename.is_empty() {
+                            file_path.to_string()
+                        } else {
+                            cur_meta.filename.clone()
+                        },
                     });
                 }
 
@@ -504,6 +517,11 @@ impl Repository {
                 committer_time: cur_meta.committer_time,
                 committer_tz: cur_meta.committer_tz.clone(),
                 is_boundary: cur_meta.boundary,
+                orig_file_path: if cur_meta.filename.is_empty() {
+                    file_path.to_string()
+                } else {
+                    cur_meta.filename.clone()
+                },
             });
         }
 
@@ -514,7 +532,6 @@ impl Repository {
 fn overlay_ai_authorship(
     repo: &Repository,
     blame_hunks: &[BlameHunk],
-    file_path: &str,
 ) -> Result<HashMap<u32, String>, GitAiError> {
     let mut line_authors: HashMap<u32, String> = HashMap::new();
 
@@ -544,8 +561,9 @@ fn overlay_ai_authorship(
                 let current_line_num = hunk.range.0 + i;
                 let orig_line_num = hunk.orig_range.0 + i;
 
+                // Look up by the path the file had in that commit so renames are followed
                 if let Some((author, prompt)) =
-                    authorship_log.get_line_attribution(file_path, orig_line_num)
+                    authorship_log.get_line_attribution(&hunk.orig_file_path, orig_line_num)
                 {
                     // If this line is AI-assisted, display the tool name; otherwise the human username
                     if let Some(prompt_record) = prompt {
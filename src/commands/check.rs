@@ -0,0 +1,313 @@
+use crate::authorship::stats::{resolve_range, stats_for_commit_stats};
+use crate::error::GitAiError;
+use crate::git::refs::show_authorship_note;
+use crate::git::repository::{Repository, exec_git};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Rules a revision range must satisfy, read from a JSON policy file. Every rule is
+/// optional; an empty policy always passes.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    /// Highest share of added lines (0-100) that may be AI-generated across the range
+    pub max_ai_percentage: Option<u32>,
+    /// Commit message marker (e.g. "Reviewed-by:") required on commits that add files
+    /// whose added lines are all AI-generated
+    pub ai_only_files_require_marker: Option<String>,
+    /// Models whose lines may not be committed (matched case-insensitively)
+    #[serde(default)]
+    pub forbidden_models: Vec<String>,
+    /// Globs (`*`, `?`, `**`) of repository paths that must be written entirely by humans
+    #[serde(default)]
+    pub human_only_paths: Vec<String>,
+    /// Fail when a non-merge commit has no authorship note
+    #[serde(default)]
+    pub require_authorship_notes: bool,
+}
+
+impl Policy {
+    pub fn from_file(path: &str) -> Result<Self, GitAiError> {
+        let content = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Violation {
+    /// Name of the policy field that failed
+    pub rule: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckReport {
+    pub range: String,
+    pub commits: usize,
+    pub ai_percentage: u32,
+    pub passed: bool,
+    pub violations: Vec<Violation>,
+}
+
+/// Check every non-merge commit in `range` against `policy`
+pub fn run(repo: &Repository, range: &str, policy: &Policy) -> Result<CheckReport, GitAiError> {
+    let (base_sha, head_sha) = resolve_range(repo, range)?;
+    let commits = repo.rev_list(&format!("{}..{}", base_sha, head_sha))?;
+    let human_only_paths = policy
+        .human_only_paths
+        .iter()
+        .map(|glob| glob_to_regex(glob))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut violations = Vec::new();
+    let mut ai_additions = 0;
+    let mut total_additions = 0;
+    let mut checked = 0;
+
+    // Oldest first, so violations read in commit order
+    for commit_sha in commits.iter().rev() {
+        if repo.find_commit(commit_sha.clone())?.parent_count()? > 1 {
+            continue;
+        }
+        checked += 1;
+
+        if policy.require_authorship_notes && show_authorship_note(repo, commit_sha).is_none() {
+            violations.push(Violation {
+                rule: "require_authorship_notes".to_string(),
+                commit: Some(commit_sha.clone()),
+                path: None,
+                message: "commit has no authorship note".to_string(),
+            });
+        }
+
+        let stats = stats_for_commit_stats(repo, commit_sha, "HEAD")?;
+        ai_additions += stats.ai_additions;
+        total_additions += stats.git_diff_added_lines;
+
+        for model in &stats.by_model {
+            let lines = model.ai_additions + model.mixed_additions;
+            if lines > 0
+                && policy
+                    .forbidden_models
+                    .iter()
+                    .any(|forbidden| forbidden.eq_ignore_ascii_case(&model.name))
+            {
+                violations.push(Violation {
+                    rule: "forbidden_models".to_string(),
+                    commit: Some(commit_sha.clone()),
+                    path: None,
+                    message: format!(
+                        "{} line(s) generated by forbidden model {}",
+                        lines, model.name
+                    ),
+                });
+            }
+        }
+
+        let mut ai_only_files = Vec::new();
+        for file in &stats.files {
+            let ai_lines = file.ai_additions + file.mixed_additions;
+            if ai_lines > 0
+                && human_only_paths
+                    .iter()
+                    .any(|glob| glob.is_match(&file.path))
+            {
+                violations.push(Violation {
+                    rule: "human_only_paths".to_string(),
+                    commit: Some(commit_sha.clone()),
+                    path: Some(file.path.clone()),
+                    message: format!("{} AI-generated line(s) in a human-only path", ai_lines),
+                });
+            }
+            if file.git_diff_added_lines > 0 && file.ai_additions == file.git_diff_added_lines {
+                ai_only_files.push(file.path.clone());
+            }
+        }
+
+        if let Some(marker) = &policy.ai_only_files_require_marker
+            && !ai_only_files.is_empty()
+            && !commit_message(repo, commit_sha)?
+                .lines()
+                .any(|line| line.trim_start().starts_with(marker.as_str()))
+        {
+            for path in ai_only_files {
+                violations.push(Violation {
+                    rule: "ai_only_files_require_marker".to_string(),
+                    commit: Some(commit_sha.clone()),
+                    path: Some(path),
+                    message: format!(
+                        "file is entirely AI-generated and the commit message has no '{}' line",
+                        marker
+                    ),
+                });
+            }
+        }
+    }
+
+    let ai_percentage = if total_additions > 0 {
+        ((ai_additions as f64 / total_additions as f64) * 100.0).round() as u32
+    } else {
+        0
+    };
+    if let Some(max) = policy.max_ai_percentage
+        && ai_percentage > max
+    {
+        violations.push(Violation {
+            rule: "max_ai_percentage".to_string(),
+            commit: None,
+            path: None,
+            message: format!(
+                "{}% of added lines are AI-generated ({} of {}), above the {}% limit",
+                ai_percentage, ai_additions, total_additions, max
+            ),
+        });
+    }
+
+    Ok(CheckReport {
+        range: range.to_string(),
+        commits: checked,
+        ai_percentage,
+        passed: violations.is_empty(),
+        violations,
+    })
+}
+
+fn commit_message(repo: &Repository, commit_sha: &str) -> Result<String, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("log".to_string());
+    args.push("-1".to_string());
+    args.push("--format=%B".to_string());
+    args.push(commit_sha.to_string());
+    let output = exec_git(&args)?;
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+/// Compile a path glob: `**` matches across directories, `*` and `?` within one
+fn glob_to_regex(glob: &str) -> Result<Regex, GitAiError> {
+    let mut pattern = String::from("^");
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    pattern.push_str("(?:.*/)?");
+                } else {
+                    pattern.push_str(".*");
+                }
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            c => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+
+    Regex::new(&pattern)
+        .map_err(|e| GitAiError::Generic(format!("Invalid path glob '{}': {}", glob, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_glob_to_regex() {
+        let glob = glob_to_regex("crypto/**").unwrap();
+        assert!(glob.is_match("crypto/aes.rs"));
+        assert!(glob.is_match("crypto/impl/aes.rs"));
+        assert!(!glob.is_match("src/crypto/aes.rs"));
+
+        let glob = glob_to_regex("**/*.pem").unwrap();
+        assert!(glob.is_match("key.pem"));
+        assert!(glob.is_match("certs/dev/key.pem"));
+        assert!(!glob.is_match("key.pem.bak"));
+
+        let glob = glob_to_regex("src/?.rs").unwrap();
+        assert!(glob.is_match("src/a.rs"));
+        assert!(!glob.is_match("src/ab.rs"));
+        assert!(!glob.is_match("src/a/b.rs"));
+    }
+
+    #[test]
+    fn test_check_reports_policy_violations() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("README.md", "hello\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let base_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        tmp_repo
+            .write_file("crypto/aes.rs", "fn encrypt() {}\nfn decrypt() {}\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("gpt-3.5-turbo"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("Add crypto").unwrap();
+        let ai_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let policy: Policy = serde_json::from_str(
+            r#"{
+                "max_ai_percentage": 50,
+                "ai_only_files_require_marker": "Reviewed-by:",
+                "forbidden_models": ["GPT-3.5-turbo"],
+                "human_only_paths": ["crypto/**"],
+                "require_authorship_notes": true
+            }"#,
+        )
+        .unwrap();
+
+        let report = run(
+            tmp_repo.gitai_repo(),
+            &format!("{}..HEAD", base_sha),
+            &policy,
+        )
+        .unwrap();
+        assert!(!report.passed);
+        assert_eq!(report.commits, 1);
+        assert_eq!(report.ai_percentage, 100);
+
+        let rules: Vec<(&str, Option<&str>)> = report
+            .violations
+            .iter()
+            .map(|v| (v.rule.as_str(), v.path.as_deref()))
+            .collect();
+        assert_eq!(
+            rules,
+            vec![
+                ("forbidden_models", None),
+                ("human_only_paths", Some("crypto/aes.rs")),
+                ("ai_only_files_require_marker", Some("crypto/aes.rs")),
+                ("max_ai_percentage", None),
+            ]
+        );
+        assert!(
+            report
+                .violations
+                .iter()
+                .all(|v| v.rule == "max_ai_percentage" || v.commit.as_deref() == Some(&ai_sha))
+        );
+
+        // An empty policy passes
+        let report = run(
+            tmp_repo.gitai_repo(),
+            &format!("{}..HEAD", base_sha),
+            &Policy::default(),
+        )
+        .unwrap();
+        assert!(report.passed);
+    }
+
+    #[test]
+    fn test_policy_rejects_unknown_fields() {
+        assert!(serde_json::from_str::<Policy>(r#"{"max_ai_percent": 50}"#).is_err());
+    }
+}
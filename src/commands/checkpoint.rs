@@ -1,5 +1,7 @@
-use crate::authorship::working_log::{Checkpoint, Line, WorkingLogEntry};
-use crate::commands::checkpoint_agent::agent_preset::AgentRunResult;
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::working_log::{AgentId, Checkpoint, Line, WorkingLogEntry};
+use crate::commands::checkpoint_agent::agent_preset::{AgentRunResult, EditedFile};
+use crate::config::Config;
 use crate::error::GitAiError;
 use crate::git::repo_storage::{PersistedWorkingLog, RepoStorage};
 use crate::git::repository::Repository;
@@ -8,6 +10,7 @@ use crate::utils::debug_log;
 use sha2::{Digest, Sha256};
 use similar::{ChangeTag, TextDiff};
 use std::collections::HashMap;
+use std::path::Path;
 
 pub fn run(
     repo: &Repository,
@@ -38,7 +41,7 @@ pub fn run(
     let repo_storage = RepoStorage::for_repo_path(repo.path());
     let working_log = repo_storage.working_log_for_base_commit(&base_commit);
 
-    let files = get_all_tracked_files(repo, &base_commit, &working_log)?;
+    let (files, renamed_files) = get_all_tracked_files(repo, &base_commit, &working_log)?;
     let mut checkpoints = if reset {
         // If reset flag is set, start with an empty working log
         working_log.reset_working_log()?;
@@ -115,21 +118,79 @@ pub fn run(
     // If this is not the first checkpoint, diff against the last saved state
     let entries = if checkpoints.is_empty() || reset {
         // First checkpoint or reset - diff against base commit
-        get_initial_checkpoint_entries(repo, &files, &base_commit, &file_content_hashes)?
+        get_initial_checkpoint_entries(
+            repo,
+            &files,
+            &base_commit,
+            &file_content_hashes,
+            &renamed_files,
+        )?
     } else {
         // Subsequent checkpoint - diff against last saved state
         get_subsequent_checkpoint_entries(
+            repo,
             &working_log,
             &files,
             &file_content_hashes,
-            checkpoints.last(),
+            &checkpoints,
+            &renamed_files,
         )?
     };
 
+    // An agent that reports the files it edited is only attributed those. The rest of the
+    // changes since the last checkpoint are the human's and get checkpointed first.
+    let (human_entries, checkpoint_entries) = match agent_run_result
+        .as_ref()
+        .and_then(|agent_run| agent_run.edited_files.as_ref())
+    {
+        Some(edited_files) if !entries.is_empty() => {
+            split_entries_by_edited_files(&entries, edited_files, &repo.workdir()?)
+        }
+        _ => (Vec::new(), entries.clone()),
+    };
+
+    // AI lines a human brought back keep the agent that wrote them: files restored to an
+    // earlier version (undo/redo, IDE checkpoints) get that version's attribution, and
+    // blocks of AI lines moved or copied elsewhere keep their agent. Only for human
+    // checkpoints: the human part of an agent run is numbered without the agent's lines, so
+    // it can't be matched against the file content.
+    let (checkpoint_entries, moved) =
+        if agent_run_result.is_none() && !checkpoint_entries.is_empty() {
+            let versions = file_version_index(&checkpoints);
+            let restored: HashMap<String, HashMap<u32, AgentId>> = checkpoint_entries
+                .iter()
+                .filter_map(|entry| {
+                    let count = versions.get(&(entry.file.clone(), entry.blob_sha.clone()))?;
+                    Some((
+                        entry.file.clone(),
+                        ai_lines_after(&checkpoints[..*count], &entry.file),
+                    ))
+                })
+                .collect();
+            let ai_fingerprints = ai_line_fingerprints(&working_log, &checkpoints);
+            split_recovered_ai_lines(
+                checkpoint_entries,
+                &restored,
+                &ai_fingerprints,
+                &working_log,
+            )
+        } else {
+            (checkpoint_entries, Vec::new())
+        };
+
+    if !human_entries.is_empty() {
+        let checkpoint = Checkpoint::new(combined_hash.clone(), author.to_string(), human_entries);
+        working_log.append_checkpoint(&checkpoint)?;
+        checkpoints.push(checkpoint);
+    }
+
     // Skip adding checkpoint if there are no changes
-    if !entries.is_empty() {
-        let mut checkpoint =
-            Checkpoint::new(combined_hash.clone(), author.to_string(), entries.clone());
+    if !checkpoint_entries.is_empty() {
+        let mut checkpoint = Checkpoint::new(
+            combined_hash.clone(),
+            author.to_string(),
+            checkpoint_entries,
+        );
 
         // Set transcript and agent_id if provided
         if let Some(agent_run) = &agent_run_result {
@@ -141,6 +202,11 @@ pub fn run(
         working_log.append_checkpoint(&checkpoint)?;
         checkpoints.push(checkpoint);
     }
+    for (agent_id, moved_entries) in moved {
+        let checkpoint = moved_lines_checkpoint(&combined_hash, author, agent_id, moved_entries);
+        working_log.append_checkpoint(&checkpoint)?;
+        checkpoints.push(checkpoint);
+    }
 
     let agent_tool = if let Some(agent_run_result) = &agent_run_result {
         Some(agent_run_result.agent_id.tool.as_str())
@@ -201,8 +267,10 @@ pub fn run(
     Ok((entries.len(), files.len(), checkpoints.len()))
 }
 
-fn get_all_files(repo: &Repository) -> Result<Vec<String>, GitAiError> {
+/// Returns the changed text files, plus a map of new path -> original path for staged renames
+fn get_all_files(repo: &Repository) -> Result<(Vec<String>, HashMap<String, String>), GitAiError> {
     let mut files = Vec::new();
+    let mut renamed_files = HashMap::new();
 
     // Use porcelain v2 format to get status
     let statuses = repo.status(None)?;
@@ -238,18 +306,40 @@ fn get_all_files(repo: &Repository) -> Result<Vec<String>, GitAiError> {
                 files.push(entry.path.clone());
             }
         }
+
+        if let (EntryKind::Rename, Some(orig_path)) = (&entry.kind, &entry.orig_path) {
+            renamed_files.insert(entry.path.clone(), orig_path.clone());
+        }
     }
 
-    Ok(files)
+    Ok((files, renamed_files))
 }
 
-/// Get all files that should be tracked, including those from previous checkpoints
+/// Get all files that should be tracked, including those from previous checkpoints.
+///
+/// Staged renames (e.g. `git mv`) are followed: working log entries recorded under the
+/// original path are moved to the new path so attribution carries over.
 fn get_all_tracked_files(
     repo: &Repository,
     _base_commit: &str,
     working_log: &PersistedWorkingLog,
-) -> Result<Vec<String>, GitAiError> {
-    let mut files = get_all_files(repo)?;
+) -> Result<(Vec<String>, HashMap<String, String>), GitAiError> {
+    let (mut files, renamed_files) = get_all_files(repo)?;
+
+    if !renamed_files.is_empty() {
+        let renames: HashMap<String, String> = renamed_files
+            .iter()
+            .map(|(new_path, orig_path)| (orig_path.clone(), new_path.clone()))
+            .collect();
+        let mut checkpoints = working_log.read_all_checkpoints()?;
+        let mut any_renamed = false;
+        for checkpoint in &mut checkpoints {
+            any_renamed |= checkpoint.rename_files(&renames);
+        }
+        if any_renamed {
+            working_log.write_all_checkpoints(&checkpoints)?;
+        }
+    }
 
     // Also include files that were in previous checkpoints but might not show up in git status
     // This ensures we track deletions when files return to their original state
@@ -266,7 +356,7 @@ fn get_all_tracked_files(
         }
     }
 
-    Ok(files)
+    Ok((files, renamed_files))
 }
 
 fn save_current_file_states(
@@ -300,6 +390,7 @@ fn get_initial_checkpoint_entries(
     files: &[String],
     _base_commit: &str,
     file_content_hashes: &HashMap<String, String>,
+    renamed_files: &HashMap<String, String>,
 ) -> Result<Vec<WorkingLogEntry>, GitAiError> {
     let mut entries = Vec::new();
 
@@ -315,9 +406,11 @@ fn get_initial_checkpoint_entries(
         let repo_workdir = repo.workdir().unwrap();
         let abs_path = repo_workdir.join(file_path);
 
-        // Previous content from HEAD tree if present, otherwise empty
+        // Previous content from HEAD tree if present (under the original path for renamed
+        // files), otherwise empty
+        let head_path = renamed_files.get(file_path).unwrap_or(file_path);
         let previous_content = if let Some(tree) = &head_tree {
-            match tree.get_path(std::path::Path::new(file_path)) {
+            match tree.get_path(std::path::Path::new(head_path)) {
                 Ok(entry) => {
                     if let Ok(blob) = repo.find_blob(entry.id()) {
                         let blob_content = blob.content()?;
@@ -407,24 +500,33 @@ fn get_initial_checkpoint_entries(
 }
 
 fn get_subsequent_checkpoint_entries(
+    repo: &Repository,
     working_log: &PersistedWorkingLog,
     files: &[String],
     file_content_hashes: &HashMap<String, String>,
-    previous_checkpoint: Option<&Checkpoint>,
+    previous_checkpoints: &[Checkpoint],
+    renamed_files: &HashMap<String, String>,
 ) -> Result<Vec<WorkingLogEntry>, GitAiError> {
     let mut entries = Vec::new();
 
-    // Build a map of file path -> blob_sha from the previous checkpoint's entries
-    let previous_file_hashes: HashMap<String, String> =
-        if let Some(prev_checkpoint) = previous_checkpoint {
-            prev_checkpoint
-                .entries
-                .iter()
-                .map(|entry| (entry.file.clone(), entry.blob_sha.clone()))
-                .collect()
-        } else {
-            HashMap::new()
-        };
+    // Build a map of file path -> blob_sha of the most recent checkpoint that touched each file.
+    // A checkpoint only carries entries for files that changed, so looking at the last one alone
+    // would treat every other file as new.
+    let mut previous_file_hashes: HashMap<String, String> = HashMap::new();
+    for checkpoint in previous_checkpoints {
+        for entry in &checkpoint.entries {
+            if !entry.blob_sha.is_empty() {
+                previous_file_hashes.insert(entry.file.clone(), entry.blob_sha.clone());
+            }
+        }
+    }
+
+    let head_tree = repo
+        .head()
+        .ok()
+        .and_then(|h| h.target().ok())
+        .and_then(|oid| repo.find_commit(oid).ok())
+        .and_then(|c| c.tree().ok());
 
     for file_path in files {
         let abs_path = working_log.repo_root.join(file_path);
@@ -438,6 +540,16 @@ fn get_subsequent_checkpoint_entries(
             working_log
                 .get_file_version(prev_content_hash)
                 .unwrap_or_default()
+        } else if let Some(tree) = &head_tree {
+            // Not checkpointed yet, diff against the base commit like the initial checkpoint does
+            let head_path = renamed_files.get(file_path).unwrap_or(file_path);
+            match tree.get_path(std::path::Path::new(head_path)) {
+                Ok(entry) => match repo.find_blob(entry.id()) {
+                    Ok(blob) => String::from_utf8_lossy(&blob.content()?).to_string(),
+                    Err(_) => String::new(),
+                },
+                Err(_) => String::new(),
+            }
         } else {
             String::new() // No previous version, treat as empty
         };
@@ -454,6 +566,17 @@ fn get_subsequent_checkpoint_entries(
             format!("{}\n", current_content)
         };
 
+        // A formatter run shouldn't take lines away from their author: compare lines by
+        // their formatting-insensitive content, so reformatted lines count as unchanged
+        let (prev_norm, curr_norm) = if Config::get().get_formatting_tolerant_attribution() {
+            (
+                normalize_formatting(&prev_norm),
+                normalize_formatting(&curr_norm),
+            )
+        } else {
+            (prev_norm, curr_norm)
+        };
+
         let diff = TextDiff::from_lines(&prev_norm, &curr_norm);
         let mut added_line_numbers = Vec::new();
         let mut deleted_line_numbers = Vec::new();
@@ -513,6 +636,360 @@ fn get_subsequent_checkpoint_entries(
     Ok(entries)
 }
 
+/// Normalize each line for comparison, keeping one output line per input line: whitespace
+/// is dropped, quotes are unified and trailing commas (before a closing bracket or at the end
+/// of the line) are removed
+fn normalize_formatting(content: &str) -> String {
+    let mut normalized = String::with_capacity(content.len());
+    for line in content.lines() {
+        let mut chars: Vec<char> = line
+            .chars()
+            .filter(|c| !c.is_whitespace())
+            .map(|c| if c == '\'' || c == '`' { '"' } else { c })
+            .collect();
+        let mut i = 0;
+        while i < chars.len() {
+            if chars[i] == ','
+                && chars
+                    .get(i + 1)
+                    .is_none_or(|next| matches!(next, ')' | ']' | '}'))
+            {
+                chars.remove(i);
+            } else {
+                i += 1;
+            }
+        }
+        normalized.extend(chars);
+        normalized.push('\n');
+    }
+    normalized
+}
+
+/// Split checkpoint entries into the human's and the agent's changes, given the files (and
+/// line ranges) the agent reports having edited. The human checkpoint is applied first, so
+/// its line numbers are rewritten as if the agent's lines weren't there yet.
+fn split_entries_by_edited_files(
+    entries: &[WorkingLogEntry],
+    edited_files: &[EditedFile],
+    workdir: &Path,
+) -> (Vec<WorkingLogEntry>, Vec<WorkingLogEntry>) {
+    let mut human_entries = Vec::new();
+    let mut agent_entries = Vec::new();
+
+    for entry in entries {
+        let edited = edited_files.iter().find(|edited| {
+            let path = Path::new(&edited.path);
+            let relative = path.strip_prefix(workdir).unwrap_or(path);
+            relative.to_string_lossy().replace('\\', "/") == entry.file
+        });
+        let Some(edited) = edited else {
+            human_entries.push(entry.clone());
+            continue;
+        };
+        if edited.line_ranges.is_empty() || entry.added_lines.is_empty() {
+            agent_entries.push(entry.clone());
+            continue;
+        }
+
+        let agent_added: Vec<u32> = expand_lines(&entry.added_lines)
+            .into_iter()
+            .filter(|line| {
+                edited
+                    .line_ranges
+                    .iter()
+                    .any(|(start, end)| (*start..=*end).contains(line))
+            })
+            .collect();
+        let (human_entry, agent_entry) = split_added_lines(entry, &agent_added);
+        human_entries.extend(human_entry);
+        agent_entries.extend(agent_entry);
+    }
+
+    (human_entries, agent_entries)
+}
+
+/// Split the added `lines` out of `entry` into an entry applied after it. The first entry
+/// keeps the other changes, numbered as if `lines` weren't there yet. An entry that only
+/// adds `lines` moves to the second position whole.
+fn split_added_lines(
+    entry: &WorkingLogEntry,
+    lines: &[u32],
+) -> (Option<WorkingLogEntry>, Option<WorkingLogEntry>) {
+    let (split, kept): (Vec<u32>, Vec<u32>) = expand_lines(&entry.added_lines)
+        .into_iter()
+        .partition(|line| lines.contains(line));
+
+    if split.is_empty() {
+        return (Some(entry.clone()), None);
+    }
+    if kept.is_empty() {
+        return (None, Some(entry.clone()));
+    }
+
+    let without_split_lines =
+        |line: u32| line - split.iter().filter(|added| **added < line).count() as u32;
+    let kept_entry = WorkingLogEntry::new(
+        entry.file.clone(),
+        entry.blob_sha.clone(),
+        consolidate_lines(kept.into_iter().map(without_split_lines).collect()),
+        consolidate_lines(
+            expand_lines(&entry.deleted_lines)
+                .into_iter()
+                .map(without_split_lines)
+                .collect(),
+        ),
+    );
+    let split_entry = WorkingLogEntry::new(
+        entry.file.clone(),
+        entry.blob_sha.clone(),
+        consolidate_lines(split),
+        Vec::new(),
+    );
+    (Some(kept_entry), Some(split_entry))
+}
+
+/// Letters and digits a block of lines needs before it counts as moved or copied, the
+/// default of `git blame -M`/`-C`
+const MOVE_THRESHOLD: usize = 20;
+
+/// Fingerprints of the lines added by AI checkpoints in this working log, with the agent
+/// that added them (the latest agent wins). Fingerprints ignore formatting, so re-indented
+/// blocks still match.
+fn ai_line_fingerprints(
+    working_log: &PersistedWorkingLog,
+    checkpoints: &[Checkpoint],
+) -> HashMap<String, AgentId> {
+    let mut fingerprints = HashMap::new();
+    for checkpoint in checkpoints {
+        let Some(agent_id) = &checkpoint.agent_id else {
+            continue;
+        };
+        for entry in &checkpoint.entries {
+            let Ok(content) = working_log.get_file_version(&entry.blob_sha) else {
+                continue;
+            };
+            let lines: Vec<&str> = content.lines().collect();
+            for line in expand_lines(&entry.added_lines) {
+                if let Some(fingerprint) = lines
+                    .get(line as usize - 1)
+                    .and_then(|text| line_fingerprint(text))
+                {
+                    fingerprints.insert(fingerprint, agent_id.clone());
+                }
+            }
+        }
+    }
+    fingerprints
+}
+
+fn line_fingerprint(line: &str) -> Option<String> {
+    let normalized = normalize_formatting(line);
+    let normalized = normalized.trim_end_matches('\n');
+    (!normalized.is_empty()).then(|| normalized.to_string())
+}
+
+/// Content-hash index of the file versions persisted in this working log: (file, blob sha)
+/// -> number of checkpoints after which the file last had that content. Every checkpoint
+/// entry records the blob it was diffed into, so the index is rebuilt from the checkpoints.
+fn file_version_index(checkpoints: &[Checkpoint]) -> HashMap<(String, String), usize> {
+    let mut index = HashMap::new();
+    for (i, checkpoint) in checkpoints.iter().enumerate() {
+        for entry in &checkpoint.entries {
+            index.insert((entry.file.clone(), entry.blob_sha.clone()), i + 1);
+        }
+    }
+    index
+}
+
+/// The agent that wrote each line of `file` once `checkpoints` are applied
+fn ai_lines_after(checkpoints: &[Checkpoint], file: &str) -> HashMap<u32, AgentId> {
+    let log =
+        AuthorshipLog::from_working_log_with_base_commit_and_human_author(checkpoints, "", None);
+    let mut ai_lines = HashMap::new();
+    for attestation in log.attestations.iter().filter(|a| a.file_path == file) {
+        for entry in &attestation.entries {
+            if let Some(prompt) = log.metadata.prompts.get(&entry.hash) {
+                for line in entry.lines() {
+                    ai_lines.insert(line, prompt.agent_id.clone());
+                }
+            }
+        }
+    }
+    ai_lines
+}
+
+/// Split AI lines a human brought back out of `entries`, so they keep the attribution of the
+/// agent that wrote them:
+/// - files back to a version the working log already has (undo/redo, an IDE restoring a
+///   local checkpoint) get that version's attribution exactly, from `restored`
+/// - elsewhere, blocks of added lines that match lines an AI checkpoint added (moved or
+///   copied code)
+///
+/// Returns the remaining entries, then the split lines grouped by agent, in the order they
+/// must be applied.
+fn split_recovered_ai_lines(
+    entries: Vec<WorkingLogEntry>,
+    restored: &HashMap<String, HashMap<u32, AgentId>>,
+    fingerprints: &HashMap<String, AgentId>,
+    working_log: &PersistedWorkingLog,
+) -> (Vec<WorkingLogEntry>, Vec<(AgentId, Vec<WorkingLogEntry>)>) {
+    if restored.is_empty() && fingerprints.is_empty() {
+        return (entries, Vec::new());
+    }
+
+    let mut agents: Vec<AgentId> = Vec::new();
+    let mut remaining = Vec::new();
+    let mut moved: Vec<Vec<WorkingLogEntry>> = Vec::new();
+
+    for entry in entries {
+        if let Some(ai_lines) = restored.get(&entry.file) {
+            let mut restored_lines: HashMap<u32, usize> = HashMap::new();
+            for line in expand_lines(&entry.added_lines) {
+                if let Some(agent) = ai_lines.get(&line) {
+                    restored_lines.insert(line, agent_index(&mut agents, agent));
+                }
+            }
+            split_entry_by_agent(
+                entry,
+                &restored_lines,
+                agents.len(),
+                &mut remaining,
+                &mut moved,
+            );
+            continue;
+        }
+
+        let content = working_log
+            .get_file_version(&entry.blob_sha)
+            .unwrap_or_default();
+        let lines: Vec<&str> = content.lines().collect();
+
+        // Moved line -> index into `agents`
+        let mut moved_lines: HashMap<u32, usize> = HashMap::new();
+        let mut block: Vec<u32> = Vec::new();
+        let mut block_agent: Option<&AgentId> = None;
+        let mut block_size = 0;
+        let mut close_block = |block: &mut Vec<u32>, agent: Option<&AgentId>, size: &mut usize| {
+            if let Some(agent) = agent
+                && *size >= MOVE_THRESHOLD
+            {
+                let index = agent_index(&mut agents, agent);
+                moved_lines.extend(block.iter().map(|line| (*line, index)));
+            }
+            block.clear();
+            *size = 0;
+        };
+
+        let mut previous_line = 0;
+        for line in expand_lines(&entry.added_lines) {
+            if line != previous_line + 1 {
+                close_block(&mut block, block_agent, &mut block_size);
+                block_agent = None;
+            }
+            previous_line = line;
+
+            let text = lines.get(line as usize - 1).copied().unwrap_or_default();
+            // Blank lines neither break a block nor get attributed
+            let Some(fingerprint) = line_fingerprint(text) else {
+                continue;
+            };
+            let agent = fingerprints.get(&fingerprint);
+            if agent != block_agent {
+                close_block(&mut block, block_agent, &mut block_size);
+                block_agent = agent;
+            }
+            if agent.is_some() {
+                block.push(line);
+                block_size += fingerprint.chars().filter(|c| c.is_alphanumeric()).count();
+            }
+        }
+        close_block(&mut block, block_agent, &mut block_size);
+        split_entry_by_agent(
+            entry,
+            &moved_lines,
+            agents.len(),
+            &mut remaining,
+            &mut moved,
+        );
+    }
+
+    let moved = agents
+        .into_iter()
+        .zip(moved.into_iter().chain(std::iter::repeat(Vec::new())))
+        .filter(|(_, entries)| !entries.is_empty())
+        .collect();
+    (remaining, moved)
+}
+
+fn agent_index(agents: &mut Vec<AgentId>, agent: &AgentId) -> usize {
+    agents.iter().position(|a| a == agent).unwrap_or_else(|| {
+        agents.push(agent.clone());
+        agents.len() - 1
+    })
+}
+
+/// Split `entry`'s lines by agent (`agent_lines` maps a line to an index into the agents),
+/// pushing what is left to `remaining` and each agent's part to `moved[index]`
+fn split_entry_by_agent(
+    entry: WorkingLogEntry,
+    agent_lines: &HashMap<u32, usize>,
+    agent_count: usize,
+    remaining: &mut Vec<WorkingLogEntry>,
+    moved: &mut Vec<Vec<WorkingLogEntry>>,
+) {
+    // Later agents' checkpoints apply last, so split them off first
+    let mut rest = Some(entry);
+    let mut parts = Vec::new();
+    for index in (0..agent_count).rev() {
+        let Some(entry) = &rest else {
+            break;
+        };
+        let later_lines: Vec<u32> = agent_lines
+            .iter()
+            .filter(|(_, agent)| **agent > index)
+            .map(|(line, _)| *line)
+            .collect();
+        let lines: Vec<u32> = agent_lines
+            .iter()
+            .filter(|(_, agent)| **agent == index)
+            .map(|(line, _)| line - later_lines.iter().filter(|l| **l < *line).count() as u32)
+            .collect();
+        let (kept, part) = split_added_lines(entry, &lines);
+        rest = kept;
+        if let Some(part) = part {
+            parts.push((index, part));
+        }
+    }
+    remaining.extend(rest);
+    for (index, part) in parts {
+        if moved.len() <= index {
+            moved.resize(index + 1, Vec::new());
+        }
+        moved[index].push(part);
+    }
+}
+
+/// A checkpoint attributing moved, copied or restored lines to the agent that originally wrote them.
+/// The transcript is left empty; the agent's prompt record already has it.
+fn moved_lines_checkpoint(
+    diff: &str,
+    author: &str,
+    agent_id: AgentId,
+    entries: Vec<WorkingLogEntry>,
+) -> Checkpoint {
+    let mut checkpoint = Checkpoint::new(diff.to_string(), author.to_string(), entries);
+    checkpoint.transcript = Some(Default::default());
+    checkpoint.agent_id = Some(agent_id);
+    checkpoint
+}
+
+fn expand_lines(lines: &[Line]) -> Vec<u32> {
+    lines
+        .iter()
+        .flat_map(|line| line.start()..=line.end())
+        .collect()
+}
+
 /// Consolidate consecutive line numbers into ranges for efficiency
 fn consolidate_lines(mut lines: Vec<u32>) -> Vec<Line> {
     if lines.is_empty() {
@@ -810,6 +1287,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_subsequent_checkpoint_diffs_each_file_against_its_latest_version() {
+        let (tmp_repo, mut lines_file, mut alphabet_file) =
+            TmpRepo::new_with_base_commit().unwrap();
+
+        lines_file.append("Human line 1\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+
+        // alphabet.md isn't in the working log yet, so it's diffed against HEAD, not an
+        // empty file
+        alphabet_file.append("AI line\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        let ai_line = std::fs::read_to_string(alphabet_file.path())
+            .unwrap()
+            .lines()
+            .count() as u32;
+
+        // The last checkpoint doesn't touch alphabet.md, so its latest version comes from an
+        // earlier one
+        lines_file.append("Human line 2\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        alphabet_file.append("Human line\n").unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+
+        let authorship_log = tmp_repo.commit_with_message("Mixed edits").unwrap();
+        assert!(
+            authorship_log
+                .get_line_attribution("alphabet.md", 1)
+                .is_none()
+        );
+        assert!(
+            authorship_log
+                .get_line_attribution("alphabet.md", ai_line)
+                .is_some()
+        );
+        assert!(
+            authorship_log
+                .get_line_attribution("alphabet.md", ai_line + 1)
+                .is_none()
+        );
+    }
+
     #[test]
     fn test_checkpoint_works_after_conflict_resolution_maintains_authorship() {
         // Create a repo with an initial commit
@@ -895,6 +1422,325 @@ mod tests {
             "Should create 1 entry for new changes after conflict resolution"
         );
     }
+
+    #[test]
+    fn test_split_entries_by_edited_files() {
+        let entries = vec![
+            WorkingLogEntry::new(
+                "src/agent.rs".to_string(),
+                "sha1".to_string(),
+                vec![Line::Range(1, 3)],
+                vec![Line::Single(5)],
+            ),
+            WorkingLogEntry::new(
+                "src/mixed.rs".to_string(),
+                "sha2".to_string(),
+                vec![Line::Range(2, 3), Line::Range(6, 8)],
+                vec![Line::Single(10)],
+            ),
+            WorkingLogEntry::new(
+                "src/human.rs".to_string(),
+                "sha3".to_string(),
+                vec![Line::Single(1)],
+                vec![],
+            ),
+        ];
+        let edited_files = vec![
+            EditedFile {
+                path: "/repo/src/agent.rs".to_string(),
+                line_ranges: vec![],
+            },
+            EditedFile {
+                path: "src/mixed.rs".to_string(),
+                line_ranges: vec![(2, 3)],
+            },
+        ];
+
+        let (human, agent) =
+            split_entries_by_edited_files(&entries, &edited_files, Path::new("/repo"));
+
+        assert_eq!(agent.len(), 2);
+        assert_eq!(agent[0].file, "src/agent.rs");
+        assert_eq!(agent[0].added_lines, vec![Line::Range(1, 3)]);
+        assert_eq!(agent[0].deleted_lines, vec![Line::Single(5)]);
+        assert_eq!(agent[1].file, "src/mixed.rs");
+        assert_eq!(agent[1].added_lines, vec![Line::Range(2, 3)]);
+        assert!(agent[1].deleted_lines.is_empty());
+
+        // The human's lines are numbered as if the agent's two lines weren't there yet
+        assert_eq!(human.len(), 2);
+        assert_eq!(human[0].file, "src/mixed.rs");
+        assert_eq!(human[0].added_lines, vec![Line::Range(4, 6)]);
+        assert_eq!(human[0].deleted_lines, vec![Line::Single(8)]);
+        assert_eq!(human[1].file, "src/human.rs");
+    }
+
+    #[test]
+    fn test_checkpoint_with_edited_files_only_attributes_reported_lines() {
+        let (tmp_repo, mut lines_file, mut alphabet_file) =
+            TmpRepo::new_with_base_commit().unwrap();
+        let base_len = lines_file.contents().lines().count() as u32;
+
+        lines_file
+            .append("human line\nagent line 1\nagent line 2\n")
+            .unwrap();
+        alphabet_file.append("human edit elsewhere\n").unwrap();
+
+        let agent_run_result = AgentRunResult {
+            agent_id: crate::authorship::working_log::AgentId {
+                tool: "in-house-agent".to_string(),
+                id: "session-1".to_string(),
+                model: "unknown".to_string(),
+            },
+            is_human: false,
+            transcript: None,
+            repo_working_dir: None,
+            edited_files: Some(vec![EditedFile {
+                path: "lines.md".to_string(),
+                line_ranges: vec![(base_len + 2, base_len + 3)],
+            }]),
+        };
+        run(
+            tmp_repo.gitai_repo(),
+            "test_user",
+            false,
+            false,
+            true,
+            Some(agent_run_result),
+        )
+        .unwrap();
+
+        let authorship_log = tmp_repo.commit_with_message("agent commit").unwrap();
+
+        assert!(
+            authorship_log
+                .get_line_prompt_hash("lines.md", base_len + 1)
+                .is_none()
+        );
+        assert!(
+            authorship_log
+                .get_line_prompt_hash("lines.md", base_len + 2)
+                .is_some()
+        );
+        assert!(
+            authorship_log
+                .get_line_prompt_hash("lines.md", base_len + 3)
+                .is_some()
+        );
+        assert!(
+            authorship_log
+                .attestations
+                .iter()
+                .all(|file| file.file_path != "alphabet.md")
+        );
+    }
+
+    #[test]
+    fn test_moved_ai_block_keeps_attribution() {
+        let (tmp_repo, _, _) = TmpRepo::new_with_base_commit().unwrap();
+
+        tmp_repo
+            .write_file(
+                "util.rs",
+                "fn parse_config(path: &str) -> Config {\n    let text = read(path);\n    Config::from(text)\n}\n",
+                true,
+            )
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+
+        // The human moves the function into a module (re-indented) and closes the module
+        // with a brace of their own, which is too short to count as moved
+        tmp_repo.write_file("util.rs", "", true).unwrap();
+        tmp_repo
+            .write_file(
+                "config.rs",
+                "mod config {\n    fn parse_config(path: &str) -> Config {\n        let text = read(path);\n        Config::from(text)\n    }\n    // more parsers to come\n}\n",
+                true,
+            )
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+
+        let authorship_log = tmp_repo.commit_with_message("Move parse_config").unwrap();
+        for line in 2..=5 {
+            assert!(
+                authorship_log
+                    .get_line_prompt_hash("config.rs", line)
+                    .is_some(),
+                "line {} should keep its AI attribution",
+                line
+            );
+        }
+        for line in [1, 6, 7] {
+            assert!(
+                authorship_log
+                    .get_line_prompt_hash("config.rs", line)
+                    .is_none()
+            );
+        }
+    }
+
+    #[test]
+    fn test_undo_redo_restores_ai_attribution() {
+        let (tmp_repo, _, _) = TmpRepo::new_with_base_commit().unwrap();
+
+        tmp_repo
+            .write_file("short.rs", "fn a() {}\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+
+        // Lines too short to count as moved, so only the restored version can bring them back
+        let ai_content = "fn a() {}\nlet x = 1;\n}\n";
+        tmp_repo.write_file("short.rs", ai_content, true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+
+        // Undo, then redo
+        tmp_repo
+            .write_file("short.rs", "fn a() {}\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.write_file("short.rs", ai_content, true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+
+        let authorship_log = tmp_repo.commit_with_message("Redo").unwrap();
+        assert!(authorship_log.get_line_prompt_hash("short.rs", 1).is_none());
+        for line in [2, 3] {
+            assert!(
+                authorship_log
+                    .get_line_prompt_hash("short.rs", line)
+                    .is_some(),
+                "line {} should get its AI attribution back",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_file_version_index_keeps_latest_checkpoint() {
+        let entry = |sha: &str| {
+            WorkingLogEntry::new(
+                "file.rs".to_string(),
+                sha.to_string(),
+                vec![Line::Single(1)],
+                vec![],
+            )
+        };
+        let checkpoints = vec![
+            Checkpoint::new("d1".to_string(), "a".to_string(), vec![entry("v1")]),
+            Checkpoint::new("d2".to_string(), "a".to_string(), vec![entry("v2")]),
+            Checkpoint::new("d3".to_string(), "a".to_string(), vec![entry("v1")]),
+        ];
+
+        let index = file_version_index(&checkpoints);
+        assert_eq!(index[&("file.rs".to_string(), "v1".to_string())], 3);
+        assert_eq!(index[&("file.rs".to_string(), "v2".to_string())], 2);
+        assert!(!index.contains_key(&("other.rs".to_string(), "v1".to_string())));
+    }
+
+    #[test]
+    fn test_split_recovered_ai_lines_orders_agents() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let working_log = tmp_repo
+            .gitai_repo()
+            .storage
+            .working_log_for_base_commit("initial");
+        let agent = |id: &str| AgentId {
+            tool: "cursor".to_string(),
+            id: id.to_string(),
+            model: "gpt-4".to_string(),
+        };
+
+        let content = "human line one\nlet first = agent_a();\nfirst.finish_a();\nhuman line two\nlet second = agent_b();\nsecond.finish_b();\n";
+        let blob_sha = working_log.persist_file_version(content).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        let mut fingerprints = HashMap::new();
+        for (line, id) in [(1, "a"), (2, "a"), (4, "b"), (5, "b")] {
+            fingerprints.insert(line_fingerprint(lines[line]).unwrap(), agent(id));
+        }
+
+        let entry = WorkingLogEntry::new(
+            "file.rs".to_string(),
+            blob_sha,
+            vec![Line::Range(1, 6)],
+            vec![],
+        );
+        let (remaining, moved) =
+            split_recovered_ai_lines(vec![entry], &HashMap::new(), &fingerprints, &working_log);
+
+        // Applied in order: the human's lines, then agent a's, then agent b's
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].added_lines, vec![Line::Range(1, 2)]);
+        assert_eq!(moved.len(), 2);
+        assert_eq!(moved[0].0, agent("a"));
+        assert_eq!(moved[0].1[0].added_lines, vec![Line::Range(2, 3)]);
+        assert_eq!(moved[1].0, agent("b"));
+        assert_eq!(moved[1].1[0].added_lines, vec![Line::Range(5, 6)]);
+    }
+
+    #[test]
+    fn test_normalize_formatting() {
+        assert_eq!(
+            normalize_formatting("const a = { 'x': 1, y: [2, 3,], };\n\n  foo( bar )\n"),
+            "consta={\"x\":1,y:[2,3]};\n\nfoo(bar)\n"
+        );
+        assert_eq!(normalize_formatting("a,\nb, c"), "a\nb,c\n");
+    }
+
+    #[test]
+    fn test_reformatting_keeps_ai_attribution() {
+        let (tmp_repo, _, _) = TmpRepo::new_with_base_commit().unwrap();
+
+        tmp_repo
+            .write_file(
+                "format.js",
+                "const config = {'name': 'demo', 'retries': 3};\ncall( config )\n",
+                true,
+            )
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+
+        // A formatter rewrites both lines, and the human adds one
+        tmp_repo
+            .write_file(
+                "format.js",
+                "const config = { \"name\": \"demo\", \"retries\": 3, };\ncall(config)\nlog(config)\n",
+                true,
+            )
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+
+        let authorship_log = tmp_repo.commit_with_message("Reformat").unwrap();
+        assert!(
+            authorship_log
+                .get_line_prompt_hash("format.js", 1)
+                .is_some()
+        );
+        assert!(
+            authorship_log
+                .get_line_prompt_hash("format.js", 2)
+                .is_some()
+        );
+        assert!(
+            authorship_log
+                .get_line_prompt_hash("format.js", 3)
+                .is_none()
+        );
+    }
 }
 
 fn is_text_file(repo: &Repository, path: &str) -> bool {
@@ -4,9 +4,11 @@ use crate::{
         working_log::AgentId,
     },
     error::GitAiError,
+    git::repository::find_repository_in_path,
 };
 use chrono::{TimeZone, Utc};
 use rusqlite::{Connection, OpenFlags};
+use serde::Deserialize;
 use std::env;
 use std::path::{Path, PathBuf};
 
@@ -20,6 +22,17 @@ pub struct AgentRunResult {
     pub is_human: bool,
     pub transcript: Option<AiTranscript>,
     pub repo_working_dir: Option<String>,
+    /// Files the agent reports having edited. When set, only these files (and lines) are
+    /// attributed to the agent and other changes are checkpointed as human.
+    pub edited_files: Option<Vec<EditedFile>>,
+}
+
+/// A file edited by an agent, relative to the repository root
+#[derive(Debug, Clone, PartialEq)]
+pub struct EditedFile {
+    pub path: String,
+    /// Inclusive 1-based line ranges in the current file. Empty means the whole file.
+    pub line_ranges: Vec<(u32, u32)>,
 }
 
 pub trait AgentCheckpointPreset {
@@ -85,6 +98,7 @@ impl AgentCheckpointPreset for ClaudePreset {
             transcript: Some(transcript),
             // use default.
             repo_working_dir: None,
+            edited_files: None,
         })
     }
 }
@@ -152,6 +166,7 @@ impl AgentCheckpointPreset for CursorPreset {
                 is_human: true,
                 transcript: None,
                 repo_working_dir: Some(repo_working_dir),
+                edited_files: None,
             });
         }
 
@@ -199,6 +214,7 @@ impl AgentCheckpointPreset for CursorPreset {
             is_human: false,
             transcript: Some(transcript),
             repo_working_dir: Some(repo_working_dir),
+            edited_files: None,
         })
     }
 }
@@ -545,6 +561,7 @@ impl AgentCheckpointPreset for GithubCopilotPreset {
             is_human: false,
             transcript: Some(transcript),
             repo_working_dir: Some(repo_working_dir),
+            edited_files: None,
         })
     }
 }
@@ -748,3 +765,926 @@ impl GithubCopilotPreset {
         Ok((transcript, detected_model))
     }
 }
+
+// Aider to checkpoint preset
+pub struct AiderPreset;
+
+impl AgentCheckpointPreset for AiderPreset {
+    fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
+        // hook_input is optional: Aider runs commands from the repo root, but a cwd can be
+        // passed explicitly when the hook runs from elsewhere
+        let cwd = match flags.hook_input {
+            Some(hook_input_json) => {
+                let hook_data: serde_json::Value =
+                    serde_json::from_str(&hook_input_json).map_err(|e| {
+                        GitAiError::PresetError(format!("Invalid JSON in hook_input: {}", e))
+                    })?;
+                hook_data
+                    .get("cwd")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string())
+            }
+            None => None,
+        };
+        let cwd = match cwd {
+            Some(cwd) => cwd,
+            None => env::current_dir()?.to_string_lossy().to_string(),
+        };
+
+        // Aider writes its history files to the root of the git repo
+        let repo = crate::git::find_repository_in_path(&cwd)?;
+        let repo_root = repo.workdir()?;
+
+        let chat_history_path = repo_root.join(".aider.chat.history.md");
+        let chat_history = std::fs::read_to_string(&chat_history_path).map_err(|e| {
+            GitAiError::PresetError(format!(
+                "Could not read Aider chat history at {:?}: {}",
+                chat_history_path, e
+            ))
+        })?;
+        let input_history = std::fs::read_to_string(repo_root.join(".aider.input.history")).ok();
+
+        let (transcript, model) = AiderPreset::transcript_and_model_from_aider_history(
+            &chat_history,
+            input_history.as_deref(),
+        );
+
+        // Each `aider` run starts a new session in the chat history, identified by its start time
+        let session_id = flags
+            .prompt_id
+            .or_else(|| AiderPreset::session_id_from_aider_chat_history(&chat_history))
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let agent_id = AgentId {
+            tool: "aider".to_string(),
+            id: session_id,
+            model: model.unwrap_or_else(|| "unknown".to_string()),
+        };
+
+        Ok(AgentRunResult {
+            agent_id,
+            is_human: false,
+            transcript: Some(transcript),
+            repo_working_dir: Some(repo_root.to_string_lossy().to_string()),
+            edited_files: None,
+        })
+    }
+}
+
+impl AiderPreset {
+    const SESSION_HEADER: &'static str = "# aider chat started at ";
+
+    /// The start time of the latest session in `.aider.chat.history.md`, used as the session id
+    pub fn session_id_from_aider_chat_history(chat_history: &str) -> Option<String> {
+        chat_history
+            .lines()
+            .rev()
+            .find_map(|line| line.strip_prefix(Self::SESSION_HEADER))
+            .map(|started_at| started_at.trim().to_string())
+    }
+
+    /// Translate the latest session of `.aider.chat.history.md` into an AiTranscript and
+    /// optional model. User turns are `#### ` lines, Aider's own output is quoted with `> `
+    /// and everything else is the assistant's reply. When `.aider.input.history` is given,
+    /// user messages are timestamped from it.
+    pub fn transcript_and_model_from_aider_history(
+        chat_history: &str,
+        input_history: Option<&str>,
+    ) -> (AiTranscript, Option<String>) {
+        // Only the latest session belongs to the running Aider process
+        let session = match chat_history.rfind(Self::SESSION_HEADER) {
+            Some(start) => &chat_history[start..],
+            None => chat_history,
+        };
+
+        let mut transcript = AiTranscript::new();
+        let mut model: Option<String> = None;
+        let mut user_text: Vec<&str> = Vec::new();
+        let mut assistant_text: Vec<&str> = Vec::new();
+
+        fn flush(
+            transcript: &mut AiTranscript,
+            user_text: &mut Vec<&str>,
+            assistant_text: &mut Vec<&str>,
+        ) {
+            let user = user_text.join("\n");
+            if !user.trim().is_empty() {
+                transcript.add_message(Message::user(user.trim().to_string(), None));
+            }
+            user_text.clear();
+
+            let assistant = assistant_text.join("\n");
+            if !assistant.trim().is_empty() {
+                transcript.add_message(Message::assistant(assistant.trim().to_string(), None));
+            }
+            assistant_text.clear();
+        }
+
+        for line in session.lines() {
+            if line.starts_with(Self::SESSION_HEADER) {
+                continue;
+            }
+
+            if let Some(text) = line.strip_prefix("####") {
+                // Consecutive `####` lines are a single multi-line user message
+                if !assistant_text.is_empty() {
+                    flush(&mut transcript, &mut user_text, &mut assistant_text);
+                }
+                // Aider ends each line with two spaces (a markdown line break)
+                user_text.push(text.strip_prefix(' ').unwrap_or(text).trim_end());
+                continue;
+            }
+
+            // Require the space so `>>>>>>> REPLACE` markers stay part of the reply
+            if line == ">" || line.starts_with("> ") {
+                let info = line[1..].trim();
+                if model.is_none() {
+                    model = info
+                        .strip_prefix("Main model: ")
+                        .or_else(|| info.strip_prefix("Model: "))
+                        .and_then(|rest| rest.split_whitespace().next())
+                        .map(|m| m.to_string());
+                }
+                if let Some(path) = info.strip_prefix("Applied edit to ") {
+                    flush(&mut transcript, &mut user_text, &mut assistant_text);
+                    transcript.add_message(Message::tool_use(
+                        "applied_edit".to_string(),
+                        serde_json::Value::String(path.trim().to_string()),
+                    ));
+                }
+                continue;
+            }
+
+            assistant_text.push(line);
+        }
+        flush(&mut transcript, &mut user_text, &mut assistant_text);
+
+        if let Some(input_history) = input_history {
+            Self::apply_input_history_timestamps(&mut transcript, input_history);
+        }
+
+        (transcript, model)
+    }
+
+    /// Timestamp user messages from `.aider.input.history`, which records every input as a
+    /// `# <local time>` line followed by `+`-prefixed lines. Inputs are matched from the most
+    /// recent backwards since the history spans all sessions.
+    fn apply_input_history_timestamps(transcript: &mut AiTranscript, input_history: &str) {
+        let mut inputs: Vec<(String, Vec<&str>)> = Vec::new();
+        for line in input_history.lines() {
+            if let Some(timestamp) = line.strip_prefix("# ") {
+                inputs.push((timestamp.trim().to_string(), Vec::new()));
+            } else if let (Some(text), Some((_, lines))) =
+                (line.strip_prefix('+'), inputs.last_mut())
+            {
+                lines.push(text);
+            }
+        }
+
+        let mut remaining = inputs.as_slice();
+        for message in transcript.messages.iter_mut().rev() {
+            let Message::User { text, timestamp } = message else {
+                continue;
+            };
+            let Some(position) = remaining
+                .iter()
+                .rposition(|(_, lines)| lines.join("\n").trim() == text.as_str())
+            else {
+                continue;
+            };
+
+            *timestamp = chrono::NaiveDateTime::parse_from_str(
+                &remaining[position].0,
+                "%Y-%m-%d %H:%M:%S%.f",
+            )
+            .ok()
+            .and_then(|naive| naive.and_local_timezone(chrono::Local).single())
+            .map(|dt| dt.to_rfc3339());
+            remaining = &remaining[..position];
+        }
+    }
+}
+
+// OpenAI Codex CLI to checkpoint preset
+pub struct CodexPreset;
+
+impl AgentCheckpointPreset for CodexPreset {
+    fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
+        // Codex's notify hook passes its JSON payload as the last argument
+        let hook_input_json = flags.hook_input.ok_or_else(|| {
+            GitAiError::PresetError("hook_input is required for Codex preset".to_string())
+        })?;
+
+        let hook_data: serde_json::Value = serde_json::from_str(&hook_input_json)
+            .map_err(|e| GitAiError::PresetError(format!("Invalid JSON in hook_input: {}", e)))?;
+
+        let thread_id = hook_data
+            .get("thread-id")
+            .or_else(|| hook_data.get("thread_id"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        // The payload doesn't point at the rollout file, so look it up by thread id unless
+        // a transcript_path is given explicitly
+        let rollout_path = match hook_data.get("transcript_path").and_then(|v| v.as_str()) {
+            Some(path) => PathBuf::from(path),
+            None => Self::find_rollout_file(&Self::codex_sessions_dir()?, thread_id.as_deref())?
+                .ok_or_else(|| {
+                    GitAiError::PresetError(match &thread_id {
+                        Some(thread_id) => {
+                            format!(
+                                "No Codex session rollout file found for thread {}",
+                                thread_id
+                            )
+                        }
+                        None => "No Codex session rollout file found".to_string(),
+                    })
+                })?,
+        };
+
+        let jsonl_content = std::fs::read_to_string(&rollout_path).map_err(GitAiError::IoError)?;
+
+        let (transcript, model) =
+            Self::transcript_and_model_from_codex_rollout_jsonl(&jsonl_content)?;
+
+        let session_id = thread_id
+            .or_else(|| Self::session_id_from_codex_rollout_jsonl(&jsonl_content))
+            .or(flags.prompt_id)
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let agent_id = AgentId {
+            tool: "codex".to_string(),
+            id: session_id,
+            model: model.unwrap_or_else(|| "unknown".to_string()),
+        };
+
+        Ok(AgentRunResult {
+            agent_id,
+            is_human: false,
+            transcript: Some(transcript),
+            // Codex runs the notify command from the session's working directory
+            repo_working_dir: hook_data
+                .get("cwd")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+            edited_files: None,
+        })
+    }
+}
+
+impl CodexPreset {
+    /// `$CODEX_HOME/sessions`, defaulting to `~/.codex/sessions`
+    fn codex_sessions_dir() -> Result<PathBuf, GitAiError> {
+        if let Ok(codex_home) = env::var("CODEX_HOME") {
+            return Ok(PathBuf::from(codex_home).join("sessions"));
+        }
+        let home =
+            env::var("HOME").map_err(|e| GitAiError::Generic(format!("HOME not set: {}", e)))?;
+        Ok(PathBuf::from(home).join(".codex").join("sessions"))
+    }
+
+    /// Find the rollout file for a session under `sessions_dir` (laid out as
+    /// `YYYY/MM/DD/rollout-<timestamp>-<session id>.jsonl`). Without a thread id, falls back
+    /// to the most recently modified rollout. With one, only that session's rollout is
+    /// returned: another concurrent session's transcript must never be attached.
+    pub fn find_rollout_file(
+        sessions_dir: &Path,
+        thread_id: Option<&str>,
+    ) -> Result<Option<PathBuf>, GitAiError> {
+        let mut rollouts = Vec::new();
+        Self::collect_rollout_files(sessions_dir, &mut rollouts)?;
+
+        if let Some(thread_id) = thread_id {
+            return Ok(rollouts.into_iter().find(|path| {
+                path.file_stem()
+                    .and_then(|stem| stem.to_str())
+                    .is_some_and(|stem| stem.ends_with(thread_id))
+            }));
+        }
+
+        Ok(rollouts
+            .into_iter()
+            .filter_map(|path| {
+                let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+                Some((modified, path))
+            })
+            .max_by_key(|(modified, _)| *modified)
+            .map(|(_, path)| path))
+    }
+
+    fn collect_rollout_files(dir: &Path, rollouts: &mut Vec<PathBuf>) -> Result<(), GitAiError> {
+        if !dir.is_dir() {
+            return Ok(());
+        }
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                Self::collect_rollout_files(&path, rollouts)?;
+            } else if path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("rollout-") && name.ends_with(".jsonl"))
+            {
+                rollouts.push(path);
+            }
+        }
+        Ok(())
+    }
+
+    /// The session id recorded in a rollout's `session_meta` line
+    pub fn session_id_from_codex_rollout_jsonl(jsonl: &str) -> Option<String> {
+        jsonl
+            .lines()
+            .filter_map(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+            .find(|entry| entry.get("type").and_then(|v| v.as_str()) == Some("session_meta"))
+            .and_then(|entry| {
+                entry
+                    .get("payload")
+                    .and_then(|p| p.get("id"))
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string())
+            })
+    }
+
+    /// Translate a Codex CLI session rollout JSONL string into an AiTranscript and optional
+    /// model. Messages and tool calls come from `response_item` lines and the model from the
+    /// latest `turn_context`.
+    pub fn transcript_and_model_from_codex_rollout_jsonl(
+        jsonl: &str,
+    ) -> Result<(AiTranscript, Option<String>), GitAiError> {
+        let mut transcript = AiTranscript::new();
+        let mut model = None;
+
+        for line in jsonl.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry: serde_json::Value =
+                serde_json::from_str(line).map_err(GitAiError::JsonError)?;
+
+            let timestamp = entry
+                .get("timestamp")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let Some(payload) = entry.get("payload") else {
+                continue;
+            };
+
+            match entry.get("type").and_then(|v| v.as_str()) {
+                Some("turn_context") => {
+                    if let Some(m) = payload.get("model").and_then(|v| v.as_str()) {
+                        model = Some(m.to_string());
+                    }
+                }
+                Some("response_item") => {
+                    Self::add_response_item(&mut transcript, payload, timestamp);
+                }
+                _ => {}
+            }
+        }
+
+        Ok((transcript, model))
+    }
+
+    fn add_response_item(
+        transcript: &mut AiTranscript,
+        item: &serde_json::Value,
+        timestamp: Option<String>,
+    ) {
+        match item.get("type").and_then(|v| v.as_str()) {
+            Some("message") => {
+                let text = item
+                    .get("content")
+                    .and_then(|v| v.as_array())
+                    .map(|content| {
+                        content
+                            .iter()
+                            .filter_map(|c| c.get("text").and_then(|v| v.as_str()))
+                            .collect::<Vec<_>>()
+                            .join("\n")
+                    })
+                    .unwrap_or_default();
+                let text = text.trim();
+                if text.is_empty() {
+                    return;
+                }
+
+                match item.get("role").and_then(|v| v.as_str()) {
+                    Some("user") => {
+                        // Codex injects the environment and AGENTS.md as user messages
+                        if text.starts_with("<environment_context>")
+                            || text.starts_with("<user_instructions>")
+                        {
+                            return;
+                        }
+                        transcript.add_message(Message::user(text.to_string(), timestamp));
+                    }
+                    Some("assistant") => {
+                        transcript.add_message(Message::assistant(text.to_string(), timestamp));
+                    }
+                    _ => {}
+                }
+            }
+            Some("function_call") => {
+                let Some(name) = item.get("name").and_then(|v| v.as_str()) else {
+                    return;
+                };
+                // Arguments are a JSON-encoded string
+                let input = match item.get("arguments") {
+                    Some(serde_json::Value::String(arguments)) => serde_json::from_str(arguments)
+                        .unwrap_or_else(|_| serde_json::Value::String(arguments.clone())),
+                    Some(arguments) => arguments.clone(),
+                    None => serde_json::Value::Null,
+                };
+                transcript.add_message(Message::ToolUse {
+                    name: name.to_string(),
+                    input,
+                    timestamp,
+                });
+            }
+            Some("custom_tool_call") => {
+                let Some(name) = item.get("name").and_then(|v| v.as_str()) else {
+                    return;
+                };
+                transcript.add_message(Message::ToolUse {
+                    name: name.to_string(),
+                    input: item
+                        .get("input")
+                        .cloned()
+                        .unwrap_or(serde_json::Value::Null),
+                    timestamp,
+                });
+            }
+            Some("local_shell_call") => {
+                transcript.add_message(Message::ToolUse {
+                    name: "local_shell".to_string(),
+                    input: item
+                        .get("action")
+                        .cloned()
+                        .unwrap_or(serde_json::Value::Null),
+                    timestamp,
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+// Gemini CLI to checkpoint preset
+pub struct GeminiPreset;
+
+impl AgentCheckpointPreset for GeminiPreset {
+    fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
+        let hook_input_json = flags.hook_input.ok_or_else(|| {
+            GitAiError::PresetError("hook_input is required for Gemini preset".to_string())
+        })?;
+
+        let hook_data: serde_json::Value = serde_json::from_str(&hook_input_json)
+            .map_err(|e| GitAiError::PresetError(format!("Invalid JSON in hook_input: {}", e)))?;
+
+        let session_id = hook_data
+            .get("session_id")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        let repo_working_dir = hook_data
+            .get("cwd")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+
+        if hook_data.get("hook_event_name").and_then(|v| v.as_str()) == Some("BeforeTool") {
+            // early return, we're just adding a human checkpoint.
+            return Ok(AgentRunResult {
+                agent_id: AgentId {
+                    tool: "gemini".to_string(),
+                    id: session_id.unwrap_or_else(|| "unknown".to_string()),
+                    model: "unknown".to_string(),
+                },
+                is_human: true,
+                transcript: None,
+                repo_working_dir,
+                edited_files: None,
+            });
+        }
+
+        let transcript_path = hook_data
+            .get("transcript_path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| {
+                GitAiError::PresetError("transcript_path not found in hook_input".to_string())
+            })?;
+
+        let json_content = std::fs::read_to_string(transcript_path).map_err(GitAiError::IoError)?;
+
+        let (transcript, model) = Self::transcript_and_model_from_gemini_json(&json_content)?;
+
+        let session_id = session_id
+            .or_else(|| Self::session_id_from_gemini_json(&json_content))
+            .or(flags.prompt_id)
+            .unwrap_or_else(|| "unknown".to_string());
+
+        let agent_id = AgentId {
+            tool: "gemini".to_string(),
+            id: session_id,
+            model: model.unwrap_or_else(|| "unknown".to_string()),
+        };
+
+        Ok(AgentRunResult {
+            agent_id,
+            is_human: false,
+            transcript: Some(transcript),
+            repo_working_dir,
+            edited_files: None,
+        })
+    }
+}
+
+impl GeminiPreset {
+    /// The `sessionId` of a Gemini CLI chat recording
+    pub fn session_id_from_gemini_json(json_str: &str) -> Option<String> {
+        serde_json::from_str::<serde_json::Value>(json_str)
+            .ok()?
+            .get("sessionId")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+    }
+
+    /// Translate a Gemini CLI chat log into an AiTranscript and optional model. Accepts both
+    /// the chat recordings Gemini CLI keeps per session (an object with `messages`) and saved
+    /// checkpoints (an array of `{role, parts}` contents, which carry no model or timestamps).
+    pub fn transcript_and_model_from_gemini_json(
+        json_str: &str,
+    ) -> Result<(AiTranscript, Option<String>), GitAiError> {
+        let json: serde_json::Value =
+            serde_json::from_str(json_str).map_err(GitAiError::JsonError)?;
+
+        let mut transcript = AiTranscript::new();
+        let mut model = None;
+
+        if let Some(contents) = json.as_array() {
+            for content in contents {
+                Self::add_checkpoint_content(&mut transcript, content);
+            }
+            return Ok((transcript, model));
+        }
+
+        let messages = json
+            .get("messages")
+            .and_then(|v| v.as_array())
+            .ok_or_else(|| {
+                GitAiError::PresetError("messages not found in Gemini chat log".to_string())
+            })?;
+
+        for message in messages {
+            let timestamp = message
+                .get("timestamp")
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let text = message
+                .get("content")
+                .map(Self::text_from_content)
+                .unwrap_or_default();
+
+            match message.get("type").and_then(|v| v.as_str()) {
+                Some("user") if !text.is_empty() => {
+                    transcript.add_message(Message::user(text, timestamp));
+                }
+                Some("gemini") => {
+                    // The model can change mid-session, the latest one wins
+                    if let Some(m) = message.get("model").and_then(|v| v.as_str()) {
+                        model = Some(m.to_string());
+                    }
+                    if !text.is_empty() {
+                        transcript.add_message(Message::assistant(text, timestamp.clone()));
+                    }
+                    for tool_call in message
+                        .get("toolCalls")
+                        .and_then(|v| v.as_array())
+                        .into_iter()
+                        .flatten()
+                    {
+                        if let Some(name) = tool_call.get("name").and_then(|v| v.as_str()) {
+                            transcript.add_message(Message::ToolUse {
+                                name: name.to_string(),
+                                input: tool_call
+                                    .get("args")
+                                    .cloned()
+                                    .unwrap_or(serde_json::Value::Null),
+                                timestamp: tool_call
+                                    .get("timestamp")
+                                    .and_then(|v| v.as_str())
+                                    .map(|s| s.to_string())
+                                    .or_else(|| timestamp.clone()),
+                            });
+                        }
+                    }
+                }
+                // info, warning and error messages come from the CLI itself
+                _ => {}
+            }
+        }
+
+        Ok((transcript, model))
+    }
+
+    /// Message content is either a string or a list of `{text}` parts
+    fn text_from_content(content: &serde_json::Value) -> String {
+        match content {
+            serde_json::Value::String(text) => text.trim().to_string(),
+            serde_json::Value::Array(parts) => parts
+                .iter()
+                .filter_map(|part| part.get("text").and_then(|v| v.as_str()))
+                .collect::<Vec<_>>()
+                .join("\n")
+                .trim()
+                .to_string(),
+            _ => String::new(),
+        }
+    }
+
+    fn add_checkpoint_content(transcript: &mut AiTranscript, content: &serde_json::Value) {
+        let role = content.get("role").and_then(|v| v.as_str());
+        let parts = content
+            .get("parts")
+            .and_then(|v| v.as_array())
+            .map(|parts| parts.as_slice())
+            .unwrap_or_default();
+
+        let text = parts
+            .iter()
+            .filter(|part| {
+                !part
+                    .get("thought")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false)
+            })
+            .filter_map(|part| part.get("text").and_then(|v| v.as_str()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let text = text.trim();
+
+        // Tool results are sent back as user contents with only functionResponse parts
+        if !text.is_empty() {
+            match role {
+                Some("user") => transcript.add_message(Message::user(text.to_string(), None)),
+                Some("model") => transcript.add_message(Message::assistant(text.to_string(), None)),
+                _ => {}
+            }
+        }
+
+        for function_call in parts.iter().filter_map(|part| part.get("functionCall")) {
+            if let Some(name) = function_call.get("name").and_then(|v| v.as_str()) {
+                transcript.add_message(Message::tool_use(
+                    name.to_string(),
+                    function_call
+                        .get("args")
+                        .cloned()
+                        .unwrap_or(serde_json::Value::Null),
+                ));
+            }
+        }
+    }
+}
+
+// Generic agent protocol preset, for agents without a dedicated preset
+pub struct GenericPreset;
+
+impl GenericPreset {
+    pub const SCHEMA_VERSION: u32 = 1;
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GenericHookInput {
+    #[allow(dead_code)] // checked before deserializing
+    version: u32,
+    tool: String,
+    session_id: String,
+    #[serde(default)]
+    model: Option<String>,
+    #[serde(default)]
+    cwd: Option<String>,
+    #[serde(default)]
+    messages: Vec<GenericMessage>,
+    #[serde(default)]
+    edited_files: Option<Vec<GenericEditedFile>>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "role", rename_all = "snake_case", deny_unknown_fields)]
+enum GenericMessage {
+    User {
+        text: String,
+        #[serde(default)]
+        timestamp: Option<String>,
+    },
+    Assistant {
+        text: String,
+        #[serde(default)]
+        timestamp: Option<String>,
+    },
+    ToolUse {
+        name: String,
+        #[serde(default)]
+        input: serde_json::Value,
+        #[serde(default)]
+        timestamp: Option<String>,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GenericEditedFile {
+    path: String,
+    #[serde(default)]
+    line_ranges: Vec<(u32, u32)>,
+}
+
+impl AgentCheckpointPreset for GenericPreset {
+    fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
+        let hook_input_json = flags.hook_input.ok_or_else(|| {
+            GitAiError::PresetError("hook_input is required for generic preset".to_string())
+        })?;
+
+        // Check the version before the rest of the schema so newer payloads get a clear error
+        let hook_data: serde_json::Value = serde_json::from_str(&hook_input_json)
+            .map_err(|e| GitAiError::PresetError(format!("Invalid JSON in hook_input: {}", e)))?;
+        match hook_data.get("version").and_then(|v| v.as_u64()) {
+            Some(version) if version == Self::SCHEMA_VERSION as u64 => {}
+            Some(version) => {
+                return Err(GitAiError::PresetError(format!(
+                    "Unsupported generic preset schema version {} (supported: {})",
+                    version,
+                    Self::SCHEMA_VERSION
+                )));
+            }
+            None => {
+                return Err(GitAiError::PresetError(
+                    "version not found in hook_input".to_string(),
+                ));
+            }
+        }
+
+        let input: GenericHookInput = serde_json::from_value(hook_data)
+            .map_err(|e| GitAiError::PresetError(format!("Invalid hook_input: {}", e)))?;
+
+        if input.tool.trim().is_empty() {
+            return Err(GitAiError::PresetError(
+                "tool must not be empty".to_string(),
+            ));
+        }
+        if input.session_id.trim().is_empty() {
+            return Err(GitAiError::PresetError(
+                "session_id must not be empty".to_string(),
+            ));
+        }
+
+        let mut transcript = AiTranscript::new();
+        for (idx, message) in input.messages.into_iter().enumerate() {
+            let message = match message {
+                GenericMessage::User { text, timestamp } => Message::User {
+                    text,
+                    timestamp: Self::validate_timestamp(idx, timestamp)?,
+                },
+                GenericMessage::Assistant { text, timestamp } => Message::Assistant {
+                    text,
+                    timestamp: Self::validate_timestamp(idx, timestamp)?,
+                },
+                GenericMessage::ToolUse {
+                    name,
+                    input,
+                    timestamp,
+                } => Message::ToolUse {
+                    name,
+                    input,
+                    timestamp: Self::validate_timestamp(idx, timestamp)?,
+                },
+            };
+            transcript.add_message(message);
+        }
+
+        let edited_files = input
+            .edited_files
+            .map(|files| {
+                // Absolute paths are checked against the repository the checkpoint runs in
+                let workdir = if files.iter().any(|file| Path::new(&file.path).is_absolute()) {
+                    Some(Self::repo_workdir(input.cwd.as_deref())?)
+                } else {
+                    None
+                };
+                files
+                    .into_iter()
+                    .map(|file| Self::validate_edited_file(file, workdir.as_deref()))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+
+        let agent_id = AgentId {
+            tool: input.tool,
+            id: input.session_id,
+            model: input
+                .model
+                .filter(|m| !m.trim().is_empty())
+                .unwrap_or_else(|| "unknown".to_string()),
+        };
+
+        Ok(AgentRunResult {
+            agent_id,
+            is_human: false,
+            transcript: Some(transcript),
+            repo_working_dir: input.cwd,
+            edited_files,
+        })
+    }
+}
+
+impl GenericPreset {
+    fn validate_timestamp(
+        message_idx: usize,
+        timestamp: Option<String>,
+    ) -> Result<Option<String>, GitAiError> {
+        if let Some(ts) = &timestamp
+            && chrono::DateTime::parse_from_rfc3339(ts).is_err()
+        {
+            return Err(GitAiError::PresetError(format!(
+                "messages[{}].timestamp is not an RFC 3339 timestamp: {}",
+                message_idx, ts
+            )));
+        }
+        Ok(timestamp)
+    }
+
+    /// Working directory of the repository containing `cwd` (or the current directory)
+    fn repo_workdir(cwd: Option<&str>) -> Result<PathBuf, GitAiError> {
+        let dir = match cwd {
+            Some(cwd) => PathBuf::from(cwd),
+            None => env::current_dir()?,
+        };
+        find_repository_in_path(&dir.to_string_lossy())
+            .and_then(|repo| repo.workdir())
+            .map_err(|e| {
+                GitAiError::PresetError(format!(
+                    "Absolute edited file paths need a repository at {}: {}",
+                    dir.display(),
+                    e
+                ))
+            })
+    }
+
+    /// Validate an edited file, making absolute paths relative to `workdir`. Absolute paths
+    /// outside the repository are rejected.
+    fn validate_edited_file(
+        file: GenericEditedFile,
+        workdir: Option<&Path>,
+    ) -> Result<EditedFile, GitAiError> {
+        let path = if Path::new(&file.path).is_absolute() {
+            workdir
+                .and_then(|workdir| Self::relative_to_workdir(Path::new(&file.path), workdir))
+                .ok_or_else(|| {
+                    GitAiError::PresetError(format!(
+                        "Edited file path is outside the repository: {:?}",
+                        file.path
+                    ))
+                })?
+        } else {
+            file.path.trim_start_matches("./").to_string()
+        };
+        if path.is_empty()
+            || Path::new(&path)
+                .components()
+                .any(|c| matches!(c, std::path::Component::ParentDir))
+        {
+            return Err(GitAiError::PresetError(format!(
+                "Invalid edited file path: {:?}",
+                file.path
+            )));
+        }
+
+        for (start, end) in &file.line_ranges {
+            if *start == 0 || start > end {
+                return Err(GitAiError::PresetError(format!(
+                    "Invalid line range [{}, {}] for {}: ranges are 1-based and inclusive",
+                    start, end, path
+                )));
+            }
+        }
+
+        Ok(EditedFile {
+            path,
+            line_ranges: file.line_ranges,
+        })
+    }
+
+    fn relative_to_workdir(path: &Path, workdir: &Path) -> Option<String> {
+        let relative = match path.strip_prefix(workdir) {
+            Ok(relative) => relative.to_path_buf(),
+            // git reports the canonical workdir; the agent's path may go through a symlink
+            Err(_) => std::fs::canonicalize(path)
+                .ok()?
+                .strip_prefix(workdir)
+                .ok()?
+                .to_path_buf(),
+        };
+        Some(relative.to_string_lossy().replace('\\', "/"))
+    }
+}
@@ -0,0 +1,413 @@
+use crate::authorship::authorship_log::LineRange;
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::error::GitAiError;
+use crate::git::refs::{
+    list_authorship_notes, notes_add_authorship, notes_remove, prompt_transcript_exists,
+    show_authorship_note,
+};
+use crate::git::repository::Repository;
+use std::fmt;
+
+/// A single inconsistency between an authorship note and the commit it annotates
+#[derive(Debug, Clone, PartialEq)]
+pub enum FsckIssue {
+    /// The note does not parse as an authorship log
+    UnreadableNote { error: String },
+    /// The note is attached to an object that is not a commit in this repository
+    OrphanedNote,
+    /// `base_commit_sha` does not resolve to a commit
+    UnknownBaseCommit { base_commit_sha: String },
+    /// An attestation for a file the commit did not touch (or that it deleted)
+    FileNotInCommit { file_path: String },
+    /// An attestation entry claims lines beyond the end of the file
+    LinesPastEof {
+        file_path: String,
+        hash: String,
+        last_line: u32,
+        line_count: u32,
+    },
+    /// An attestation entry whose hash has no record in `metadata.prompts`
+    MissingPrompt { file_path: String, hash: String },
+    /// A prompt whose `messages_ref` is not in the prompt store
+    MissingTranscript { hash: String, messages_ref: String },
+}
+
+impl FsckIssue {
+    /// Unreadable notes and lost transcripts need a human; everything else has a safe fix
+    pub fn is_repairable(&self) -> bool {
+        !matches!(
+            self,
+            FsckIssue::UnreadableNote { .. } | FsckIssue::MissingTranscript { .. }
+        )
+    }
+}
+
+impl fmt::Display for FsckIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FsckIssue::UnreadableNote { error } => write!(f, "unreadable note: {}", error),
+            FsckIssue::OrphanedNote => write!(f, "note is not attached to a commit"),
+            FsckIssue::UnknownBaseCommit { base_commit_sha } => {
+                write!(f, "base commit {} does not exist", base_commit_sha)
+            }
+            FsckIssue::FileNotInCommit { file_path } => {
+                write!(f, "{}: file is not part of the commit", file_path)
+            }
+            FsckIssue::LinesPastEof {
+                file_path,
+                hash,
+                last_line,
+                line_count,
+            } => write!(
+                f,
+                "{}: prompt {} claims line {} but the file has {} line(s)",
+                file_path, hash, last_line, line_count
+            ),
+            FsckIssue::MissingPrompt { file_path, hash } => {
+                write!(f, "{}: prompt {} is missing from metadata", file_path, hash)
+            }
+            FsckIssue::MissingTranscript { hash, messages_ref } => write!(
+                f,
+                "prompt {} references transcript {} which is not in the prompt store",
+                hash, messages_ref
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteCheck {
+    pub commit_sha: String,
+    pub issues: Vec<FsckIssue>,
+    /// Whether the repairable issues were fixed (only with `--repair`)
+    pub repaired: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct FsckReport {
+    pub checked: usize,
+    /// Notes with at least one issue
+    pub problems: Vec<NoteCheck>,
+}
+
+impl FsckReport {
+    /// Whether anything is still wrong after this run
+    pub fn has_outstanding_issues(&self) -> bool {
+        self.problems
+            .iter()
+            .any(|check| !check.repaired || check.issues.iter().any(|issue| !issue.is_repairable()))
+    }
+}
+
+/// Check every note in refs/notes/ai against the commit it annotates. With `repair`,
+/// orphaned notes are removed and notes with repairable issues are rewritten.
+pub fn run(repo: &Repository, repair: bool) -> Result<FsckReport, GitAiError> {
+    let mut report = FsckReport::default();
+
+    for commit_sha in list_authorship_notes(repo)? {
+        report.checked += 1;
+
+        if repo.find_commit(commit_sha.clone()).is_err() {
+            if repair {
+                notes_remove(repo, &commit_sha)?;
+            }
+            report.problems.push(NoteCheck {
+                commit_sha,
+                issues: vec![FsckIssue::OrphanedNote],
+                repaired: repair,
+            });
+            continue;
+        }
+
+        let Some(content) = show_authorship_note(repo, &commit_sha) else {
+            continue;
+        };
+        let authorship_log = match AuthorshipLog::deserialize_from_string(&content) {
+            Ok(log) => log,
+            Err(e) => {
+                report.problems.push(NoteCheck {
+                    commit_sha,
+                    issues: vec![FsckIssue::UnreadableNote {
+                        error: e.to_string(),
+                    }],
+                    repaired: false,
+                });
+                continue;
+            }
+        };
+
+        let (issues, repaired_log) = check_note(repo, &commit_sha, &authorship_log)?;
+        if issues.is_empty() {
+            continue;
+        }
+
+        let needs_rewrite = issues.iter().any(|issue| issue.is_repairable());
+        if repair && needs_rewrite {
+            notes_add_authorship(repo, &commit_sha, &repaired_log)?;
+        }
+        report.problems.push(NoteCheck {
+            commit_sha,
+            issues,
+            repaired: repair && needs_rewrite,
+        });
+    }
+
+    Ok(report)
+}
+
+/// Cross-check one note against its commit. Returns the issues found and a copy of the
+/// log with every repairable issue fixed.
+fn check_note(
+    repo: &Repository,
+    commit_sha: &str,
+    authorship_log: &AuthorshipLog,
+) -> Result<(Vec<FsckIssue>, AuthorshipLog), GitAiError> {
+    let mut issues = Vec::new();
+    let mut repaired = authorship_log.clone();
+
+    let base_commit_sha = &authorship_log.metadata.base_commit_sha;
+    if !base_commit_sha.is_empty()
+        && base_commit_sha != "initial"
+        && repo.find_commit(base_commit_sha.clone()).is_err()
+    {
+        issues.push(FsckIssue::UnknownBaseCommit {
+            base_commit_sha: base_commit_sha.clone(),
+        });
+        // Post-commit records the parent the working log was based on
+        let commit = repo.find_commit(commit_sha.to_string())?;
+        repaired.metadata.base_commit_sha = match commit.parent(0) {
+            Ok(parent) => parent.id(),
+            Err(_) => "initial".to_string(),
+        };
+    }
+
+    let commit_files = repo.list_commit_files(commit_sha, None)?;
+    repaired.attestations.clear();
+
+    for file_attestation in &authorship_log.attestations {
+        let file_path = &file_attestation.file_path;
+        let content = if commit_files.contains(file_path) {
+            repo.file_content_at_commit(commit_sha, file_path)?
+        } else {
+            None
+        };
+        let Some(content) = content else {
+            issues.push(FsckIssue::FileNotInCommit {
+                file_path: file_path.clone(),
+            });
+            continue;
+        };
+        let line_count = content.lines().count() as u32;
+
+        let mut repaired_file = file_attestation.clone();
+        repaired_file.entries.clear();
+
+        for entry in &file_attestation.entries {
+            if !authorship_log.metadata.prompts.contains_key(&entry.hash) {
+                issues.push(FsckIssue::MissingPrompt {
+                    file_path: file_path.clone(),
+                    hash: entry.hash.clone(),
+                });
+                continue;
+            }
+
+            let mut repaired_entry = entry.clone();
+            if let Some(&last_line) = entry.lines().last()
+                && last_line > line_count
+            {
+                issues.push(FsckIssue::LinesPastEof {
+                    file_path: file_path.clone(),
+                    hash: entry.hash.clone(),
+                    last_line,
+                    line_count,
+                });
+                repaired_entry.remove_line_ranges(&[LineRange::Range(line_count + 1, last_line)]);
+            }
+            if !repaired_entry.line_ranges.is_empty() {
+                repaired_file.add_entry(repaired_entry);
+            }
+        }
+
+        if !repaired_file.entries.is_empty() {
+            repaired.attestations.push(repaired_file);
+        }
+    }
+
+    for (hash, prompt_record) in &authorship_log.metadata.prompts {
+        if let Some(messages_ref) = &prompt_record.messages_ref
+            && !prompt_transcript_exists(repo, messages_ref)
+        {
+            issues.push(FsckIssue::MissingTranscript {
+                hash: hash.clone(),
+                messages_ref: messages_ref.clone(),
+            });
+        }
+    }
+
+    Ok((issues, repaired))
+}
+
+pub fn write_report_to_terminal(report: &FsckReport, repair: bool) {
+    for check in &report.problems {
+        for issue in &check.issues {
+            let status = if check.repaired && issue.is_repairable() {
+                "repaired"
+            } else if issue.is_repairable() {
+                "repairable"
+            } else {
+                "needs attention"
+            };
+            println!(
+                "{}  {} [{}]",
+                &check.commit_sha[..8.min(check.commit_sha.len())],
+                issue,
+                status
+            );
+        }
+    }
+
+    let issue_count: usize = report.problems.iter().map(|c| c.issues.len()).sum();
+    println!(
+        "Checked {} note(s): {} issue(s) in {} note(s){}",
+        report.checked,
+        issue_count,
+        report.problems.len(),
+        if !repair
+            && report
+                .problems
+                .iter()
+                .any(|c| c.issues.iter().any(|i| i.is_repairable()))
+        {
+            " (run with --repair to fix repairable issues)"
+        } else {
+            ""
+        }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::authorship_log::PromptRecord;
+    use crate::authorship::authorship_log_serialization::{AttestationEntry, FileAttestation};
+    use crate::authorship::working_log::AgentId;
+    use crate::git::refs::get_authorship;
+    use crate::git::test_utils::TmpRepo;
+
+    fn prompt_record() -> PromptRecord {
+        PromptRecord {
+            agent_id: AgentId {
+                tool: "cursor".to_string(),
+                id: "session-1".to_string(),
+                model: "gpt-4".to_string(),
+            },
+            human_author: None,
+            messages: Vec::new(),
+            messages_ref: None,
+            total_additions: 2,
+            total_deletions: 0,
+            accepted_lines: 2,
+            overriden_lines: 0,
+            redactions: 0,
+        }
+    }
+
+    #[test]
+    fn test_fsck_reports_and_repairs_inconsistent_note() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\ntwo\n", true).unwrap();
+        tmp_repo.commit_with_message("Add a.txt").unwrap();
+        let commit_sha = tmp_repo.get_head_commit_sha().unwrap();
+        let repo = tmp_repo.gitai_repo();
+
+        let mut log = AuthorshipLog::new();
+        log.metadata.base_commit_sha = "0123456789abcdef0123456789abcdef01234567".to_string();
+        log.metadata
+            .prompts
+            .insert("abc1234".to_string(), prompt_record());
+        let mut a = FileAttestation::new("a.txt".to_string());
+        a.add_entry(AttestationEntry::new(
+            "abc1234".to_string(),
+            vec![LineRange::Range(1, 5)],
+        ));
+        a.add_entry(AttestationEntry::new(
+            "fff0000".to_string(),
+            vec![LineRange::Single(1)],
+        ));
+        log.attestations.push(a);
+        let mut b = FileAttestation::new("b.txt".to_string());
+        b.add_entry(AttestationEntry::new(
+            "abc1234".to_string(),
+            vec![LineRange::Single(1)],
+        ));
+        log.attestations.push(b);
+        notes_add_authorship(repo, &commit_sha, &log).unwrap();
+
+        let report = run(repo, false).unwrap();
+        let check = report
+            .problems
+            .iter()
+            .find(|c| c.commit_sha == commit_sha)
+            .unwrap();
+        assert!(!check.repaired);
+        assert_eq!(
+            check.issues,
+            vec![
+                FsckIssue::UnknownBaseCommit {
+                    base_commit_sha: "0123456789abcdef0123456789abcdef01234567".to_string(),
+                },
+                FsckIssue::LinesPastEof {
+                    file_path: "a.txt".to_string(),
+                    hash: "abc1234".to_string(),
+                    last_line: 5,
+                    line_count: 2,
+                },
+                FsckIssue::MissingPrompt {
+                    file_path: "a.txt".to_string(),
+                    hash: "fff0000".to_string(),
+                },
+                FsckIssue::FileNotInCommit {
+                    file_path: "b.txt".to_string(),
+                },
+            ]
+        );
+        assert!(report.has_outstanding_issues());
+
+        let report = run(repo, true).unwrap();
+        assert!(!report.has_outstanding_issues());
+
+        let repaired = get_authorship(repo, &commit_sha).unwrap();
+        assert_eq!(repaired.metadata.base_commit_sha, "initial");
+        assert_eq!(repaired.attestations.len(), 1);
+        assert_eq!(repaired.attestations[0].entries.len(), 1);
+        assert_eq!(
+            repaired.attestations[0].entries[0].line_ranges,
+            vec![LineRange::Range(1, 2)]
+        );
+
+        let report = run(repo, false).unwrap();
+        assert!(report.problems.is_empty());
+    }
+
+    #[test]
+    fn test_fsck_removes_orphaned_notes_on_repair() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo.commit_with_message("Add a.txt").unwrap();
+        let repo = tmp_repo.gitai_repo();
+
+        let blob_sha = repo.blob(b"not a commit").unwrap();
+        crate::git::refs::notes_add(repo, &blob_sha, "stray").unwrap();
+
+        let report = run(repo, false).unwrap();
+        let check = report
+            .problems
+            .iter()
+            .find(|c| c.commit_sha == blob_sha)
+            .unwrap();
+        assert_eq!(check.issues, vec![FsckIssue::OrphanedNote]);
+
+        run(repo, true).unwrap();
+        assert!(show_authorship_note(repo, &blob_sha).is_none());
+    }
+}
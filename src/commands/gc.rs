@@ -0,0 +1,436 @@
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::git::refs::{AI_AUTHORSHIP_REFNAME, ref_exists};
+use crate::git::repository::{Repository, exec_git, exec_git_stdin};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+#[derive(Debug, Clone, Default)]
+pub struct GcOptions {
+    /// Working logs untouched for longer than this are removed even if their base commit is
+    /// reachable. The working log for HEAD is always kept.
+    pub max_age: Option<Duration>,
+    pub dry_run: bool,
+}
+
+/// The maximum working log age from `working_log_max_age_days` in config.json (0 disables)
+pub fn configured_max_age() -> Option<Duration> {
+    match Config::get().get_working_log_max_age_days() {
+        0 => None,
+        days => Some(Duration::from_secs(days * 24 * 60 * 60)),
+    }
+}
+
+/// Why a working log was removed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PruneReason {
+    /// The base commit no longer exists (e.g. garbage-collected after a rebase)
+    MissingBaseCommit,
+    /// The base commit exists but no branch, tag or HEAD reaches it
+    UnreachableBaseCommit,
+    /// Not written to for longer than the maximum age
+    Stale,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrunedWorkingLog {
+    pub base_commit: String,
+    pub reason: PruneReason,
+    pub bytes: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct GcReport {
+    pub working_logs: Vec<PrunedWorkingLog>,
+    /// Objects whose authorship note was pruned because the object no longer exists
+    pub pruned_notes: Vec<String>,
+    /// Bytes of working logs and note blobs freed (note blobs are reclaimed by the next
+    /// `git gc`)
+    pub reclaimed_bytes: u64,
+}
+
+/// Remove working logs whose base commit is gone, unreachable or stale, and notes on
+/// objects that no longer exist. With `dry_run`, only reports what would be removed.
+pub fn run(repo: &Repository, options: &GcOptions) -> Result<GcReport, GitAiError> {
+    let mut report = GcReport::default();
+
+    for pruned in working_logs_to_prune(repo, options.max_age)? {
+        if !options.dry_run {
+            repo.storage
+                .delete_working_log_for_base_commit(&pruned.base_commit)?;
+        }
+        report.reclaimed_bytes += pruned.bytes;
+        report.working_logs.push(pruned);
+    }
+
+    let notes_ref = format!("refs/notes/{}", AI_AUTHORSHIP_REFNAME);
+    if ref_exists(repo, &notes_ref) {
+        let (pruned_notes, bytes) = notes_on_missing_objects(repo)?;
+        if !pruned_notes.is_empty() && !options.dry_run {
+            let mut args = repo.global_args_for_exec();
+            args.push("notes".to_string());
+            args.push(format!("--ref={}", AI_AUTHORSHIP_REFNAME));
+            args.push("prune".to_string());
+            exec_git(&args)?;
+        }
+        report.pruned_notes = pruned_notes;
+        report.reclaimed_bytes += bytes;
+    }
+
+    Ok(report)
+}
+
+fn working_logs_to_prune(
+    repo: &Repository,
+    max_age: Option<Duration>,
+) -> Result<Vec<PrunedWorkingLog>, GitAiError> {
+    let working_logs_dir = &repo.storage.working_logs;
+    if !working_logs_dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut base_commits = Vec::new();
+    for entry in fs::read_dir(working_logs_dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir() {
+            base_commits.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    base_commits.sort();
+
+    let head_sha = repo
+        .revparse_single("HEAD")
+        .and_then(|object| object.peel_to_commit())
+        .map(|commit| commit.id())
+        .ok();
+    let object_types = object_types(repo, &base_commits)?;
+    let existing: Vec<String> = base_commits
+        .iter()
+        .filter(|sha| object_types.get(*sha).map(String::as_str) == Some("commit"))
+        .cloned()
+        .collect();
+    let unreachable = unreachable_commits(repo, &existing)?;
+
+    let mut pruned = Vec::new();
+    for base_commit in base_commits {
+        if head_sha.as_deref() == Some(base_commit.as_str()) {
+            continue;
+        }
+        let dir = working_logs_dir.join(&base_commit);
+        let reason = if base_commit == "initial" {
+            // Checkpoints taken before the first commit; obsolete once there is one
+            if head_sha.is_none() {
+                continue;
+            }
+            PruneReason::UnreachableBaseCommit
+        } else if !object_types.contains_key(&base_commit) {
+            PruneReason::MissingBaseCommit
+        } else if unreachable.contains(&base_commit) {
+            PruneReason::UnreachableBaseCommit
+        } else if let Some(max_age) = max_age
+            && last_modified(&dir)?
+                .elapsed()
+                .is_ok_and(|age| age > max_age)
+        {
+            PruneReason::Stale
+        } else {
+            continue;
+        };
+
+        pruned.push(PrunedWorkingLog {
+            bytes: dir_size(&dir)?,
+            base_commit,
+            reason,
+        });
+    }
+
+    Ok(pruned)
+}
+
+/// Type of each object that exists, in one `git cat-file --batch-check` call
+fn object_types(
+    repo: &Repository,
+    objects: &[String],
+) -> Result<HashMap<String, String>, GitAiError> {
+    Ok(batch_check(repo, objects)?
+        .into_iter()
+        .map(|(sha, object_type, _)| (sha, object_type))
+        .collect())
+}
+
+/// (sha, type, size) for each object that exists
+fn batch_check(
+    repo: &Repository,
+    objects: &[String],
+) -> Result<Vec<(String, String, u64)>, GitAiError> {
+    if objects.is_empty() {
+        return Ok(Vec::new());
+    }
+    let mut args = repo.global_args_for_exec();
+    args.push("cat-file".to_string());
+    args.push("--batch-check=%(objectname) %(objecttype) %(objectsize)".to_string());
+
+    // Missing objects come back as "<name> missing" and are skipped
+    let output = exec_git_stdin(&args, format!("{}\n", objects.join("\n")).as_bytes())?;
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let sha = parts.next()?.to_string();
+            let object_type = parts.next()?.to_string();
+            let size = parts.next()?.parse().ok()?;
+            Some((sha, object_type, size))
+        })
+        .collect())
+}
+
+/// The commits in `commits` that no branch, tag, remote-tracking ref or HEAD reaches
+fn unreachable_commits(
+    repo: &Repository,
+    commits: &[String],
+) -> Result<HashSet<String>, GitAiError> {
+    if commits.is_empty() {
+        return Ok(HashSet::new());
+    }
+    let mut args = repo.global_args_for_exec();
+    args.push("rev-list".to_string());
+    args.extend(commits.iter().cloned());
+    args.push("--not".to_string());
+    // Notes refs point at notes commits, not at history
+    args.push("--exclude=refs/notes/*".to_string());
+    args.push("--all".to_string());
+    args.push("HEAD".to_string());
+    args.push("--".to_string());
+    let output = exec_git(&args)?;
+    let candidates: HashSet<&String> = commits.iter().collect();
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .filter(|sha| candidates.contains(&sha.to_string()))
+        .map(|sha| sha.to_string())
+        .collect())
+}
+
+/// Notes in refs/notes/ai whose object no longer exists, and the size of their note blobs
+fn notes_on_missing_objects(repo: &Repository) -> Result<(Vec<String>, u64), GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("notes".to_string());
+    args.push(format!("--ref={}", AI_AUTHORSHIP_REFNAME));
+    args.push("list".to_string());
+    let output = exec_git(&args)?;
+
+    // Each line is "<note blob> <annotated object>"
+    let notes: Vec<(String, String)> = String::from_utf8(output.stdout)?
+        .lines()
+        .filter_map(|line| {
+            let (blob_sha, object_sha) = line.split_once(' ')?;
+            Some((blob_sha.to_string(), object_sha.to_string()))
+        })
+        .collect();
+    let objects: Vec<String> = notes.iter().map(|(_, object)| object.clone()).collect();
+    let existing: HashSet<String> = batch_check(repo, &objects)?
+        .into_iter()
+        .map(|(sha, _, _)| sha)
+        .collect();
+
+    let missing: Vec<&(String, String)> = notes
+        .iter()
+        .filter(|(_, object)| !existing.contains(object))
+        .collect();
+    let blobs: Vec<String> = missing.iter().map(|(blob, _)| blob.clone()).collect();
+    let bytes = batch_check(repo, &blobs)?
+        .iter()
+        .map(|(_, _, size)| size)
+        .sum();
+
+    Ok((
+        missing
+            .into_iter()
+            .map(|(_, object)| object.clone())
+            .collect(),
+        bytes,
+    ))
+}
+
+/// Most recent modification time of anything in `dir` (or of `dir` itself)
+fn last_modified(dir: &Path) -> Result<SystemTime, GitAiError> {
+    let mut latest = fs::metadata(dir)?.modified()?;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let modified = if entry.file_type()?.is_dir() {
+            last_modified(&entry.path())?
+        } else {
+            entry.metadata()?.modified()?
+        };
+        latest = latest.max(modified);
+    }
+    Ok(latest)
+}
+
+fn dir_size(dir: &Path) -> Result<u64, GitAiError> {
+    let mut size = 0;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        size += if entry.file_type()?.is_dir() {
+            dir_size(&entry.path())?
+        } else {
+            entry.metadata()?.len()
+        };
+    }
+    Ok(size)
+}
+
+pub fn write_report_to_terminal(report: &GcReport, dry_run: bool) {
+    for pruned in &report.working_logs {
+        let reason = match pruned.reason {
+            PruneReason::MissingBaseCommit => "base commit no longer exists",
+            PruneReason::UnreachableBaseCommit => "base commit is unreachable",
+            PruneReason::Stale => "older than the maximum age",
+        };
+        println!(
+            "working log {}: {}",
+            &pruned.base_commit[..8.min(pruned.base_commit.len())],
+            reason
+        );
+    }
+    println!(
+        "{} {} working log(s) and {} note(s) on missing objects, {} reclaimed",
+        if dry_run { "Would remove" } else { "Removed" },
+        report.working_logs.len(),
+        report.pruned_notes.len(),
+        format_bytes(report.reclaimed_bytes)
+    );
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KiB");
+        assert_eq!(format_bytes(3 * 1024 * 1024), "3.0 MiB");
+    }
+
+    #[test]
+    fn test_gc_prunes_unreachable_working_logs_and_orphaned_notes() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let base_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        // A commit that is abandoned, as by a rebase or reset
+        tmp_repo.write_file("b.txt", "two\n", true).unwrap();
+        tmp_repo.commit_with_message("Abandoned commit").unwrap();
+        let abandoned_sha = tmp_repo.get_head_commit_sha().unwrap();
+        tmp_repo
+            .git_command(&["reset", "--hard", &base_sha])
+            .unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        let missing_sha = "1234567890123456789012345678901234567890";
+        // "initial" holds checkpoints from before the first commit
+        for base_commit in [
+            &base_sha,
+            &abandoned_sha,
+            &missing_sha.to_string(),
+            &"initial".to_string(),
+        ] {
+            let working_log = repo.storage.working_log_for_base_commit(base_commit);
+            working_log.persist_file_version("content\n").unwrap();
+        }
+
+        // A note on a blob that is then deleted stands in for a garbage-collected commit
+        fs::write(tmp_repo.path().join("orphan.txt"), "orphan\n").unwrap();
+        tmp_repo
+            .git_command(&["hash-object", "-w", "orphan.txt"])
+            .unwrap();
+        let orphan_sha = "029e05d8c5005f4eb93c355e7e704c7cebc8fe3f"; // "orphan\n"
+        let orphan_object = tmp_repo
+            .path()
+            .join(".git/objects")
+            .join(&orphan_sha[..2])
+            .join(&orphan_sha[2..]);
+        crate::git::refs::notes_add(repo, orphan_sha, "orphaned note").unwrap();
+        crate::git::refs::notes_add(repo, &base_sha, "kept note").unwrap();
+        fs::remove_file(&orphan_object).unwrap();
+
+        let dry_run = run(
+            repo,
+            &GcOptions {
+                max_age: None,
+                dry_run: true,
+            },
+        )
+        .unwrap();
+        assert_eq!(dry_run.working_logs.len(), 3);
+        assert!(repo.storage.working_logs.join(&abandoned_sha).exists());
+
+        let report = run(repo, &GcOptions::default()).unwrap();
+        let mut pruned: Vec<(&str, PruneReason)> = report
+            .working_logs
+            .iter()
+            .map(|p| (p.base_commit.as_str(), p.reason))
+            .collect();
+        pruned.sort_by_key(|(sha, _)| sha.to_string());
+        let mut expected = vec![
+            (abandoned_sha.as_str(), PruneReason::UnreachableBaseCommit),
+            (missing_sha, PruneReason::MissingBaseCommit),
+            ("initial", PruneReason::UnreachableBaseCommit),
+        ];
+        expected.sort_by_key(|(sha, _)| sha.to_string());
+        assert_eq!(pruned, expected);
+        assert!(report.reclaimed_bytes > 0);
+        assert_eq!(report.pruned_notes, vec![orphan_sha.to_string()]);
+
+        assert!(repo.storage.working_logs.join(&base_sha).exists());
+        assert!(!repo.storage.working_logs.join(&abandoned_sha).exists());
+        assert!(crate::git::refs::show_authorship_note(repo, &base_sha).is_some());
+        // Notes on commits that still exist are kept, even if they are unreachable
+        let notes = crate::git::refs::list_authorship_notes(repo).unwrap();
+        assert!(notes.contains(&abandoned_sha));
+        assert!(!notes.contains(&orphan_sha.to_string()));
+
+        // Once HEAD moves on, the old base is only removed when it is stale
+        tmp_repo.commit_with_message("Next commit").unwrap();
+        assert!(
+            run(repo, &GcOptions::default())
+                .unwrap()
+                .working_logs
+                .is_empty()
+        );
+        let report = run(
+            repo,
+            &GcOptions {
+                max_age: Some(Duration::ZERO),
+                dry_run: false,
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            report.working_logs[0],
+            PrunedWorkingLog {
+                base_commit: base_sha.clone(),
+                reason: PruneReason::Stale,
+                bytes: report.working_logs[0].bytes,
+            }
+        );
+    }
+}
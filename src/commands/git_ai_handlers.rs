@@ -1,16 +1,16 @@
-use crate::authorship::stats::stats_command;
+use crate::authorship::stats::{PathBreakdown, stats_command, stats_range_command};
 use crate::authorship::working_log::AgentId;
 use crate::commands;
 use crate::commands::checkpoint_agent::agent_preset::{
-    AgentCheckpointFlags, AgentCheckpointPreset, AgentRunResult, ClaudePreset, CursorPreset,
-    GithubCopilotPreset,
+    AgentCheckpointFlags, AgentCheckpointPreset, AgentRunResult, AiderPreset, ClaudePreset,
+    CodexPreset, CursorPreset, GeminiPreset, GenericPreset, GithubCopilotPreset,
 };
 use crate::config;
 use crate::git::find_repository;
 use crate::git::find_repository_in_path;
 use crate::git::repository::exec_git;
 use crate::utils::Timer;
-use std::io::IsTerminal;
+use std::io::{IsTerminal, Read};
 
 pub fn handle_git_ai(args: &[String]) {
     if args.is_empty() {
@@ -33,6 +33,9 @@ pub fn handle_git_ai(args: &[String]) {
         "stats" => {
             handle_stats(&args[1..]);
         }
+        "stats-range" => {
+            handle_stats_range(&args[1..]);
+        }
         "stats-display" | "show-ai" => {
             handle_stats_display(&args[1..]);
         }
@@ -62,6 +65,27 @@ pub fn handle_git_ai(args: &[String]) {
         "squash-authorship" => {
             commands::squash_authorship::handle_squash_authorship(&args[1..]);
         }
+        "migrate-notes" => {
+            handle_migrate_notes(&args[1..]);
+        }
+        "fsck" => {
+            handle_fsck(&args[1..]);
+        }
+        "gc" => {
+            handle_gc(&args[1..]);
+        }
+        "verify-authorship" => {
+            handle_verify_authorship(&args[1..]);
+        }
+        "check" => {
+            handle_check(&args[1..]);
+        }
+        "reconcile" => {
+            handle_reconcile(&args[1..]);
+        }
+        "server-hook" => {
+            handle_server_hook(&args[1..]);
+        }
         _ => {
             println!("Unknown git-ai command: {}", args[0]);
             std::process::exit(1);
@@ -76,7 +100,7 @@ fn print_help() {
     eprintln!("");
     eprintln!("Commands:");
     eprintln!("  checkpoint         checkpoint working changes and specify author");
-    eprintln!("    Presets: github-copilot, cursor. Debug/Testing presets mock_ai");
+    eprintln!("    Presets: github-copilot, cursor, claude, aider, codex, gemini, generic. Debug/Testing presets mock_ai");
     eprintln!("    --show-working-log    Display current working log");
     eprintln!("    --reset               Reset working log");
     eprintln!("  blame              [override] git blame with AI authorship tracking");
@@ -86,6 +110,13 @@ fn print_help() {
     eprintln!("  stats              Show AI authorship statistics for a commit");
     eprintln!("    <commit>               Optional commit SHA (defaults to current HEAD)");
     eprintln!("    --json                 Output in JSON format");
+    eprintln!("    --by-file              Break additions down per file");
+    eprintln!("    --by-dir <depth>       Break additions down per directory, up to <depth> levels");
+    eprintln!("  stats-range        Show AI authorship statistics for a revision range (e.g. a PR)");
+    eprintln!("    <base>..<head>         Revision range (<base>...<head> diffs from the merge base)");
+    eprintln!("    --json                 Output in JSON format");
+    eprintln!("    --markdown             Output a Markdown summary for PR comments");
+    eprintln!("    --by-file | --by-dir <depth>  Break additions down per file or directory");
     eprintln!("  stats-display      Show prominent AI% display for a commit (alias: show-ai)");
     eprintln!("    <commit>               Optional commit SHA (defaults to current HEAD)");
     eprintln!("  stats-repo         Show aggregate AI% across entire repository history (alias: stats-aggregate)");
@@ -93,10 +124,33 @@ fn print_help() {
     eprintln!("    [--branch name]        Analyze specific branch (default: current branch)");
     eprintln!("    [--since date]         Only commits after date (e.g., '2024-01-01', '1 week ago')");
     eprintln!("    [--json]               Output in JSON format");
+    eprintln!("    [--by-file | --by-dir <depth>]  Break additions down per file or directory");
     eprintln!("  install-hooks      Install git hooks for AI authorship tracking");
     eprintln!("  squash-authorship  Generate authorship from squashed commits");
     eprintln!("    <branch> <new_sha> <old_sha>  Required: branch, new commit SHA, old commit SHA");
     eprintln!("    --dry-run             Show what would be done without making changes");
+    eprintln!("  migrate-notes      Rewrite authorship notes in the current schema");
+    eprintln!("    --range <range>       Only migrate notes on commits in a revision range");
+    eprintln!("    --dry-run             Report what would change without rewriting notes");
+    eprintln!("  fsck               Check authorship notes against the commits they annotate");
+    eprintln!("    --repair              Fix repairable issues (drops or trims invalid attributions)");
+    eprintln!("  gc                 Remove working logs and notes for commits that are gone (also runs after 'git gc')");
+    eprintln!("    --max-age-days <n>    Also remove working logs untouched for n days (default: working_log_max_age_days, 30)");
+    eprintln!("    --dry-run             Report what would be removed without removing it");
+    eprintln!("  verify-authorship  Check the signatures on authorship notes");
+    eprintln!("    <commit|range>        Commit or revision range (defaults to HEAD)");
+    eprintln!("  check              Enforce an AI contribution policy on a revision range (for CI)");
+    eprintln!("    --range <range>       Revision range to check (e.g. origin/main..HEAD)");
+    eprintln!("    --policy <file>       JSON policy file; violations are printed as JSON");
+    eprintln!("  reconcile          Rebuild authorship for a squash- or rebase-merged PR commit (for CI)");
+    eprintln!("    <commit>              Commit on the target branch (defaults to HEAD)");
+    eprintln!("    --pr <number>         PR number (defaults to the '(#123)' in the commit subject)");
+    eprintln!("    --head <ref>          Use this PR head instead of fetching refs/pull/<number>/head");
+    eprintln!("    --remote <name>       Remote to fetch from and push notes to");
+    eprintln!("    --no-push             Don't push the reconstructed notes");
+    eprintln!("    --dry-run             Show what would be done without making changes");
+    eprintln!("  server-hook        Run as a hook on a git server (works in bare repositories)");
+    eprintln!("    post-receive          Rebuild notes for rebased or squashed pushes (ref updates on stdin)");
     eprintln!("");
     std::process::exit(0);
 }
@@ -212,6 +266,69 @@ fn handle_checkpoint(args: &[String]) {
                     }
                 }
             }
+            "aider" => {
+                match AiderPreset.run(AgentCheckpointFlags {
+                    prompt_id: prompt_id.clone(),
+                    hook_input: hook_input.clone(),
+                }) {
+                    Ok(agent_run) => {
+                        agent_run_result = Some(agent_run);
+                    }
+                    Err(e) => {
+                        eprintln!("Aider preset error: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            "codex" => {
+                match CodexPreset.run(AgentCheckpointFlags {
+                    prompt_id: prompt_id.clone(),
+                    hook_input: hook_input.clone(),
+                }) {
+                    Ok(agent_run) => {
+                        agent_run_result = Some(agent_run);
+                    }
+                    Err(e) => {
+                        eprintln!("Codex preset error: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            "gemini" => {
+                match GeminiPreset.run(AgentCheckpointFlags {
+                    prompt_id: prompt_id.clone(),
+                    hook_input: hook_input.clone(),
+                }) {
+                    Ok(agent_run) => {
+                        if agent_run.is_human {
+                            agent_run_result = None;
+                            if let Some(dir) = agent_run.repo_working_dir {
+                                repository_working_dir = dir;
+                            }
+                        } else {
+                            agent_run_result = Some(agent_run);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("Gemini preset error: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
+            "generic" => {
+                match GenericPreset.run(AgentCheckpointFlags {
+                    prompt_id: prompt_id.clone(),
+                    hook_input: hook_input.clone(),
+                }) {
+                    Ok(agent_run) => {
+                        agent_run_result = Some(agent_run);
+                    }
+                    Err(e) => {
+                        eprintln!("Generic preset error: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            }
             "mock_ai" => {
                 agent_run_result = Some(AgentRunResult {
                     agent_id: AgentId {
@@ -222,6 +339,7 @@ fn handle_checkpoint(args: &[String]) {
                     is_human: false,
                     transcript: None,
                     repo_working_dir: None,
+                    edited_files: None,
                 });
             }
             _ => {}
@@ -340,10 +458,21 @@ fn handle_ai_blame(args: &[String]) {
     }
 }
 
+/// Parse the directory depth given to `--by-dir`, the first of `values`.
+fn parse_by_dir_flag(values: &[String]) -> Result<usize, String> {
+    let depth = values
+        .first()
+        .ok_or_else(|| "--by-dir requires a directory depth".to_string())?;
+    depth
+        .parse()
+        .map_err(|_| format!("--by-dir depth must be a number, got '{}'", depth))
+}
+
 fn handle_stats(args: &[String]) {
     // Parse stats-specific arguments
     let mut json_output = false;
     let mut commit_sha = None;
+    let mut breakdown = None;
 
     let mut i = 0;
     while i < args.len() {
@@ -352,6 +481,20 @@ fn handle_stats(args: &[String]) {
                 json_output = true;
                 i += 1;
             }
+            "--by-file" => {
+                breakdown = Some(PathBreakdown::File);
+                i += 1;
+            }
+            "--by-dir" => {
+                match parse_by_dir_flag(&args[i + 1..]) {
+                    Ok(depth) => breakdown = Some(PathBreakdown::Dir(depth)),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+                i += 2;
+            }
             _ => {
                 // First non-flag argument is treated as commit SHA
                 if commit_sha.is_none() {
@@ -374,7 +517,7 @@ fn handle_stats(args: &[String]) {
         }
     };
 
-    if let Err(e) = stats_command(&repo, commit_sha.as_deref(), json_output) {
+    if let Err(e) = stats_command(&repo, commit_sha.as_deref(), json_output, breakdown) {
         match e {
             crate::error::GitAiError::Generic(msg) if msg.starts_with("No commit found:") => {
                 eprintln!("{}", msg);
@@ -387,9 +530,394 @@ fn handle_stats(args: &[String]) {
     }
 }
 
+fn handle_stats_range(args: &[String]) {
+    // Parse stats-range-specific arguments
+    let mut json_output = false;
+    let mut markdown_output = false;
+    let mut range = None;
+    let mut breakdown = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--json" => {
+                json_output = true;
+                i += 1;
+            }
+            "--markdown" | "--md" => {
+                markdown_output = true;
+                i += 1;
+            }
+            "--by-file" => {
+                breakdown = Some(PathBreakdown::File);
+                i += 1;
+            }
+            "--by-dir" => {
+                match parse_by_dir_flag(&args[i + 1..]) {
+                    Ok(depth) => breakdown = Some(PathBreakdown::Dir(depth)),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+                i += 2;
+            }
+            _ => {
+                if range.is_none() && !args[i].starts_with('-') {
+                    range = Some(args[i].clone());
+                    i += 1;
+                } else {
+                    eprintln!("Unknown stats-range argument: {}", args[i]);
+                    std::process::exit(1);
+                }
+            }
+        }
+    }
+
+    let range = match range {
+        Some(range) => range,
+        None => {
+            eprintln!("Error: stats-range requires a revision range (e.g. main..HEAD)");
+            std::process::exit(1);
+        }
+    };
+
+    // Find the git repository
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    if let Err(e) = stats_range_command(&repo, &range, json_output, markdown_output, breakdown) {
+        match e {
+            crate::error::GitAiError::Generic(msg) if msg.starts_with("No commit found:") => {
+                eprintln!("{}", msg);
+            }
+            _ => {
+                eprintln!("Stats range failed: {}", e);
+            }
+        }
+        std::process::exit(1);
+    }
+}
+
+fn handle_migrate_notes(args: &[String]) {
+    let mut range = None;
+    let mut dry_run = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--range" => {
+                match args.get(i + 1) {
+                    Some(value) => range = Some(value.clone()),
+                    None => {
+                        eprintln!("Error: --range requires a revision range (e.g. main..HEAD)");
+                        std::process::exit(1);
+                    }
+                }
+                i += 2;
+            }
+            "--dry-run" => {
+                dry_run = true;
+                i += 1;
+            }
+            _ => {
+                eprintln!("Unknown migrate-notes argument: {}", args[i]);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match commands::migrate_notes::run(&repo, range.as_deref(), dry_run) {
+        Ok(report) => commands::migrate_notes::write_report_to_terminal(&report, dry_run),
+        Err(e) => {
+            eprintln!("Migrate notes failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_fsck(args: &[String]) {
+    let mut repair = false;
+    for arg in args {
+        match arg.as_str() {
+            "--repair" => repair = true,
+            _ => {
+                eprintln!("Unknown fsck argument: {}", arg);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match commands::fsck::run(&repo, repair) {
+        Ok(report) => {
+            commands::fsck::write_report_to_terminal(&report, repair);
+            if report.has_outstanding_issues() {
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("Fsck failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_gc(args: &[String]) {
+    let mut options = commands::gc::GcOptions {
+        max_age: commands::gc::configured_max_age(),
+        dry_run: false,
+    };
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--max-age-days" => {
+                let Some(days) = args.get(i + 1).and_then(|v| v.parse::<u64>().ok()) else {
+                    eprintln!("Error: --max-age-days requires a number of days");
+                    std::process::exit(1);
+                };
+                options.max_age = (days > 0)
+                    .then(|| std::time::Duration::from_secs(days * 24 * 60 * 60));
+                i += 2;
+            }
+            "--dry-run" => {
+                options.dry_run = true;
+                i += 1;
+            }
+            _ => {
+                eprintln!("Unknown gc argument: {}", args[i]);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match commands::gc::run(&repo, &options) {
+        Ok(report) => commands::gc::write_report_to_terminal(&report, options.dry_run),
+        Err(e) => {
+            eprintln!("Gc failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_check(args: &[String]) {
+    let mut range = None;
+    let mut policy_path = None;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--range" | "--policy" => {
+                let Some(value) = args.get(i + 1) else {
+                    eprintln!("Error: {} requires a value", args[i]);
+                    std::process::exit(1);
+                };
+                if args[i] == "--range" {
+                    range = Some(value.clone());
+                } else {
+                    policy_path = Some(value.clone());
+                }
+                i += 2;
+            }
+            _ => {
+                eprintln!("Unknown check argument: {}", args[i]);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let (Some(range), Some(policy_path)) = (range, policy_path) else {
+        eprintln!("Usage: git-ai-tracker check --range <base>..<head> --policy <policy.json>");
+        std::process::exit(1);
+    };
+
+    let policy = match commands::check::Policy::from_file(&policy_path) {
+        Ok(policy) => policy,
+        Err(e) => {
+            eprintln!("Failed to read policy {}: {}", policy_path, e);
+            std::process::exit(1);
+        }
+    };
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match commands::check::run(&repo, &range, &policy) {
+        Ok(report) => {
+            println!("{}", serde_json::to_string_pretty(&report).unwrap());
+            if !report.passed {
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("Check failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_verify_authorship(args: &[String]) {
+    if args.len() > 1 {
+        eprintln!("Usage: git-ai-tracker verify-authorship [<commit>|<base>..<head>]");
+        std::process::exit(1);
+    }
+    let spec = args.first().map(|s| s.as_str()).unwrap_or("HEAD");
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    match commands::verify_authorship::run(&repo, spec) {
+        Ok(results) => {
+            commands::verify_authorship::write_report_to_terminal(&results);
+            if !commands::verify_authorship::all_signed(&results) {
+                std::process::exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("Verify authorship failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_reconcile(args: &[String]) {
+    let mut commit = None;
+    let mut options = commands::reconcile::ReconcileOptions {
+        push: true,
+        ..Default::default()
+    };
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--pr" | "--head" | "--remote" => {
+                let Some(value) = args.get(i + 1) else {
+                    eprintln!("Error: {} requires a value", args[i]);
+                    std::process::exit(1);
+                };
+                match args[i].as_str() {
+                    "--pr" => match value.trim_start_matches('#').parse() {
+                        Ok(number) => options.pr_number = Some(number),
+                        Err(_) => {
+                            eprintln!("Error: --pr requires a number, got '{}'", value);
+                            std::process::exit(1);
+                        }
+                    },
+                    "--head" => options.pr_head = Some(value.clone()),
+                    _ => options.remote = Some(value.clone()),
+                }
+                i += 2;
+            }
+            "--no-push" => {
+                options.push = false;
+                i += 1;
+            }
+            "--dry-run" => {
+                options.dry_run = true;
+                i += 1;
+            }
+            arg if !arg.starts_with('-') && commit.is_none() => {
+                commit = Some(arg.to_string());
+                i += 1;
+            }
+            _ => {
+                eprintln!("Unknown reconcile argument: {}", args[i]);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let commit = commit.unwrap_or_else(|| "HEAD".to_string());
+    match commands::reconcile::run(&repo, &commit, &options) {
+        Ok(report) => commands::reconcile::write_report_to_terminal(&report, options.dry_run),
+        Err(e) => {
+            eprintln!("Reconcile failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_server_hook(args: &[String]) {
+    if args.len() != 1 || args[0] != "post-receive" {
+        eprintln!("Usage: git-ai-tracker server-hook post-receive");
+        std::process::exit(1);
+    }
+
+    let mut input = String::new();
+    if let Err(e) = std::io::stdin().read_to_string(&mut input) {
+        eprintln!("Failed to read ref updates from stdin: {}", e);
+        std::process::exit(1);
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            eprintln!("Failed to find repository: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let updates = commands::server_hook::parse_ref_updates(&input);
+    match commands::server_hook::post_receive(&repo, &updates) {
+        Ok(report) => commands::server_hook::write_report_to_terminal(&report),
+        Err(e) => {
+            eprintln!("git-ai-tracker post-receive failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
 fn handle_stats_display(args: &[String]) {
-    use crate::authorship::stats::stats_for_commit_stats;
-    use crate::git::refs::show_authorship_note;
+    use crate::authorship::stats::{
+        contribution_summary, stats_for_commit_stats, stored_contribution_summary,
+    };
     
     // Find the repository starting from the current directory
     let repo = match find_repository(&Vec::<String>::new()) {
@@ -436,22 +964,21 @@ fn handle_stats_display(args: &[String]) {
         }
     };
 
-    // Get stats
-    let stats = match stats_for_commit_stats(&repo, &target, &refname) {
-        Ok(s) => s,
-        Err(e) => {
-            eprintln!("Error getting stats: {}", e);
-            std::process::exit(1);
-        }
+    // Prefer the summary recorded in the note; only re-diff when it is missing or stale
+    let stored_summary = stored_contribution_summary(&repo, &target);
+    let summary = match &stored_summary {
+        Some(summary) => summary.clone(),
+        None => match stats_for_commit_stats(&repo, &target, &refname) {
+            Ok(stats) => contribution_summary(&target, &stats),
+            Err(e) => {
+                eprintln!("Error getting stats: {}", e);
+                std::process::exit(1);
+            }
+        },
     };
 
-    // Calculate AI percentage
-    let total_additions = stats.git_diff_added_lines;
-    let ai_percentage = if total_additions > 0 {
-        ((stats.ai_additions as f64 / total_additions as f64) * 100.0).round() as u32
-    } else {
-        0
-    };
+    let total_additions = summary.total_lines;
+    let ai_percentage = summary.ai_percentage;
 
     // Display the AI% prominently!
     println!("\n╔══════════════════════════════════════════════════════════╗");
@@ -473,27 +1000,18 @@ fn handle_stats_display(args: &[String]) {
     println!("║  📊 Breakdown:                                           ║");
     println!("║                                                          ║");
     println!("║    AI Lines:       {:>4} / {:<4} ({:>3}%)                  ║", 
-        stats.ai_additions, total_additions, ai_percentage);
+        summary.ai_lines, total_additions, ai_percentage);
     println!("║    Human Lines:    {:>4} / {:<4} ({:>3}%)                  ║", 
-        stats.human_additions, total_additions, 
-        if total_additions > 0 { ((stats.human_additions as f64 / total_additions as f64) * 100.0).round() as u32 } else { 0 });
-    println!("║    Mixed Lines:    {:>4}                                  ║", stats.mixed_additions);
+        summary.human_lines, total_additions, 
+        if total_additions > 0 { ((summary.human_lines as f64 / total_additions as f64) * 100.0).round() as u32 } else { 0 });
+    println!("║    Mixed Lines:    {:>4}                                  ║", summary.mixed_lines);
     println!("║                                                          ║");
     println!("╚══════════════════════════════════════════════════════════╝");
     println!("");
 
-    // Show raw metadata from notes if available
-    if let Some(notes) = show_authorship_note(&repo, &target) {
-        if notes.contains("--- AI Contribution Metadata ---") {
-            println!("📝 Git Notes Metadata:");
-            println!("");
-            for line in notes.lines() {
-                if line.starts_with("AI-") || line.starts_with("Human-") || line.starts_with("Mixed-") || line.starts_with("Generated:") {
-                    println!("   {}", line);
-                }
-            }
-            println!("");
-        }
+    if stored_summary.is_some() {
+        println!("📝 Read from the commit's git note (refs/notes/ai)");
+        println!("");
     }
 
     println!("💡 Tip: Use 'git notes show {}' to see full authorship data", &target[..8.min(target.len())]);
@@ -501,7 +1019,9 @@ fn handle_stats_display(args: &[String]) {
 }
 
 fn handle_stats_repo(args: &[String]) {
-    use crate::authorship::stats::stats_for_commit_stats;
+    use crate::authorship::stats::{
+        group_agent_stats, group_path_stats, stats_for_commit_stats, write_path_stats_to_terminal,
+    };
     use serde_json::json;
 
     // Parse arguments
@@ -509,6 +1029,7 @@ fn handle_stats_repo(args: &[String]) {
     let mut branch: Option<String> = None;
     let mut since: Option<String> = None;
     let mut json_output = false;
+    let mut breakdown = None;
 
     let mut i = 0;
     while i < args.len() {
@@ -544,6 +1065,20 @@ fn handle_stats_repo(args: &[String]) {
                 json_output = true;
                 i += 1;
             }
+            "--by-file" => {
+                breakdown = Some(PathBreakdown::File);
+                i += 1;
+            }
+            "--by-dir" => {
+                match parse_by_dir_flag(&args[i + 1..]) {
+                    Ok(depth) => breakdown = Some(PathBreakdown::Dir(depth)),
+                    Err(e) => {
+                        eprintln!("Error: {}", e);
+                        std::process::exit(1);
+                    }
+                }
+                i += 2;
+            }
             _ => {
                 eprintln!("Unknown argument: {}", args[i]);
                 std::process::exit(1);
@@ -605,6 +1140,9 @@ fn handle_stats_repo(args: &[String]) {
     let mut total_additions = 0;
 
     let mut commit_details = Vec::new();
+    let mut file_stats = Vec::new();
+    let mut tool_stats = Vec::new();
+    let mut model_stats = Vec::new();
 
     // Parse commits (format: hash\nmessage\n)
     let mut i = 0;
@@ -641,6 +1179,12 @@ fn handle_stats_repo(args: &[String]) {
             total_mixed_lines += mixed_adds;
             total_additions += diff_adds;
 
+            if breakdown.is_some() {
+                file_stats.extend(stats.files);
+            }
+            tool_stats.extend(stats.by_tool);
+            model_stats.extend(stats.by_model);
+
             if json_output {
                 let ai_pct = if diff_adds > 0 {
                     ((ai_adds as f64 / diff_adds as f64) * 100.0).round() as u32
@@ -674,8 +1218,23 @@ fn handle_stats_repo(args: &[String]) {
         0
     };
 
+    let path_stats = match breakdown {
+        Some(breakdown) => group_path_stats(&file_stats, breakdown),
+        None => Vec::new(),
+    };
+    let by_tool = group_agent_stats(
+        tool_stats
+            .iter()
+            .map(|a| (a.name.as_str(), a.ai_additions, a.mixed_additions)),
+    );
+    let by_model = group_agent_stats(
+        model_stats
+            .iter()
+            .map(|a| (a.name.as_str(), a.ai_additions, a.mixed_additions)),
+    );
+
     if json_output {
-        let output = json!({
+        let mut output = json!({
             "summary": {
                 "total_commits": total_commits,
                 "commits_with_ai": commits_with_ai,
@@ -686,8 +1245,13 @@ fn handle_stats_repo(args: &[String]) {
                 "total_mixed_lines": total_mixed_lines,
                 "total_additions": total_additions,
             },
+            "by_tool": by_tool,
+            "by_model": by_model,
             "commits": commit_details,
         });
+        if breakdown.is_some() {
+            output["files"] = json!(path_stats);
+        }
         println!("{}", serde_json::to_string_pretty(&output).unwrap());
     } else {
         // Pretty printed output
@@ -739,6 +1303,25 @@ fn handle_stats_repo(args: &[String]) {
         println!("╚══════════════════════════════════════════════════════════════════════════╝");
         println!("");
 
+        for (label, agents) in [("Tool", &by_tool), ("Model", &by_model)] {
+            if agents.is_empty() {
+                continue;
+            }
+            println!("{:<24}  {:>7}  {:>7}", label, "ai", "mixed");
+            for agent in agents {
+                println!(
+                    "{:<24}  {:>7}  {:>7}",
+                    agent.name, agent.ai_additions, agent.mixed_additions
+                );
+            }
+            println!();
+        }
+
+        if breakdown.is_some() {
+            write_path_stats_to_terminal(&path_stats);
+            println!();
+        }
+
         if total_commits == 0 {
             println!("⚠️  No commits found with AI tracking data.");
             println!("   Make sure you've committed code with git-ai-tracker active!");
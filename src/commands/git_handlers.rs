@@ -1,10 +1,13 @@
 use crate::commands::hooks::cherry_pick_hooks;
+use crate::commands::hooks::clone_hooks;
 use crate::commands::hooks::commit_hooks;
 use crate::commands::hooks::fetch_hooks;
+use crate::commands::hooks::gc_hooks;
 use crate::commands::hooks::merge_hooks;
 use crate::commands::hooks::push_hooks;
 use crate::commands::hooks::rebase_hooks;
 use crate::commands::hooks::reset_hooks;
+use crate::commands::hooks::stash_hooks;
 use crate::config;
 use crate::git::cli_parser::{ParsedGitInvocation, parse_git_cli_args};
 use crate::git::find_repository;
@@ -59,6 +62,7 @@ pub struct CommandHooksContext {
     pub pre_commit_hook_result: Option<bool>,
     pub rebase_original_head: Option<String>,
     pub _rebase_onto: Option<String>,
+    pub stash_sha: Option<String>,
 }
 
 pub fn handle_git(args: &[String]) {
@@ -74,6 +78,7 @@ pub fn handle_git(args: &[String]) {
         pre_commit_hook_result: None,
         rebase_original_head: None,
         _rebase_onto: None,
+        stash_sha: None,
     };
 
     let parsed_args = parse_git_cli_args(args);
@@ -124,6 +129,11 @@ pub fn handle_git(args: &[String]) {
         Timer::default()
             .print_duration("git-ai hooks", pre_command_duration + post_command_duration);
 
+        exit_status
+    } else if !parsed_args.is_help && parsed_args.command.as_deref() == Some("clone") {
+        // Clone usually runs outside any repository; its hook finds the new one
+        let exit_status = proxy_to_git(&parsed_args.to_invocation_vec(), false);
+        clone_hooks::clone_post_command_hook(&parsed_args, exit_status);
         exit_status
     } else {
         Timer::default().print_duration("git-ai proxy overhead", Timer::default().epoch.elapsed());
@@ -154,6 +164,9 @@ fn run_pre_command_hooks(
         Some("cherry-pick") => {
             cherry_pick_hooks::pre_cherry_pick_hook(parsed_args, repository, command_hooks_context);
         }
+        Some("stash") => {
+            stash_hooks::pre_stash_hook(parsed_args, repository, command_hooks_context);
+        }
         _ => {}
     }
 }
@@ -172,8 +185,12 @@ fn run_post_command_hooks(
             repository,
             command_hooks_context,
         ),
-        Some("fetch") => fetch_hooks::fetch_post_command_hook(parsed_args, exit_status),
+        Some("fetch") | Some("pull") => {
+            fetch_hooks::fetch_post_command_hook(parsed_args, exit_status)
+        }
+        Some("clone") => clone_hooks::clone_post_command_hook(parsed_args, exit_status),
         Some("push") => push_hooks::push_post_command_hook(parsed_args, exit_status),
+        Some("gc") => gc_hooks::post_gc_hook(parsed_args, exit_status, repository),
         Some("reset") => reset_hooks::post_reset_hook(parsed_args, repository, exit_status),
         Some("merge") => merge_hooks::post_merge_hook(parsed_args, exit_status, repository),
         Some("rebase") => rebase_hooks::handle_rebase_post_command(
@@ -188,6 +205,12 @@ fn run_post_command_hooks(
             exit_status,
             repository,
         ),
+        Some("stash") => stash_hooks::post_stash_hook(
+            command_hooks_context,
+            parsed_args,
+            repository,
+            exit_status,
+        ),
         _ => {}
     }
 }
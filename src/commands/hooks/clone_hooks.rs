@@ -0,0 +1,176 @@
+use crate::commands::hooks::fetch_hooks::fetch_authorship_notes;
+use crate::config::Config;
+use crate::git::cli_parser::ParsedGitInvocation;
+use crate::git::find_repository;
+use crate::utils::debug_log;
+
+/// Clone options whose value is the following argument
+const OPTIONS_WITH_VALUE: &[&str] = &[
+    "-o",
+    "--origin",
+    "-b",
+    "--branch",
+    "-u",
+    "--upload-pack",
+    "--reference",
+    "--reference-if-able",
+    "--separate-git-dir",
+    "--depth",
+    "--shallow-since",
+    "--shallow-exclude",
+    "-c",
+    "--config",
+    "--server-option",
+    "-j",
+    "--jobs",
+    "--filter",
+    "--template",
+    "--bundle-uri",
+    "--ref-format",
+];
+
+/// Fetch the remote's authorship notes into a freshly cloned repository, so blame shows AI
+/// attribution straight away instead of after the first explicit fetch.
+pub fn clone_post_command_hook(
+    parsed_args: &ParsedGitInvocation,
+    exit_status: std::process::ExitStatus,
+) {
+    if !exit_status.success() {
+        return;
+    }
+
+    let Some(target_dir) = clone_target_dir(&parsed_args.command_args) else {
+        debug_log("could not determine clone directory; skipping authorship fetch");
+        return;
+    };
+
+    // Resolve the new repository the same way git resolved the clone directory
+    let mut global_args = parsed_args.global_args.clone();
+    global_args.push("-C".to_string());
+    global_args.push(target_dir);
+
+    let repository = match find_repository(&global_args) {
+        Ok(repo) => Some(repo),
+        Err(e) => {
+            debug_log(&format!("cloned repository not found: {}", e));
+            return;
+        }
+    };
+    if !Config::get().is_allowed_repository(&repository) {
+        debug_log("Skipping authorship fetch because the cloned repository is not allowed");
+        return;
+    }
+
+    if let Some(repo) = &repository {
+        let remote = clone_origin_name(&parsed_args.command_args);
+        fetch_authorship_notes(&global_args, repo, &remote);
+    }
+}
+
+/// The directory `git clone` creates: the explicit `<directory>` argument, or the name git
+/// derives from the repository URL
+fn clone_target_dir(args: &[String]) -> Option<String> {
+    let mut positionals = Vec::new();
+    let mut bare = false;
+    let mut after_double_dash = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        if after_double_dash || !arg.starts_with('-') {
+            positionals.push(arg);
+        } else if arg == "--" {
+            after_double_dash = true;
+        } else if arg == "--bare" || arg == "--mirror" {
+            bare = true;
+        } else if OPTIONS_WITH_VALUE.contains(&arg) {
+            i += 1;
+        }
+        i += 1;
+    }
+
+    match positionals.as_slice() {
+        [_, directory, ..] => Some(directory.to_string()),
+        [repository] => guess_dir_name(repository, bare),
+        [] => None,
+    }
+}
+
+/// Mirrors git's `guess_dir_name`: the last path component of the URL without `.git`
+/// (kept, or added, for bare clones)
+fn guess_dir_name(repository: &str, bare: bool) -> Option<String> {
+    let trimmed = repository.trim_end_matches(['/', '\\']);
+    let trimmed = trimmed.strip_suffix("/.git").unwrap_or(trimmed);
+    let trimmed = trimmed.strip_suffix(".git").unwrap_or(trimmed);
+    let trimmed = trimmed.strip_suffix(".bundle").unwrap_or(trimmed);
+
+    let name = trimmed.rsplit(['/', '\\', ':']).next()?;
+    if name.is_empty() {
+        return None;
+    }
+    Some(if bare {
+        format!("{}.git", name)
+    } else {
+        name.to_string()
+    })
+}
+
+fn clone_origin_name(args: &[String]) -> String {
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        if arg == "--" {
+            break;
+        }
+        if let Some(name) = arg.strip_prefix("--origin=") {
+            return name.to_string();
+        }
+        if (arg == "-o" || arg == "--origin")
+            && let Some(name) = args.get(i + 1)
+        {
+            return name.clone();
+        }
+        i += 1;
+    }
+    "origin".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_clone_target_dir() {
+        assert_eq!(
+            clone_target_dir(&args(&["https://github.com/org/repo.git"])),
+            Some("repo".to_string())
+        );
+        assert_eq!(
+            clone_target_dir(&args(&["--bare", "git@github.com:org/repo.git"])),
+            Some("repo.git".to_string())
+        );
+        assert_eq!(
+            clone_target_dir(&args(&["-b", "main", "/srv/repo/.git/", "checkout"])),
+            Some("checkout".to_string())
+        );
+        assert_eq!(
+            clone_target_dir(&args(&["--depth=1", "--origin", "upstream", "../repo"])),
+            Some("repo".to_string())
+        );
+        assert_eq!(clone_target_dir(&args(&["--quiet"])), None);
+    }
+
+    #[test]
+    fn test_clone_origin_name() {
+        assert_eq!(clone_origin_name(&args(&["repo"])), "origin");
+        assert_eq!(
+            clone_origin_name(&args(&["-o", "upstream", "repo"])),
+            "upstream"
+        );
+        assert_eq!(clone_origin_name(&args(&["--origin=fork", "repo"])), "fork");
+    }
+}
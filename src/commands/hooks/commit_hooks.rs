@@ -84,65 +84,6 @@ pub fn commit_post_command_hook(
             true,
         );
     }
-
-    // NEW FEATURE: Append AI% metadata to git notes
-    if let Some(ref sha) = new_sha {
-        if let Err(e) = append_ai_percentage_to_notes(repository, sha) {
-            // Don't fail the commit if this fails, just warn
-            debug_log(&format!("Warning: Failed to append AI percentage to notes: {}", e));
-        }
-    }
-}
-
-/// Appends AI percentage metadata to the git notes for a commit
-/// This is the showcase feature that automatically tracks AI contribution percentage!
-pub fn append_ai_percentage_to_notes(
-    repository: &Repository,
-    commit_sha: &str,
-) -> Result<(), crate::error::GitAiError> {
-    use crate::authorship::stats::stats_for_commit_stats;
-    use crate::git::refs::{show_authorship_note, notes_add};
-    
-    // Get AI stats for this commit
-    let stats = match stats_for_commit_stats(repository, commit_sha, "HEAD") {
-        Ok(s) => s,
-        Err(_) => {
-            // If we can't get stats (e.g., no authorship log yet), skip silently
-            return Ok(());
-        }
-    };
-    
-    // Calculate AI percentage
-    let total_additions = stats.git_diff_added_lines;
-    let ai_percentage = if total_additions > 0 {
-        ((stats.ai_additions as f64 / total_additions as f64) * 100.0).round() as u32
-    } else {
-        0
-    };
-    
-    // Read existing notes (which contains the authorship log)
-    let existing_notes = show_authorship_note(repository, commit_sha)
-        .unwrap_or_default();
-    
-    // Create AI% metadata section
-    let ai_metadata = format!(
-        "\n\n--- AI Contribution Metadata ---\nAI-Percentage: {}%\nAI-Lines: {}/{}\nHuman-Lines: {}/{}\nMixed-Lines: {}\nGenerated: {}",
-        ai_percentage,
-        stats.ai_additions,
-        total_additions,
-        stats.human_additions,
-        total_additions,
-        stats.mixed_additions,
-        chrono::Utc::now().to_rfc3339()
-    );
-    
-    // Append metadata to existing notes
-    let updated_notes = format!("{}{}", existing_notes, ai_metadata);
-    
-    // Write back to notes
-    notes_add(repository, commit_sha, &updated_notes)?;
-    
-    Ok(())
 }
 
 pub fn get_commit_default_author(repo: &Repository, args: &[String]) -> String {
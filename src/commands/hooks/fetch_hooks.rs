@@ -1,7 +1,10 @@
 use crate::git::cli_parser::{ParsedGitInvocation, is_dry_run};
 use crate::git::find_repository;
-use crate::git::refs::{copy_ref, merge_notes_from_ref, ref_exists, tracking_ref_for_remote};
-use crate::git::repository::exec_git;
+use crate::git::refs::{
+    AI_PROMPTS_REF, AI_PROMPTS_REFNAME, copy_ref, merge_notes_from_ref, merge_notes_into,
+    notes_remote_for, prompts_tracking_ref_for_remote, ref_exists, tracking_ref_for_remote,
+};
+use crate::git::repository::{Repository, exec_git};
 use crate::utils::debug_log;
 
 pub fn fetch_post_command_hook(
@@ -47,62 +50,112 @@ pub fn fetch_post_command_hook(
         .or_else(|| repo.get_default_remote().ok().flatten());
 
     if let Some(remote) = remote {
-        // Generate tracking ref for this remote
-        let tracking_ref = tracking_ref_for_remote(&remote);
-        let fetch_refspec = format!("+refs/notes/ai:{}", tracking_ref);
-
-        // Build the internal authorship fetch with explicit flags and disabled hooks
-        // IMPORTANT: run in the same repo context by prefixing original global args (e.g., -C <path>)
-        let mut fetch_authorship: Vec<String> = parsed_args.global_args.clone();
-        fetch_authorship.push("-c".to_string());
-        fetch_authorship.push("core.hooksPath=/dev/null".to_string());
-        fetch_authorship.push("fetch".to_string());
-        fetch_authorship.push("--no-tags".to_string());
-        fetch_authorship.push("--recurse-submodules=no".to_string());
-        fetch_authorship.push("--no-write-fetch-head".to_string());
-        fetch_authorship.push("--no-write-commit-graph".to_string());
-        fetch_authorship.push("--no-auto-maintenance".to_string());
-        fetch_authorship.push(remote.clone());
-        fetch_authorship.push(fetch_refspec.clone());
-
-        debug_log(&format!(
-            "fetching authorship refs: {:?}",
-            &fetch_authorship
-        ));
-
-        if let Err(e) = exec_git(&fetch_authorship) {
-            // Treat as best-effort; do not fail the user command if authorship sync fails
-            debug_log(&format!("authorship fetch skipped due to error: {}", e));
-            return;
-        }
+        fetch_authorship_notes(&parsed_args.global_args, &repo, &remote);
+    } else {
+        // No remotes to sync from; silently skip
+        debug_log("no remotes found for authorship fetch; skipping");
+    }
+}
 
-        // After successful fetch, merge the tracking ref into refs/notes/ai
-        let local_notes_ref = "refs/notes/ai";
-
-        if ref_exists(&repo, &tracking_ref) {
-            if ref_exists(&repo, local_notes_ref) {
-                // Both exist - merge them
-                debug_log(&format!(
-                    "merging {} into {}",
-                    tracking_ref, local_notes_ref
-                ));
-                if let Err(e) = merge_notes_from_ref(&repo, &tracking_ref) {
-                    debug_log(&format!("notes merge failed: {}", e));
-                }
-            } else {
-                // Only tracking ref exists - copy it to local
-                debug_log(&format!(
-                    "initializing {} from {}",
-                    local_notes_ref, tracking_ref
-                ));
-                if let Err(e) = copy_ref(&repo, &tracking_ref, local_notes_ref) {
-                    debug_log(&format!("notes copy failed: {}", e));
-                }
+/// Fetch the notes remote's authorship notes and merge them into refs/notes/ai, along with
+/// the prompt store. The notes remote is `remote` unless the repository sets
+/// `git-ai-tracker.notesRemote`. Best-effort: failures are logged, never returned.
+pub fn fetch_authorship_notes(global_args: &[String], repo: &Repository, remote: &str) {
+    let notes_remote = notes_remote_for(repo, remote);
+    let remote = notes_remote.as_str();
+
+    // Generate tracking ref for this remote
+    let tracking_ref = tracking_ref_for_remote(remote);
+    let fetch_refspec = format!("+refs/notes/ai:{}", tracking_ref);
+
+    // Build the internal authorship fetch with explicit flags and disabled hooks
+    // IMPORTANT: run in the same repo context by prefixing original global args (e.g., -C <path>)
+    let mut fetch_authorship: Vec<String> = global_args.to_vec();
+    fetch_authorship.push("-c".to_string());
+    fetch_authorship.push("core.hooksPath=/dev/null".to_string());
+    fetch_authorship.push("fetch".to_string());
+    fetch_authorship.push("--no-tags".to_string());
+    fetch_authorship.push("--recurse-submodules=no".to_string());
+    fetch_authorship.push("--no-write-fetch-head".to_string());
+    fetch_authorship.push("--no-write-commit-graph".to_string());
+    fetch_authorship.push("--no-auto-maintenance".to_string());
+    fetch_authorship.push(remote.to_string());
+    fetch_authorship.push(fetch_refspec.clone());
+
+    debug_log(&format!(
+        "fetching authorship refs: {:?}",
+        &fetch_authorship
+    ));
+
+    if let Err(e) = exec_git(&fetch_authorship) {
+        // Treat as best-effort; do not fail the user command if authorship sync fails
+        debug_log(&format!("authorship fetch skipped due to error: {}", e));
+        return;
+    }
+
+    // After successful fetch, merge the tracking ref into refs/notes/ai
+    let local_notes_ref = "refs/notes/ai";
+
+    if ref_exists(repo, &tracking_ref) {
+        if ref_exists(repo, local_notes_ref) {
+            // Both exist - merge them
+            debug_log(&format!(
+                "merging {} into {}",
+                tracking_ref, local_notes_ref
+            ));
+            if let Err(e) = merge_notes_from_ref(repo, &tracking_ref) {
+                debug_log(&format!("notes merge failed: {}", e));
+            }
+        } else {
+            // Only tracking ref exists - copy it to local
+            debug_log(&format!(
+                "initializing {} from {}",
+                local_notes_ref, tracking_ref
+            ));
+            if let Err(e) = copy_ref(repo, &tracking_ref, local_notes_ref) {
+                debug_log(&format!("notes copy failed: {}", e));
             }
         }
+    }
+
+    // Transcripts referenced by the notes live in the prompt store
+    fetch_prompt_store(global_args, repo, remote);
+}
+
+/// Fetch the remote's prompt store (refs/notes/ai-prompts) and merge it into the local one.
+/// Best-effort: remotes without a prompt store (older clients) are skipped silently.
+pub fn fetch_prompt_store(global_args: &[String], repo: &Repository, remote: &str) {
+    let tracking_ref = prompts_tracking_ref_for_remote(remote);
+
+    let mut fetch_prompts: Vec<String> = global_args.to_vec();
+    fetch_prompts.push("-c".to_string());
+    fetch_prompts.push("core.hooksPath=/dev/null".to_string());
+    fetch_prompts.push("fetch".to_string());
+    fetch_prompts.push("--no-tags".to_string());
+    fetch_prompts.push("--recurse-submodules=no".to_string());
+    fetch_prompts.push("--no-write-fetch-head".to_string());
+    fetch_prompts.push("--no-write-commit-graph".to_string());
+    fetch_prompts.push("--no-auto-maintenance".to_string());
+    fetch_prompts.push(remote.to_string());
+    fetch_prompts.push(format!("+{}:{}", AI_PROMPTS_REF, tracking_ref));
+
+    debug_log(&format!("fetching prompt store: {:?}", &fetch_prompts));
+
+    if let Err(e) = exec_git(&fetch_prompts) {
+        debug_log(&format!("prompt store fetch skipped due to error: {}", e));
+        return;
+    }
+
+    if !ref_exists(repo, &tracking_ref) {
+        return;
+    }
+    let result = if ref_exists(repo, AI_PROMPTS_REF) {
+        merge_notes_into(repo, AI_PROMPTS_REFNAME, &tracking_ref)
     } else {
-        // No remotes to sync from; silently skip
-        debug_log("no remotes found for authorship fetch; skipping");
+        copy_ref(repo, &tracking_ref, AI_PROMPTS_REF)
+    };
+    if let Err(e) = result {
+        debug_log(&format!("prompt store merge failed: {}", e));
     }
 }
 
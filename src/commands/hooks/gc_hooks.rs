@@ -0,0 +1,30 @@
+use crate::commands::gc::{self, GcOptions};
+use crate::git::cli_parser::ParsedGitInvocation;
+use crate::git::repository::Repository;
+use crate::utils::debug_log;
+
+/// After `git gc`, drop the working logs and notes whose commits it may just have removed.
+/// Quiet and best-effort: errors are logged, never returned.
+pub fn post_gc_hook(
+    parsed_args: &ParsedGitInvocation,
+    exit_status: std::process::ExitStatus,
+    repository: &Repository,
+) {
+    if !exit_status.success() || parsed_args.is_help {
+        return;
+    }
+
+    let options = GcOptions {
+        max_age: gc::configured_max_age(),
+        dry_run: false,
+    };
+    match gc::run(repository, &options) {
+        Ok(report) => debug_log(&format!(
+            "gc: removed {} working log(s) and {} note(s), {} bytes",
+            report.working_logs.len(),
+            report.pruned_notes.len(),
+            report.reclaimed_bytes
+        )),
+        Err(e) => debug_log(&format!("gc of authorship data failed: {}", e)),
+    }
+}
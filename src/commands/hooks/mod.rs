@@ -1,7 +1,10 @@
 pub mod cherry_pick_hooks;
+pub mod clone_hooks;
 pub mod commit_hooks;
 pub mod fetch_hooks;
+pub mod gc_hooks;
 pub mod merge_hooks;
 pub mod push_hooks;
 pub mod rebase_hooks;
 pub mod reset_hooks;
+pub mod stash_hooks;
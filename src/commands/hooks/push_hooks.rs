@@ -1,10 +1,35 @@
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::commands::hooks::fetch_hooks::fetch_prompt_store;
+use crate::error::GitAiError;
 use crate::git::cli_parser::{ParsedGitInvocation, is_dry_run};
 use crate::git::find_repository;
 use crate::git::refs::{
-    AI_AUTHORSHIP_PUSH_REFSPEC, copy_ref, merge_notes_from_ref, ref_exists, tracking_ref_for_remote,
+    AI_AUTHORSHIP_PUSH_REFSPEC, AI_PROMPTS_PUSH_REFSPEC, AI_PROMPTS_REF, copy_ref,
+    list_authorship_notes, merge_notes_from_ref, notes_commit_for_objects, notes_remote_for,
+    prompts_tracking_ref_for_remote, ref_exists, show_authorship_note, tracking_ref_for_remote,
 };
-use crate::git::repository::exec_git;
+use crate::git::repository::{Repository, exec_git};
 use crate::utils::debug_log;
+use std::collections::HashSet;
+
+/// Which authorship notes a push publishes, from the repository's `git-ai-tracker.notesPush`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NotesPushMode {
+    /// All of refs/notes/ai (the default)
+    All,
+    /// Only notes on commits reachable from the pushed refs, so notes for unpublished local
+    /// branches stay local
+    Reachable,
+}
+
+impl NotesPushMode {
+    pub fn for_repo(repo: &Repository) -> Self {
+        match repo.config_get_str("git-ai-tracker.notesPush") {
+            Ok(Some(mode)) if mode.eq_ignore_ascii_case("reachable") => NotesPushMode::Reachable,
+            _ => NotesPushMode::All,
+        }
+    }
+}
 
 pub fn push_post_command_hook(
     parsed_args: &ParsedGitInvocation,
@@ -56,72 +81,9 @@ pub fn push_post_command_hook(
         .or_else(|| repo.get_default_remote().ok().flatten());
 
     if let Some(remote) = remote {
-        // STEP 1: Fetch remote notes into tracking ref and merge before pushing
-        // This ensures we don't lose notes from other branches/clones
-        let tracking_ref = tracking_ref_for_remote(&remote);
-        let fetch_refspec = format!("+refs/notes/ai:{}", tracking_ref);
-
-        let mut fetch_before_push: Vec<String> = parsed_args.global_args.clone();
-        fetch_before_push.push("-c".to_string());
-        fetch_before_push.push("core.hooksPath=/dev/null".to_string());
-        fetch_before_push.push("fetch".to_string());
-        fetch_before_push.push("--no-tags".to_string());
-        fetch_before_push.push("--recurse-submodules=no".to_string());
-        fetch_before_push.push("--no-write-fetch-head".to_string());
-        fetch_before_push.push("--no-write-commit-graph".to_string());
-        fetch_before_push.push("--no-auto-maintenance".to_string());
-        fetch_before_push.push(remote.clone());
-        fetch_before_push.push(fetch_refspec);
-
-        debug_log(&format!(
-            "pre-push authorship fetch: {:?}",
-            &fetch_before_push
-        ));
-
-        // Fetch is best-effort; if it fails (e.g., no remote notes yet), continue
-        if exec_git(&fetch_before_push).is_ok() {
-            // Merge fetched notes into local refs/notes/ai
-            let local_notes_ref = "refs/notes/ai";
-
-            if ref_exists(&repo, &tracking_ref) {
-                if ref_exists(&repo, local_notes_ref) {
-                    // Both exist - merge them
-                    debug_log(&format!(
-                        "pre-push: merging {} into {}",
-                        tracking_ref, local_notes_ref
-                    ));
-                    if let Err(e) = merge_notes_from_ref(&repo, &tracking_ref) {
-                        debug_log(&format!("pre-push notes merge failed: {}", e));
-                    }
-                } else {
-                    // Only tracking ref exists - copy it to local
-                    debug_log(&format!(
-                        "pre-push: initializing {} from {}",
-                        local_notes_ref, tracking_ref
-                    ));
-                    if let Err(e) = copy_ref(&repo, &tracking_ref, local_notes_ref) {
-                        debug_log(&format!("pre-push notes copy failed: {}", e));
-                    }
-                }
-            }
-        }
-
-        // STEP 2: Push notes without force (requires fast-forward)
-        let mut push_authorship: Vec<String> = parsed_args.global_args.clone();
-        push_authorship.push("-c".to_string());
-        push_authorship.push("core.hooksPath=/dev/null".to_string());
-        push_authorship.push("push".to_string());
-        push_authorship.push("--quiet".to_string());
-        push_authorship.push("--no-recurse-submodules".to_string());
-        push_authorship.push("--no-verify".to_string());
-        push_authorship.push(remote);
-        push_authorship.push(AI_AUTHORSHIP_PUSH_REFSPEC.to_string());
-
-        debug_log(&format!(
-            "pushing authorship refs (no force): {:?}",
-            &push_authorship
-        ));
-        if let Err(e) = exec_git(&push_authorship) {
+        let revisions = pushed_revisions(&parsed_args.command_args);
+        if let Err(e) = push_authorship_notes(&parsed_args.global_args, &repo, &remote, &revisions)
+        {
             // Best-effort; don't fail user operation due to authorship sync issues
             debug_log(&format!("authorship push skipped due to error: {}", e));
         }
@@ -131,6 +93,224 @@ pub fn push_post_command_hook(
     }
 }
 
+/// Merge the notes remote's authorship notes into ours, then push refs/notes/ai (and the
+/// prompt store) to it without force. The notes remote is `remote` unless the repository sets
+/// `git-ai-tracker.notesRemote`. In `NotesPushMode::Reachable`, only notes on commits
+/// reachable from `pushed_revisions` (rev-list arguments) are pushed. Fetching and merging
+/// the remote's notes is best-effort; failing to select or push the notes is returned.
+pub fn push_authorship_notes(
+    global_args: &[String],
+    repo: &Repository,
+    remote: &str,
+    pushed_revisions: &[String],
+) -> Result<(), GitAiError> {
+    let notes_remote = notes_remote_for(repo, remote);
+    let remote = notes_remote.as_str();
+
+    // STEP 1: Fetch remote notes into tracking ref and merge before pushing
+    // This ensures we don't lose notes from other branches/clones
+    let tracking_ref = tracking_ref_for_remote(remote);
+    let fetch_refspec = format!("+refs/notes/ai:{}", tracking_ref);
+
+    let mut fetch_before_push: Vec<String> = global_args.to_vec();
+    fetch_before_push.push("-c".to_string());
+    fetch_before_push.push("core.hooksPath=/dev/null".to_string());
+    fetch_before_push.push("fetch".to_string());
+    fetch_before_push.push("--no-tags".to_string());
+    fetch_before_push.push("--recurse-submodules=no".to_string());
+    fetch_before_push.push("--no-write-fetch-head".to_string());
+    fetch_before_push.push("--no-write-commit-graph".to_string());
+    fetch_before_push.push("--no-auto-maintenance".to_string());
+    fetch_before_push.push(remote.to_string());
+    fetch_before_push.push(fetch_refspec);
+
+    debug_log(&format!(
+        "pre-push authorship fetch: {:?}",
+        &fetch_before_push
+    ));
+
+    // Fetch is best-effort; if it fails (e.g., no remote notes yet), continue
+    if exec_git(&fetch_before_push).is_ok() {
+        // Merge fetched notes into local refs/notes/ai
+        let local_notes_ref = "refs/notes/ai";
+
+        if ref_exists(repo, &tracking_ref) {
+            if ref_exists(repo, local_notes_ref) {
+                // Both exist - merge them
+                debug_log(&format!(
+                    "pre-push: merging {} into {}",
+                    tracking_ref, local_notes_ref
+                ));
+                if let Err(e) = merge_notes_from_ref(repo, &tracking_ref) {
+                    debug_log(&format!("pre-push notes merge failed: {}", e));
+                }
+            } else {
+                // Only tracking ref exists - copy it to local
+                debug_log(&format!(
+                    "pre-push: initializing {} from {}",
+                    local_notes_ref, tracking_ref
+                ));
+                if let Err(e) = copy_ref(repo, &tracking_ref, local_notes_ref) {
+                    debug_log(&format!("pre-push notes copy failed: {}", e));
+                }
+            }
+        }
+    }
+    fetch_prompt_store(global_args, repo, remote);
+
+    // STEP 2: Push notes without force (requires fast-forward)
+    let refspecs = match NotesPushMode::for_repo(repo) {
+        NotesPushMode::All => {
+            let mut refspecs = vec![AI_AUTHORSHIP_PUSH_REFSPEC.to_string()];
+            if ref_exists(repo, AI_PROMPTS_REF) {
+                refspecs.push(AI_PROMPTS_PUSH_REFSPEC.to_string());
+            }
+            refspecs
+        }
+        NotesPushMode::Reachable => reachable_notes_refspecs(repo, remote, pushed_revisions)?,
+    };
+    if refspecs.is_empty() {
+        debug_log("no authorship notes to push");
+        return Ok(());
+    }
+
+    let mut push_authorship: Vec<String> = global_args.to_vec();
+    push_authorship.push("-c".to_string());
+    push_authorship.push("core.hooksPath=/dev/null".to_string());
+    push_authorship.push("push".to_string());
+    push_authorship.push("--quiet".to_string());
+    push_authorship.push("--no-recurse-submodules".to_string());
+    push_authorship.push("--no-verify".to_string());
+    push_authorship.push(remote.to_string());
+    push_authorship.extend(refspecs);
+
+    debug_log(&format!(
+        "pushing authorship refs (no force): {:?}",
+        &push_authorship
+    ));
+    exec_git(&push_authorship)?;
+    Ok(())
+}
+
+/// Refspecs that push notes commits holding the remote's notes plus our notes on commits
+/// reachable from `pushed_revisions`, and the transcripts those notes reference
+fn reachable_notes_refspecs(
+    repo: &Repository,
+    remote: &str,
+    pushed_revisions: &[String],
+) -> Result<Vec<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("rev-list".to_string());
+    args.extend(pushed_revisions.iter().cloned());
+    args.push("--".to_string());
+    let output = exec_git(&args)?;
+    let reachable: HashSet<String> = String::from_utf8(output.stdout)?
+        .lines()
+        .map(|sha| sha.to_string())
+        .collect();
+
+    let mut refspecs = Vec::new();
+    let base = resolve_ref(repo, &tracking_ref_for_remote(remote))?;
+    if let Some(notes_commit) =
+        notes_commit_for_objects(repo, "refs/notes/ai", base.as_deref(), &reachable)?
+    {
+        refspecs.push(format!("{}:refs/notes/ai", notes_commit));
+    }
+
+    let mut transcripts = HashSet::new();
+    for commit_sha in list_authorship_notes(repo)?
+        .iter()
+        .filter(|sha| reachable.contains(*sha))
+    {
+        let Some(content) = show_authorship_note(repo, commit_sha) else {
+            continue;
+        };
+        if let Ok(authorship_log) = AuthorshipLog::deserialize_from_string(&content) {
+            transcripts.extend(
+                authorship_log
+                    .metadata
+                    .prompts
+                    .values()
+                    .filter_map(|prompt| prompt.messages_ref.clone()),
+            );
+        }
+    }
+    let base = resolve_ref(repo, &prompts_tracking_ref_for_remote(remote))?;
+    if !transcripts.is_empty()
+        && let Some(prompts_commit) =
+            notes_commit_for_objects(repo, AI_PROMPTS_REF, base.as_deref(), &transcripts)?
+    {
+        refspecs.push(format!("{}:{}", prompts_commit, AI_PROMPTS_REF));
+    }
+
+    Ok(refspecs)
+}
+
+fn resolve_ref(repo: &Repository, ref_name: &str) -> Result<Option<String>, GitAiError> {
+    if !ref_exists(repo, ref_name) {
+        return Ok(None);
+    }
+    Ok(Some(repo.revparse_single(ref_name)?.peel_to_commit()?.id()))
+}
+
+/// rev-list arguments for the commits a `git push` publishes: the source side of each
+/// refspec, `--branches`/`--tags` for `--all`/`--tags`, or HEAD when no refspec is given
+fn pushed_revisions(args: &[String]) -> Vec<String> {
+    let mut revisions = Vec::new();
+    let mut positionals = Vec::new();
+    let mut explicit_repo = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        if arg == "--" {
+            positionals.extend(args[i + 1..].iter().map(|a| a.as_str()));
+            break;
+        }
+        if arg == "--all" || arg == "--branches" {
+            revisions.push("--branches".to_string());
+        } else if arg == "--tags" {
+            revisions.push("--tags".to_string());
+        } else if arg == "--repo" || arg.starts_with("--repo=") {
+            explicit_repo = true;
+        }
+
+        if !arg.starts_with('-') {
+            positionals.push(arg);
+        } else if is_push_option_with_inline_value(arg).is_none()
+            && option_consumes_separate_value(arg)
+        {
+            i += 1;
+        }
+        i += 1;
+    }
+
+    // `git push <repository> <refspec>...`; with --repo every positional is a refspec
+    let refspecs = if explicit_repo {
+        &positionals[..]
+    } else {
+        positionals.get(1..).unwrap_or_default()
+    };
+    for refspec in refspecs {
+        let refspec = refspec.strip_prefix('+').unwrap_or(refspec);
+        let src = refspec.split_once(':').map_or(refspec, |(src, _)| src);
+        if src.is_empty() {
+            // `:dst` deletes dst; nothing is published
+            continue;
+        }
+        if src.contains('*') {
+            revisions.push(format!("--glob={}", src));
+        } else {
+            revisions.push(src.to_string());
+        }
+    }
+
+    if revisions.is_empty() {
+        revisions.push("HEAD".to_string());
+    }
+    revisions
+}
+
 fn extract_remote_from_push_args(args: &[String], known_remotes: &[String]) -> Option<String> {
     let mut i = 0;
     while i < args.len() {
@@ -186,3 +366,40 @@ fn option_consumes_separate_value(arg: &str) -> bool {
         "--repo" | "--receive-pack" | "--exec" | "-o" | "--push-option" | "-c" | "-C"
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_pushed_revisions() {
+        assert_eq!(pushed_revisions(&args(&[])), vec!["HEAD"]);
+        assert_eq!(pushed_revisions(&args(&["-u", "origin"])), vec!["HEAD"]);
+        assert_eq!(
+            pushed_revisions(&args(&["origin", "main", "+feature:refs/heads/review"])),
+            vec!["main", "feature"]
+        );
+        assert_eq!(
+            pushed_revisions(&args(&[
+                "-o",
+                "ci.skip",
+                "origin",
+                ":old",
+                "refs/heads/*:refs/heads/*"
+            ])),
+            vec!["--glob=refs/heads/*"]
+        );
+        assert_eq!(
+            pushed_revisions(&args(&["--all", "--repo=fork"])),
+            vec!["--branches"]
+        );
+        assert_eq!(
+            pushed_revisions(&args(&["--tags", "origin"])),
+            vec!["--tags"]
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use crate::{
+    authorship::stash_authorship,
+    commands::{git_handlers::CommandHooksContext, hooks::commit_hooks},
+    git::{
+        cli_parser::ParsedGitInvocation,
+        repository::Repository,
+        rewrite_log::{RewriteLogEvent, StashEvent, StashOperation},
+    },
+    utils::debug_log,
+};
+
+pub fn pre_stash_hook(
+    parsed_args: &ParsedGitInvocation,
+    repository: &mut Repository,
+    command_hooks_context: &mut CommandHooksContext,
+) {
+    let subcommand = stash_subcommand(parsed_args);
+
+    match subcommand.as_str() {
+        "push" | "save" | "pop" | "apply" => {
+            // Capture the current working directory state so the working log is
+            // up to date before files are moved in or out of the stash
+            let human_author =
+                commit_hooks::get_commit_default_author(repository, &parsed_args.command_args);
+            let _result = crate::commands::checkpoint::run(
+                repository,
+                &human_author,
+                false,
+                false,
+                true,
+                None,
+            );
+
+            repository.require_pre_command_head();
+        }
+        _ => {}
+    }
+
+    // Resolve the stash entry before the command runs: push needs the previous top of
+    // the stash to detect whether a new entry was created, and pop/drop remove the
+    // entry we need to look up.
+    command_hooks_context.stash_sha = match subcommand.as_str() {
+        "push" | "save" => resolve_stash_sha(repository, "refs/stash"),
+        "pop" | "apply" | "drop" => resolve_stash_sha(repository, &extract_stash_ref(parsed_args)),
+        _ => None,
+    };
+}
+
+pub fn post_stash_hook(
+    command_hooks_context: &CommandHooksContext,
+    parsed_args: &ParsedGitInvocation,
+    repository: &mut Repository,
+    exit_status: std::process::ExitStatus,
+) {
+    let subcommand = stash_subcommand(parsed_args);
+
+    if !exit_status.success() {
+        debug_log(&format!(
+            "Stash {} failed, skipping authorship handling",
+            subcommand
+        ));
+        return;
+    }
+
+    match subcommand.as_str() {
+        "push" | "save" => handle_stash_push(command_hooks_context, parsed_args, repository),
+        "pop" | "apply" => handle_stash_apply(command_hooks_context, &subcommand, repository),
+        "drop" => handle_stash_drop(command_hooks_context, repository),
+        "clear" => handle_stash_clear(repository),
+        _ => {}
+    }
+}
+
+fn handle_stash_push(
+    command_hooks_context: &CommandHooksContext,
+    parsed_args: &ParsedGitInvocation,
+    repository: &Repository,
+) {
+    let stash_sha = match resolve_stash_sha(repository, "refs/stash") {
+        Some(sha) => sha,
+        None => {
+            debug_log("No stash entry after stash push, nothing to save");
+            return;
+        }
+    };
+
+    // "No local changes to save" exits successfully without creating an entry
+    if command_hooks_context.stash_sha.as_deref() == Some(stash_sha.as_str()) {
+        debug_log("Stash push did not create a new entry, nothing to save");
+        return;
+    }
+
+    let base_commit = match &repository.pre_command_base_commit {
+        Some(sha) => sha.clone(),
+        None => {
+            debug_log("No pre-command head captured, skipping stash authorship handling");
+            return;
+        }
+    };
+
+    let keep_working_log =
+        parsed_args.has_command_flag("--keep-index") || parsed_args.has_command_flag("-k");
+
+    match stash_authorship::save_working_log_for_stash(
+        repository,
+        &base_commit,
+        &stash_sha,
+        keep_working_log,
+    ) {
+        Ok(affected_files) => {
+            let _ = repository
+                .storage
+                .append_rewrite_event(RewriteLogEvent::stash(StashEvent::new(
+                    StashOperation::Create,
+                    Some(stash_sha),
+                    true,
+                    affected_files,
+                )));
+        }
+        Err(e) => {
+            debug_log(&format!("Failed to save working log for stash: {}", e));
+        }
+    }
+}
+
+fn handle_stash_apply(
+    command_hooks_context: &CommandHooksContext,
+    subcommand: &str,
+    repository: &Repository,
+) {
+    let stash_sha = match &command_hooks_context.stash_sha {
+        Some(sha) => sha.clone(),
+        None => {
+            debug_log("No stash entry resolved before command, skipping authorship handling");
+            return;
+        }
+    };
+
+    let base_commit = match repository.head().ok().and_then(|h| h.target().ok()) {
+        Some(sha) => sha,
+        None => {
+            debug_log("No HEAD after stash apply, skipping authorship handling");
+            return;
+        }
+    };
+
+    let human_author = commit_hooks::get_commit_default_author(repository, &[]);
+
+    if let Err(e) = stash_authorship::restore_working_log_from_stash(
+        repository,
+        &stash_sha,
+        &base_commit,
+        &human_author,
+    ) {
+        debug_log(&format!("Failed to restore working log from stash: {}", e));
+        return;
+    }
+
+    let operation = if subcommand == "pop" {
+        // The stash entry is gone, so is its snapshot
+        let _ = repository.storage.delete_stash_log(&stash_sha);
+        StashOperation::Pop
+    } else {
+        StashOperation::Apply
+    };
+
+    let _ = repository
+        .storage
+        .append_rewrite_event(RewriteLogEvent::stash(StashEvent::new(
+            operation,
+            Some(stash_sha),
+            true,
+            Vec::new(),
+        )));
+}
+
+fn handle_stash_drop(command_hooks_context: &CommandHooksContext, repository: &Repository) {
+    let stash_sha = match &command_hooks_context.stash_sha {
+        Some(sha) => sha.clone(),
+        None => return,
+    };
+
+    let _ = repository.storage.delete_stash_log(&stash_sha);
+    let _ = repository
+        .storage
+        .append_rewrite_event(RewriteLogEvent::stash(StashEvent::new(
+            StashOperation::Drop,
+            Some(stash_sha),
+            true,
+            Vec::new(),
+        )));
+}
+
+fn handle_stash_clear(repository: &Repository) {
+    let _ = repository.storage.delete_all_stash_logs();
+    let _ = repository
+        .storage
+        .append_rewrite_event(RewriteLogEvent::stash(StashEvent::new(
+            StashOperation::Clear,
+            None,
+            true,
+            Vec::new(),
+        )));
+}
+
+/// Returns the stash subcommand, defaulting to "push" for a bare `git stash`
+/// (or `git stash -m ...`, `git stash -u`, etc.)
+fn stash_subcommand(parsed_args: &ParsedGitInvocation) -> String {
+    match parsed_args.command_args.first() {
+        Some(arg) if !arg.starts_with('-') => arg.clone(),
+        _ => "push".to_string(),
+    }
+}
+
+/// Extract the stash entry argument for pop/apply/drop, defaulting to stash@{0}.
+/// A bare index (`git stash pop 1`) is expanded to `stash@{1}`.
+fn extract_stash_ref(parsed_args: &ParsedGitInvocation) -> String {
+    let stash_ref = parsed_args
+        .command_args
+        .iter()
+        .skip(1)
+        .find(|arg| !arg.starts_with('-'))
+        .cloned();
+
+    match stash_ref {
+        Some(r) if !r.is_empty() && r.chars().all(|c| c.is_ascii_digit()) => {
+            format!("stash@{{{}}}", r)
+        }
+        Some(r) => r,
+        None => "stash@{0}".to_string(),
+    }
+}
+
+fn resolve_stash_sha(repository: &Repository, stash_ref: &str) -> Option<String> {
+    repository
+        .revparse_single(stash_ref)
+        .ok()
+        .map(|obj| obj.id())
+}
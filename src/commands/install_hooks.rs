@@ -12,6 +12,7 @@ use std::process::Command;
 const MIN_CURSOR_VERSION: (u32, u32) = (1, 7);
 const MIN_CODE_VERSION: (u32, u32) = (1, 99);
 const MIN_CLAUDE_VERSION: (u32, u32) = (1, 0);
+const MIN_CODEX_VERSION: (u32, u32) = (0, 30);
 
 // Command patterns for hooks (after "git-ai-tracker")
 // Cursor hooks (requires absolute path to avoid shell config loading delay)
@@ -22,6 +23,13 @@ const CURSOR_AFTER_EDIT_CMD: &str = "checkpoint cursor --hook-input \"$(cat)\"";
 const CLAUDE_PRE_TOOL_CMD: &str = "checkpoint --hook-input \"$(cat)\"";
 const CLAUDE_POST_TOOL_CMD: &str = "checkpoint claude --hook-input \"$(cat)\"";
 
+// Codex notify hook (Codex appends its JSON payload as the last argument)
+const CODEX_NOTIFY_ARGS: [&str; 3] = ["checkpoint", "codex", "--hook-input"];
+
+// Gemini CLI hooks (requires absolute path, same as Cursor)
+const GEMINI_TOOL_CMD: &str = "checkpoint gemini --hook-input \"$(cat)\"";
+const GEMINI_EDIT_TOOLS_MATCHER: &str = "write_file|replace";
+
 pub fn run(args: &[String]) -> Result<(), GitAiError> {
     // Parse --dry-run flag (default: false)
     let mut dry_run = false;
@@ -83,6 +91,87 @@ async fn async_run(binary_path: PathBuf, dry_run: bool) -> Result<(), GitAiError
         }
     }
 
+    match check_codex() {
+        Ok(true) => {
+            any_checked = true;
+            // Install/update Codex notify hook
+            let spinner = Spinner::new("Codex: checking hooks");
+            spinner.start();
+
+            match install_codex_hooks(&binary_path, dry_run) {
+                Ok(Some(diff)) => {
+                    if dry_run {
+                        spinner.pending("Codex: Pending updates");
+                    } else {
+                        spinner.success("Codex: Hooks updated");
+                    }
+                    println!(); // Blank line before diff
+                    print_diff(&diff);
+                    has_changes = true;
+                }
+                Ok(None) => {
+                    spinner.success("Codex: Hooks already up to date");
+                }
+                Err(e) => {
+                    spinner.error("Codex: Failed to update hooks");
+                    eprintln!("  Error: {}", e);
+                }
+            }
+        }
+        Ok(false) => {
+            // Codex not detected
+        }
+        Err(version_error) => {
+            any_checked = true;
+            let spinner = Spinner::new("Codex: checking version");
+            spinner.start();
+            spinner.error("Codex: Version check failed");
+            eprintln!("  Error: {}", version_error);
+            eprintln!("  Please update Codex to continue using git-ai hooks");
+        }
+    }
+
+    match check_gemini_cli() {
+        Ok(true) => {
+            any_checked = true;
+            // Install/update Gemini CLI hooks
+            let spinner = Spinner::new("Gemini CLI: checking hooks");
+            spinner.start();
+
+            match install_gemini_hooks(&binary_path, dry_run) {
+                Ok(Some(diff)) => {
+                    if dry_run {
+                        spinner.pending("Gemini CLI: Pending updates");
+                    } else {
+                        spinner.success("Gemini CLI: Hooks updated");
+                    }
+                    println!(); // Blank line before diff
+                    print_diff(&diff);
+                    has_changes = true;
+                }
+                Ok(None) => {
+                    spinner.success("Gemini CLI: Hooks already up to date");
+                }
+                Err(e) => {
+                    spinner.error("Gemini CLI: Failed to update hooks");
+                    eprintln!("  Error: {}", e);
+                    eprintln!("  Check that ~/.gemini/settings.json is valid JSON");
+                }
+            }
+        }
+        Ok(false) => {
+            // Gemini CLI not detected
+        }
+        Err(version_error) => {
+            any_checked = true;
+            let spinner = Spinner::new("Gemini CLI: checking version");
+            spinner.start();
+            spinner.error("Gemini CLI: Version check failed");
+            eprintln!("  Error: {}", version_error);
+            eprintln!("  Please update Gemini CLI to continue using git-ai hooks");
+        }
+    }
+
     match check_vscode() {
         Ok(true) => {
             any_checked = true;
@@ -239,6 +328,53 @@ fn check_cursor() -> Result<bool, String> {
     Ok(true)
 }
 
+fn check_codex() -> Result<bool, String> {
+    let has_binary = binary_exists("codex");
+    let has_dotfiles = codex_home().exists();
+
+    if !has_binary && !has_dotfiles {
+        return Ok(false);
+    }
+
+    // If we have the binary, check version
+    if has_binary {
+        match get_binary_version("codex") {
+            Ok(version_str) => {
+                // Codex prints "codex-cli 0.46.0"
+                let version_str = version_str.trim_start_matches("codex-cli").trim();
+                if let Some(version) = parse_version(version_str)
+                    && !version_meets_requirement(version, MIN_CODEX_VERSION)
+                {
+                    return Err(format!(
+                        "Codex version {}.{} detected, but minimum version {}.{} is required",
+                        version.0, version.1, MIN_CODEX_VERSION.0, MIN_CODEX_VERSION.1
+                    ));
+                }
+                // If we can't parse, continue anyway (be permissive)
+            }
+            Err(_) => {
+                // If version check fails, continue anyway (be permissive)
+            }
+        }
+    }
+
+    Ok(true)
+}
+
+fn check_gemini_cli() -> Result<bool, String> {
+    let has_binary = binary_exists("gemini");
+    let has_dotfiles = {
+        let home = home_dir();
+        home.join(".gemini").exists()
+    };
+
+    if !has_binary && !has_dotfiles {
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
 fn check_vscode() -> Result<bool, String> {
     let has_binary = binary_exists("code");
     let has_dotfiles = {
@@ -725,6 +861,280 @@ fn install_cursor_hooks(binary_path: &Path, dry_run: bool) -> Result<Option<Stri
     Ok(Some(diff_output))
 }
 
+fn install_codex_hooks(binary_path: &Path, dry_run: bool) -> Result<Option<String>, GitAiError> {
+    let config_path = codex_config_path();
+
+    // Ensure directory exists
+    if let Some(dir) = config_path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    // Read existing content as string
+    let existing_content = if config_path.exists() {
+        fs::read_to_string(&config_path)?
+    } else {
+        String::new()
+    };
+
+    // Build the notify program with absolute path
+    let binary_path = binary_path.display().to_string();
+    let notify_args = std::iter::once(binary_path.as_str())
+        .chain(CODEX_NOTIFY_ARGS)
+        .map(serde_json::to_string)
+        .collect::<Result<Vec<_>, _>>()?;
+    let desired_line = format!("notify = [{}]", notify_args.join(", "));
+
+    let new_content = match merge_codex_notify(&existing_content, &desired_line)? {
+        Some(content) => content,
+        None => return Ok(None), // No changes needed
+    };
+
+    // Generate diff
+    let diff = TextDiff::from_lines(&existing_content, &new_content);
+    let mut diff_output = String::new();
+    diff_output.push_str(&format!("--- {}\n", config_path.display()));
+    diff_output.push_str(&format!("+++ {}\n", config_path.display()));
+
+    for change in diff.iter_all_changes() {
+        let sign = match change.tag() {
+            ChangeTag::Delete => "-",
+            ChangeTag::Insert => "+",
+            ChangeTag::Equal => " ",
+        };
+        diff_output.push_str(&format!("{}{}", sign, change));
+    }
+
+    // Write if not dry-run
+    if !dry_run {
+        write_atomic(&config_path, new_content.as_bytes())?;
+    }
+
+    Ok(Some(diff_output))
+}
+
+/// Set the top-level `notify` key of a Codex config.toml to `desired_line`.
+/// Returns None if it's already set. Codex only runs a single notify program, so a
+/// notify command that isn't ours is left alone and reported as an error.
+fn merge_codex_notify(existing: &str, desired_line: &str) -> Result<Option<String>, GitAiError> {
+    let lines: Vec<&str> = existing.lines().collect();
+
+    // Top-level keys live before the first [table] header
+    let mut notify_range: Option<(usize, usize)> = None;
+    let mut idx = 0;
+    while idx < lines.len() {
+        let trimmed = lines[idx].trim_start();
+        if trimmed.starts_with('[') {
+            break;
+        }
+        let is_notify = trimmed
+            .strip_prefix("notify")
+            .is_some_and(|rest| rest.trim_start().starts_with('='));
+        if is_notify {
+            // The array may span several lines
+            let mut end = idx;
+            let mut depth = toml_bracket_depth(lines[idx]);
+            while depth > 0 && end + 1 < lines.len() {
+                end += 1;
+                depth += toml_bracket_depth(lines[end]);
+            }
+            notify_range = Some((idx, end));
+            break;
+        }
+        idx += 1;
+    }
+
+    let mut new_lines: Vec<&str> = Vec::with_capacity(lines.len() + 1);
+    match notify_range {
+        Some((start, end)) => {
+            let existing_notify = lines[start..=end].join("\n");
+            if existing_notify.trim() == desired_line {
+                return Ok(None);
+            }
+            if !(existing_notify.contains("git-ai") && existing_notify.contains("checkpoint")) {
+                return Err(GitAiError::Generic(format!(
+                    "Codex already has a notify command configured ({}). Remove it to let git-ai-tracker install its hook",
+                    existing_notify.trim()
+                )));
+            }
+            new_lines.extend(&lines[..start]);
+            new_lines.push(desired_line);
+            new_lines.extend(&lines[end + 1..]);
+        }
+        None => {
+            new_lines.push(desired_line);
+            new_lines.extend(&lines);
+        }
+    }
+
+    let mut new_content = new_lines.join("\n");
+    new_content.push('\n');
+    Ok(Some(new_content))
+}
+
+/// Net count of opening minus closing brackets on a TOML line, ignoring strings and comments
+fn toml_bracket_depth(line: &str) -> i32 {
+    let mut depth = 0;
+    let mut in_string: Option<char> = None;
+    let mut escaped = false;
+    for ch in line.chars() {
+        match in_string {
+            Some(quote) => {
+                if escaped {
+                    escaped = false;
+                } else if ch == '\\' && quote == '"' {
+                    escaped = true;
+                } else if ch == quote {
+                    in_string = None;
+                }
+            }
+            None => match ch {
+                '"' | '\'' => in_string = Some(ch),
+                '#' => break,
+                '[' => depth += 1,
+                ']' => depth -= 1,
+                _ => {}
+            },
+        }
+    }
+    depth
+}
+
+fn install_gemini_hooks(binary_path: &Path, dry_run: bool) -> Result<Option<String>, GitAiError> {
+    let settings_path = gemini_settings_path();
+
+    // Ensure directory exists
+    if let Some(dir) = settings_path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+
+    // Read existing content as string
+    let existing_content = if settings_path.exists() {
+        fs::read_to_string(&settings_path)?
+    } else {
+        String::new()
+    };
+
+    // Parse existing JSON if present, else start with empty object
+    let existing: Value = if existing_content.trim().is_empty() {
+        json!({})
+    } else {
+        serde_json::from_str(&existing_content)?
+    };
+
+    // Build command with absolute path
+    let tool_cmd = format!("{} {}", binary_path.display(), GEMINI_TOOL_CMD);
+    let merged = merge_gemini_hooks(&existing, &tool_cmd);
+
+    // Generate new content
+    let new_content = serde_json::to_string_pretty(&merged)?;
+
+    // Check if there are changes
+    if existing_content.trim() == new_content.trim() {
+        return Ok(None); // No changes needed
+    }
+
+    // Generate diff
+    let diff = TextDiff::from_lines(&existing_content, &new_content);
+    let mut diff_output = String::new();
+    diff_output.push_str(&format!("--- {}\n", settings_path.display()));
+    diff_output.push_str(&format!("+++ {}\n", settings_path.display()));
+
+    for change in diff.iter_all_changes() {
+        let sign = match change.tag() {
+            ChangeTag::Delete => "-",
+            ChangeTag::Insert => "+",
+            ChangeTag::Equal => " ",
+        };
+        diff_output.push_str(&format!("{}{}", sign, change));
+    }
+
+    // Write if not dry-run
+    if !dry_run {
+        write_atomic(&settings_path, new_content.as_bytes())?;
+    }
+
+    Ok(Some(diff_output))
+}
+
+/// Register `desired_cmd` for BeforeTool and AfterTool on Gemini CLI's file editing tools,
+/// keeping any other hooks. Outdated git-ai checkpoint commands are updated in place and
+/// duplicates removed.
+fn merge_gemini_hooks(existing: &Value, desired_cmd: &str) -> Value {
+    let mut merged = existing.clone();
+    let mut hooks_obj = merged.get("hooks").cloned().unwrap_or_else(|| json!({}));
+
+    for hook_type in &["BeforeTool", "AfterTool"] {
+        let mut hook_type_array = hooks_obj
+            .get(*hook_type)
+            .and_then(|v| v.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        // Find existing matcher block for the edit tools
+        let matcher_idx = match hook_type_array.iter().position(|item| {
+            item.get("matcher").and_then(|m| m.as_str()) == Some(GEMINI_EDIT_TOOLS_MATCHER)
+        }) {
+            Some(idx) => idx,
+            None => {
+                hook_type_array.push(json!({
+                    "matcher": GEMINI_EDIT_TOOLS_MATCHER,
+                    "hooks": []
+                }));
+                hook_type_array.len() - 1
+            }
+        };
+
+        let hooks_array = hook_type_array[matcher_idx]
+            .get("hooks")
+            .and_then(|h| h.as_array())
+            .cloned()
+            .unwrap_or_default();
+
+        // Replace the first git-ai checkpoint command with the desired one and drop the rest
+        let mut new_hooks_array = Vec::with_capacity(hooks_array.len() + 1);
+        let mut found = false;
+        for hook in hooks_array {
+            let is_checkpoint_cmd =
+                hook.get("command")
+                    .and_then(|c| c.as_str())
+                    .is_some_and(|cmd| {
+                        cmd.contains("git-ai")
+                            && cmd.contains("checkpoint")
+                            && cmd.contains("gemini")
+                    });
+            if !is_checkpoint_cmd {
+                new_hooks_array.push(hook);
+            } else if !found {
+                found = true;
+                new_hooks_array.push(json!({
+                    "type": "command",
+                    "command": desired_cmd
+                }));
+            }
+        }
+        if !found {
+            new_hooks_array.push(json!({
+                "type": "command",
+                "command": desired_cmd
+            }));
+        }
+
+        if let Some(matcher_block) = hook_type_array[matcher_idx].as_object_mut() {
+            matcher_block.insert("hooks".to_string(), Value::Array(new_hooks_array));
+        }
+
+        if let Some(obj) = hooks_obj.as_object_mut() {
+            obj.insert(hook_type.to_string(), Value::Array(hook_type_array));
+        }
+    }
+
+    if let Some(root) = merged.as_object_mut() {
+        root.insert("hooks".to_string(), hooks_obj);
+    }
+
+    merged
+}
+
 fn claude_settings_path() -> PathBuf {
     home_dir().join(".claude").join("settings.json")
 }
@@ -733,6 +1143,21 @@ fn cursor_hooks_path() -> PathBuf {
     home_dir().join(".cursor").join("hooks.json")
 }
 
+fn gemini_settings_path() -> PathBuf {
+    home_dir().join(".gemini").join("settings.json")
+}
+
+fn codex_home() -> PathBuf {
+    if let Ok(codex_home) = std::env::var("CODEX_HOME") {
+        return PathBuf::from(codex_home);
+    }
+    home_dir().join(".codex")
+}
+
+fn codex_config_path() -> PathBuf {
+    codex_home().join("config.toml")
+}
+
 fn write_atomic(path: &Path, data: &[u8]) -> Result<(), GitAiError> {
     let tmp_path = path.with_extension("tmp");
     {
@@ -1796,4 +2221,167 @@ mod tests {
         assert!(!is_git_ai_checkpoint_command("git status", false));
         assert!(!is_git_ai_checkpoint_command("checkpoint", false));
     }
+
+    const CODEX_NOTIFY_LINE: &str =
+        r#"notify = ["/usr/local/bin/git-ai-tracker", "checkpoint", "codex", "--hook-input"]"#;
+
+    #[test]
+    fn test_codex_notify_added_to_empty_config() {
+        let result = merge_codex_notify("", CODEX_NOTIFY_LINE).unwrap();
+        assert_eq!(result, Some(format!("{}\n", CODEX_NOTIFY_LINE)));
+    }
+
+    #[test]
+    fn test_codex_notify_added_before_tables() {
+        let existing = "model = \"gpt-5-codex\"\n\n[mcp_servers.docs]\ncommand = \"docs-mcp\"\n";
+        let result = merge_codex_notify(existing, CODEX_NOTIFY_LINE)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            format!(
+                "{}\nmodel = \"gpt-5-codex\"\n\n[mcp_servers.docs]\ncommand = \"docs-mcp\"\n",
+                CODEX_NOTIFY_LINE
+            )
+        );
+    }
+
+    #[test]
+    fn test_codex_notify_already_up_to_date() {
+        let existing = format!("model = \"o3\"\n{}\n", CODEX_NOTIFY_LINE);
+        assert_eq!(
+            merge_codex_notify(&existing, CODEX_NOTIFY_LINE).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn test_codex_notify_updates_outdated_command() {
+        let existing = "model = \"o3\"\nnotify = [\n  \"/old/bin/git-ai-tracker\",\n  \"checkpoint\",\n  \"codex\",\n]\n\n[profiles.fast]\nmodel = \"o4-mini\"\n";
+        let result = merge_codex_notify(existing, CODEX_NOTIFY_LINE)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            result,
+            format!(
+                "model = \"o3\"\n{}\n\n[profiles.fast]\nmodel = \"o4-mini\"\n",
+                CODEX_NOTIFY_LINE
+            )
+        );
+    }
+
+    #[test]
+    fn test_codex_notify_ignores_notify_inside_tables() {
+        let existing = "[profiles.fast]\nnotify = [\"say\", \"done\"]\n";
+        let result = merge_codex_notify(existing, CODEX_NOTIFY_LINE)
+            .unwrap()
+            .unwrap();
+        assert!(result.starts_with(CODEX_NOTIFY_LINE));
+        assert!(result.contains("notify = [\"say\", \"done\"]"));
+    }
+
+    #[test]
+    fn test_codex_notify_keeps_foreign_command() {
+        let existing = "notify = [\"python3\", \"/Users/me/notify.py\"]\n";
+        assert!(merge_codex_notify(existing, CODEX_NOTIFY_LINE).is_err());
+    }
+
+    #[test]
+    fn test_toml_bracket_depth() {
+        assert_eq!(toml_bracket_depth(r#"notify = ["a", "b"]"#), 0);
+        assert_eq!(toml_bracket_depth("notify = ["), 1);
+        assert_eq!(toml_bracket_depth(r#"  "C:\\tools\\[x]", # ]"#), 0);
+        assert_eq!(toml_bracket_depth("]"), -1);
+    }
+
+    #[test]
+    fn test_gemini_hooks_created_from_scratch() {
+        let cmd = format!("/usr/local/bin/git-ai-tracker {}", GEMINI_TOOL_CMD);
+        let merged = merge_gemini_hooks(&json!({}), &cmd);
+
+        let expected_block = json!([
+            {
+                "matcher": "write_file|replace",
+                "hooks": [
+                    {
+                        "type": "command",
+                        "command": cmd
+                    }
+                ]
+            }
+        ]);
+        assert_eq!(merged["hooks"]["BeforeTool"], expected_block);
+        assert_eq!(merged["hooks"]["AfterTool"], expected_block);
+    }
+
+    #[test]
+    fn test_gemini_hooks_preserve_other_settings_and_hooks() {
+        let cmd = format!("/usr/local/bin/git-ai-tracker {}", GEMINI_TOOL_CMD);
+        let existing = json!({
+            "theme": "GitHub",
+            "hooks": {
+                "AfterTool": [
+                    {
+                        "matcher": "run_shell_command",
+                        "hooks": [{"type": "command", "command": "echo shell"}]
+                    },
+                    {
+                        "matcher": "write_file|replace",
+                        "hooks": [{"type": "command", "command": "prettier --write"}]
+                    }
+                ],
+                "SessionStart": [
+                    {"hooks": [{"type": "command", "command": "echo start"}]}
+                ]
+            }
+        });
+
+        let merged = merge_gemini_hooks(&existing, &cmd);
+
+        assert_eq!(merged["theme"], json!("GitHub"));
+        assert_eq!(
+            merged["hooks"]["SessionStart"],
+            existing["hooks"]["SessionStart"]
+        );
+
+        let after_tool = merged["hooks"]["AfterTool"].as_array().unwrap();
+        assert_eq!(after_tool.len(), 2);
+        assert_eq!(after_tool[0], existing["hooks"]["AfterTool"][0]);
+        assert_eq!(
+            after_tool[1]["hooks"],
+            json!([
+                {"type": "command", "command": "prettier --write"},
+                {"type": "command", "command": cmd}
+            ])
+        );
+    }
+
+    #[test]
+    fn test_gemini_hooks_update_outdated_and_remove_duplicates() {
+        let cmd = format!("/usr/local/bin/git-ai-tracker {}", GEMINI_TOOL_CMD);
+        let outdated = json!({"type": "command", "command": "git-ai-tracker checkpoint gemini"});
+        let existing = json!({
+            "hooks": {
+                "BeforeTool": [
+                    {
+                        "matcher": "write_file|replace",
+                        "hooks": [outdated.clone(), {"type": "command", "command": "echo keep"}, outdated]
+                    }
+                ]
+            }
+        });
+
+        let merged = merge_gemini_hooks(&existing, &cmd);
+
+        assert_eq!(
+            merged["hooks"]["BeforeTool"][0]["hooks"],
+            json!([
+                {"type": "command", "command": cmd},
+                {"type": "command", "command": "echo keep"}
+            ])
+        );
+
+        // Merging again is a no-op
+        assert_eq!(merge_gemini_hooks(&merged, &cmd), merged);
+    }
 }
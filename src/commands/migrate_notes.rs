@@ -0,0 +1,218 @@
+use crate::authorship::authorship_log_serialization::{AUTHORSHIP_LOG_VERSION, AuthorshipLog};
+use crate::error::GitAiError;
+use crate::git::refs::{list_authorship_notes, notes_add_authorship, show_authorship_note};
+use crate::git::repository::Repository;
+use std::collections::HashSet;
+
+/// What rewriting a single note changes
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteMigration {
+    pub commit_sha: String,
+    pub from_version: String,
+    /// Inline transcripts moved to the prompt store
+    pub transcripts_moved: usize,
+    /// Attributed lines that gained a content fingerprint
+    pub lines_fingerprinted: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct MigrationReport {
+    pub migrated: Vec<NoteMigration>,
+    pub up_to_date: usize,
+    /// Notes that could not be parsed, with the parse error
+    pub failed: Vec<(String, String)>,
+}
+
+/// Rewrite authorship notes in refs/notes/ai to the current schema. With `range`, only
+/// notes on commits in that revision range are considered; with `dry_run`, nothing is
+/// written and the report describes what would change.
+pub fn run(
+    repo: &Repository,
+    range: Option<&str>,
+    dry_run: bool,
+) -> Result<MigrationReport, GitAiError> {
+    let mut commits = list_authorship_notes(repo)?;
+    if let Some(range) = range {
+        let in_range: HashSet<String> = repo.rev_list(range)?.into_iter().collect();
+        commits.retain(|sha| in_range.contains(sha));
+    }
+
+    let mut report = MigrationReport::default();
+    for commit_sha in commits {
+        let Some(content) = show_authorship_note(repo, &commit_sha) else {
+            continue;
+        };
+        let authorship_log = match AuthorshipLog::deserialize_from_string(&content) {
+            Ok(log) => log,
+            Err(e) => {
+                report.failed.push((commit_sha, e.to_string()));
+                continue;
+            }
+        };
+
+        match plan_migration(repo, &commit_sha, &authorship_log) {
+            Some(migration) => {
+                if !dry_run {
+                    notes_add_authorship(repo, &commit_sha, &authorship_log)?;
+                }
+                report.migrated.push(migration);
+            }
+            None => report.up_to_date += 1,
+        }
+    }
+
+    Ok(report)
+}
+
+/// Work out what migrating a note would change, or None if it is already current
+fn plan_migration(
+    repo: &Repository,
+    commit_sha: &str,
+    authorship_log: &AuthorshipLog,
+) -> Option<NoteMigration> {
+    let transcripts_moved = authorship_log
+        .metadata
+        .prompts
+        .values()
+        .filter(|prompt| !prompt.messages.is_empty())
+        .count();
+
+    let was_fingerprinted = |log: &AuthorshipLog| -> Vec<bool> {
+        log.attestations
+            .iter()
+            .flat_map(|file| file.entries.iter())
+            .map(|entry| {
+                !entry.fingerprints.is_empty() && entry.fingerprints.len() == entry.lines().len()
+            })
+            .collect()
+    };
+    let before = was_fingerprinted(authorship_log);
+
+    let mut fingerprinted = authorship_log.clone();
+    fingerprinted.compute_fingerprints(|file_path| {
+        repo.file_content_at_commit(commit_sha, file_path)
+            .ok()
+            .flatten()
+    });
+    let lines_fingerprinted: usize = fingerprinted
+        .attestations
+        .iter()
+        .flat_map(|file| file.entries.iter())
+        .zip(before)
+        .filter(|(entry, had_fingerprints)| !had_fingerprints && !entry.fingerprints.is_empty())
+        .map(|(entry, _)| entry.fingerprints.len())
+        .sum();
+
+    let from_version = authorship_log.metadata.schema_version.clone();
+    if from_version == AUTHORSHIP_LOG_VERSION && transcripts_moved == 0 && lines_fingerprinted == 0
+    {
+        return None;
+    }
+
+    Some(NoteMigration {
+        commit_sha: commit_sha.to_string(),
+        from_version,
+        transcripts_moved,
+        lines_fingerprinted,
+    })
+}
+
+pub fn write_report_to_terminal(report: &MigrationReport, dry_run: bool) {
+    for migration in &report.migrated {
+        println!(
+            "{}  {} -> {}  ({} transcript(s) moved to prompt store, {} line(s) fingerprinted)",
+            &migration.commit_sha[..8.min(migration.commit_sha.len())],
+            migration.from_version,
+            AUTHORSHIP_LOG_VERSION,
+            migration.transcripts_moved,
+            migration.lines_fingerprinted
+        );
+    }
+    for (commit_sha, error) in &report.failed {
+        println!(
+            "{}  unreadable: {}",
+            &commit_sha[..8.min(commit_sha.len())],
+            error
+        );
+    }
+
+    println!(
+        "{} {} note(s), {} already up to date, {} unreadable{}",
+        if dry_run { "Would migrate" } else { "Migrated" },
+        report.migrated.len(),
+        report.up_to_date,
+        report.failed.len(),
+        if dry_run { " (dry run)" } else { "" }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::authorship_log_serialization::AUTHORSHIP_LOG_VERSION_V3;
+    use crate::git::refs::notes_add;
+    use crate::git::test_utils::TmpRepo;
+
+    fn v3_note() -> String {
+        format!(
+            "a.txt\n  abc1234 1-2\n---\n{{\n  \"schema_version\": \"{}\",\n  \"base_commit_sha\": \"\",\n  \"prompts\": {{\n    \"abc1234\": {{\n      \"agent_id\": {{\"tool\": \"cursor\", \"id\": \"s1\", \"model\": \"gpt-4\"}},\n      \"human_author\": null,\n      \"messages\": [{{\"type\": \"user\", \"text\": \"write a.txt\"}}]\n    }}\n  }}\n}}",
+            AUTHORSHIP_LOG_VERSION_V3
+        )
+    }
+
+    #[test]
+    fn test_migrate_notes_dry_run_then_apply() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\ntwo\n", true).unwrap();
+        tmp_repo.commit_with_message("Add a.txt").unwrap();
+        let commit_sha = tmp_repo.get_head_commit_sha().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        notes_add(repo, &commit_sha, &v3_note()).unwrap();
+
+        // Dry run reports the migration but leaves the note untouched
+        let report = run(repo, None, true).unwrap();
+        assert_eq!(
+            report.migrated,
+            vec![NoteMigration {
+                commit_sha: commit_sha.clone(),
+                from_version: AUTHORSHIP_LOG_VERSION_V3.to_string(),
+                transcripts_moved: 1,
+                lines_fingerprinted: 2,
+            }]
+        );
+        assert_eq!(show_authorship_note(repo, &commit_sha).unwrap(), v3_note());
+
+        // Applying rewrites the note in the current schema
+        let report = run(repo, Some("HEAD"), false).unwrap();
+        assert_eq!(report.migrated.len(), 1);
+        let migrated = AuthorshipLog::deserialize_from_string(
+            &show_authorship_note(repo, &commit_sha).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(migrated.metadata.schema_version, AUTHORSHIP_LOG_VERSION);
+        let prompt = &migrated.metadata.prompts["abc1234"];
+        assert!(prompt.messages.is_empty());
+        assert!(prompt.messages_ref.is_some());
+        assert_eq!(migrated.attestations[0].entries[0].fingerprints.len(), 2);
+
+        // A second run has nothing left to do
+        let report = run(repo, None, false).unwrap();
+        assert!(report.migrated.is_empty());
+        assert!(report.up_to_date >= 1);
+    }
+
+    #[test]
+    fn test_migrate_notes_reports_unreadable_notes() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo.commit_with_message("Add a.txt").unwrap();
+        let commit_sha = tmp_repo.get_head_commit_sha().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        notes_add(repo, &commit_sha, "not an authorship log").unwrap();
+
+        let report = run(repo, None, true).unwrap();
+        assert!(report.migrated.is_empty());
+        assert_eq!(report.failed.len(), 1);
+        assert_eq!(report.failed[0].0, commit_sha);
+    }
+}
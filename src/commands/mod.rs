@@ -1,9 +1,16 @@
 pub mod blame;
+pub mod check;
 pub mod checkpoint;
 pub mod checkpoint_agent;
+pub mod fsck;
+pub mod gc;
 pub mod git_ai_handlers;
 pub mod git_handlers;
 pub mod hooks;
 pub mod install_hooks;
+pub mod migrate_notes;
+pub mod reconcile;
+pub mod server_hook;
 pub mod squash_authorship;
 pub mod stats_delta;
+pub mod verify_authorship;
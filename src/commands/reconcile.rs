@@ -0,0 +1,495 @@
+use crate::authorship::rebase_authorship::rewrite_authorship_after_squash_or_rebase;
+use crate::commands::hooks::fetch_hooks::fetch_authorship_notes;
+use crate::commands::hooks::push_hooks::push_authorship_notes;
+use crate::error::GitAiError;
+use crate::git::refs::{ref_exists, show_authorship_note};
+use crate::git::repository::{Repository, exec_git, git_version};
+use crate::utils::debug_log;
+
+/// Rebuilding a note merges trees with `git merge-tree --merge-base`, added in git 2.40
+pub const MIN_GIT_VERSION: (u32, u32) = (2, 40);
+
+#[derive(Debug, Clone, Default)]
+pub struct ReconcileOptions {
+    /// Ref or SHA of the pull request head, when the caller already has it
+    pub pr_head: Option<String>,
+    /// Pull request number; defaults to the last `(#123)` in the commit subject
+    pub pr_number: Option<u64>,
+    /// Remote to fetch PR refs and notes from and push notes to
+    pub remote: Option<String>,
+    pub push: bool,
+    pub dry_run: bool,
+}
+
+/// How the pull request landed on the target branch
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MergeStrategy {
+    /// The commit already has an authorship note; nothing to do
+    AlreadyAttributed,
+    /// A merge commit or fast-forward: the PR commits and their notes are in history
+    Merged,
+    Squash,
+    RebaseMerge,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReconcileReport {
+    pub commit_sha: String,
+    pub strategy: MergeStrategy,
+    pub pr_head: Option<String>,
+    /// Commits that received a reconstructed note, oldest first
+    pub rewritten: Vec<String>,
+    pub pushed_to: Option<String>,
+}
+
+/// Reconstruct authorship notes for a commit that landed on the target branch through a
+/// pull request. The original PR commits are found from `options.pr_head`, or by fetching
+/// `refs/pull/<n>/head` (GitHub) or `refs/merge-requests/<n>/head` (GitLab) for the PR
+/// number, then the notes are rewritten as for `squash-authorship` and pushed.
+pub fn run(
+    repo: &Repository,
+    commit: &str,
+    options: &ReconcileOptions,
+) -> Result<ReconcileReport, GitAiError> {
+    let commit_sha = repo.revparse_single(commit)?.peel_to_commit()?.id();
+    let remote = options
+        .remote
+        .clone()
+        .or_else(|| repo.upstream_remote().ok().flatten())
+        .or_else(|| repo.get_default_remote().ok().flatten());
+    let global_args = repo.global_args_for_exec();
+
+    // CI checkouts don't fetch notes, and the PR commits' notes are what we rewrite from
+    if let Some(remote) = &remote {
+        fetch_authorship_notes(&global_args, repo, remote);
+    }
+
+    let mut report = ReconcileReport {
+        commit_sha: commit_sha.clone(),
+        strategy: MergeStrategy::AlreadyAttributed,
+        pr_head: None,
+        rewritten: Vec::new(),
+        pushed_to: None,
+    };
+
+    if show_authorship_note(repo, &commit_sha).is_some() {
+        return Ok(report);
+    }
+    let commit_obj = repo.find_commit(commit_sha.clone())?;
+    if commit_obj.parent_count()? > 1 {
+        report.strategy = MergeStrategy::Merged;
+        return Ok(report);
+    }
+
+    let pr_head = match &options.pr_head {
+        Some(pr_head) => repo.revparse_single(pr_head)?.peel_to_commit()?.id(),
+        None => {
+            let pr_number = options
+                .pr_number
+                .or_else(|| parse_pr_number(&commit_obj.summary().unwrap_or_default()))
+                .ok_or_else(|| {
+                    GitAiError::Generic(format!(
+                        "Could not find a pull request number in the subject of {}; pass --pr or --head",
+                        commit_sha
+                    ))
+                })?;
+            resolve_pr_head(repo, remote.as_deref(), pr_number)?
+        }
+    };
+    report.pr_head = Some(pr_head.clone());
+
+    // PR commits that did not land as-is, newest first
+    let pr_commits = repo.rev_list(&format!("{}..{}", commit_sha, pr_head))?;
+    if pr_commits.is_empty() {
+        report.strategy = MergeStrategy::Merged;
+        return Ok(report);
+    }
+
+    // A rebase-merge replays each PR commit with the same subject; a squash leaves one commit
+    let mut replayed = Vec::new();
+    let mut cursor = commit_obj;
+    for pr_commit in &pr_commits {
+        let original = repo.find_commit(pr_commit.clone())?;
+        if cursor.summary()? != original.summary()?
+            || show_authorship_note(repo, &cursor.id()).is_some()
+        {
+            break;
+        }
+        replayed.push((cursor.id(), pr_commit.clone()));
+        if cursor.parent_count()? != 1 {
+            break;
+        }
+        cursor = cursor.parent(0)?;
+    }
+    let pairs = if replayed.len() > 1 {
+        report.strategy = MergeStrategy::RebaseMerge;
+        replayed
+    } else {
+        report.strategy = MergeStrategy::Squash;
+        vec![(commit_sha.clone(), pr_head.clone())]
+    };
+
+    check_git_version(git_version()?)?;
+    for (new_sha, original_sha) in pairs.into_iter().rev() {
+        rewrite_authorship_after_squash_or_rebase(
+            repo,
+            "", // branch name not used in the logic
+            &original_sha,
+            &new_sha,
+            options.dry_run,
+        )?;
+        report.rewritten.push(new_sha);
+    }
+
+    if options.push
+        && !options.dry_run
+        && let Some(remote) = remote
+    {
+        push_authorship_notes(&global_args, repo, &remote, &report.rewritten).map_err(|e| {
+            GitAiError::Generic(format!(
+                "Reconciled {} commit(s) but pushing their notes to {} failed: {}",
+                report.rewritten.len(),
+                remote,
+                e
+            ))
+        })?;
+        report.pushed_to = Some(remote);
+    }
+
+    Ok(report)
+}
+
+fn check_git_version(version: (u32, u32)) -> Result<(), GitAiError> {
+    if version < MIN_GIT_VERSION {
+        return Err(GitAiError::Generic(format!(
+            "reconcile requires git >= {}.{} (found {}.{})",
+            MIN_GIT_VERSION.0, MIN_GIT_VERSION.1, version.0, version.1
+        )));
+    }
+    Ok(())
+}
+
+/// The PR number from a merge subject such as "Add feature (#123)" (the last one wins)
+pub fn parse_pr_number(subject: &str) -> Option<u64> {
+    subject.rmatch_indices("(#").find_map(|(start, _)| {
+        let rest = &subject[start + 2..];
+        let end = rest.find(')')?;
+        rest[..end].parse().ok()
+    })
+}
+
+/// Find the head of PR `pr_number`: a local `refs/pull/<n>/head` if the checkout has one,
+/// otherwise fetched from `remote` into FETCH_HEAD
+fn resolve_pr_head(
+    repo: &Repository,
+    remote: Option<&str>,
+    pr_number: u64,
+) -> Result<String, GitAiError> {
+    let candidates = [
+        format!("refs/pull/{}/head", pr_number),
+        format!("refs/merge-requests/{}/head", pr_number),
+    ];
+
+    if let Some(local_ref) = candidates.iter().find(|r| ref_exists(repo, r)) {
+        return Ok(repo.revparse_single(local_ref)?.peel_to_commit()?.id());
+    }
+
+    let remote = remote.ok_or_else(|| {
+        GitAiError::Generic(format!(
+            "No remote to fetch pull request #{} from; pass --remote or --head",
+            pr_number
+        ))
+    })?;
+    for pr_ref in &candidates {
+        let mut args = repo.global_args_for_exec();
+        args.push("-c".to_string());
+        args.push("core.hooksPath=/dev/null".to_string());
+        args.push("fetch".to_string());
+        args.push("--no-tags".to_string());
+        args.push("--recurse-submodules=no".to_string());
+        args.push("--no-write-commit-graph".to_string());
+        args.push("--no-auto-maintenance".to_string());
+        args.push(remote.to_string());
+        args.push(pr_ref.clone());
+
+        match exec_git(&args) {
+            Ok(_) => return Ok(repo.revparse_single("FETCH_HEAD")?.peel_to_commit()?.id()),
+            Err(e) => debug_log(&format!(
+                "fetching {} from {} failed: {}",
+                pr_ref, remote, e
+            )),
+        }
+    }
+
+    Err(GitAiError::Generic(format!(
+        "Could not fetch pull request #{} from {}",
+        pr_number, remote
+    )))
+}
+
+pub fn write_report_to_terminal(report: &ReconcileReport, dry_run: bool) {
+    let short = |sha: &str| sha[..8.min(sha.len())].to_string();
+    match report.strategy {
+        MergeStrategy::AlreadyAttributed => {
+            println!(
+                "{} already has an authorship note",
+                short(&report.commit_sha)
+            )
+        }
+        MergeStrategy::Merged => println!(
+            "{} was merged with its original commits; their notes already apply",
+            short(&report.commit_sha)
+        ),
+        MergeStrategy::Squash | MergeStrategy::RebaseMerge => {
+            println!(
+                "{} {} of {}: {} {} commit(s){}",
+                if report.strategy == MergeStrategy::Squash {
+                    "Squash merge"
+                } else {
+                    "Rebase merge"
+                },
+                short(&report.commit_sha),
+                short(report.pr_head.as_deref().unwrap_or_default()),
+                if dry_run {
+                    "would reconcile"
+                } else {
+                    "reconciled"
+                },
+                report.rewritten.len(),
+                match &report.pushed_to {
+                    Some(remote) => format!(", pushed notes to {}", remote),
+                    None => String::new(),
+                }
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::authorship_log_serialization::AuthorshipLog;
+    use crate::git::test_utils::TmpRepo;
+
+    /// Rebuilding notes needs a newer git than some CI images ship
+    fn git_can_reconcile() -> bool {
+        let supported = check_git_version(git_version().unwrap()).is_ok();
+        if !supported {
+            eprintln!("skipping: reconcile requires git >= 2.40");
+        }
+        supported
+    }
+
+    #[test]
+    fn test_check_git_version() {
+        let err = check_git_version((2, 39)).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("reconcile requires git >= 2.40 (found 2.39)")
+        );
+        assert!(check_git_version((2, 40)).is_ok());
+        assert!(check_git_version((3, 0)).is_ok());
+    }
+
+    #[test]
+    fn test_parse_pr_number() {
+        assert_eq!(parse_pr_number("Add feature (#123)"), Some(123));
+        assert_eq!(parse_pr_number("Revert \"Fix (#4)\" (#56)"), Some(56));
+        assert_eq!(parse_pr_number("Fix (#abc)"), None);
+        assert_eq!(parse_pr_number("No pull request"), None);
+    }
+
+    #[test]
+    fn test_resolve_pr_head_prefers_local_pull_ref() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("README.md", "hello\n", true).unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let head_sha = tmp_repo.get_head_commit_sha().unwrap();
+        tmp_repo
+            .git_command(&["update-ref", "refs/merge-requests/7/head", &head_sha])
+            .unwrap();
+
+        let repo = tmp_repo.gitai_repo();
+        assert_eq!(resolve_pr_head(repo, None, 7).unwrap(), head_sha);
+        assert!(resolve_pr_head(repo, None, 8).is_err());
+    }
+
+    #[test]
+    fn test_reconcile_merge_commit_and_missing_pr_number() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("README.md", "hello\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let main_branch = tmp_repo.current_branch().unwrap();
+
+        tmp_repo.create_branch("feature").unwrap();
+        tmp_repo
+            .write_file("feature.rs", "fn one() {}\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("Add feature").unwrap();
+
+        // Merged the way a hosting service does: no hooks, so no note on the merge commit
+        tmp_repo.switch_branch(&main_branch).unwrap();
+        tmp_repo
+            .git_command(&["merge", "--no-ff", "feature", "-m", "Merge pull request #7"])
+            .unwrap();
+        let merge_sha = tmp_repo.get_head_commit_sha().unwrap();
+        let repo = tmp_repo.gitai_repo();
+
+        let report = run(repo, "HEAD", &ReconcileOptions::default()).unwrap();
+        assert_eq!(report.commit_sha, merge_sha);
+        assert_eq!(report.strategy, MergeStrategy::Merged);
+        assert!(report.rewritten.is_empty());
+        assert!(show_authorship_note(repo, &merge_sha).is_none());
+
+        // A commit without an attributed note or a PR reference can't be reconciled
+        tmp_repo
+            .git_command(&["commit", "--allow-empty", "-m", "Direct push"])
+            .unwrap();
+        let err = run(repo, "HEAD", &ReconcileOptions::default()).unwrap_err();
+        assert!(err.to_string().contains("--pr"));
+    }
+
+    /// A `feature` branch with one AI commit per file, and a human commit on the main branch
+    /// since it was created. Returns the main branch, checked out.
+    fn pr_with_ai_commits(tmp_repo: &TmpRepo, files: &[&str]) -> String {
+        tmp_repo.write_file("README.md", "hello\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let main_branch = tmp_repo.current_branch().unwrap();
+
+        tmp_repo.create_branch("feature").unwrap();
+        for file in files {
+            tmp_repo
+                .write_file(
+                    file,
+                    &format!("fn {}() {{}}\n", file.replace(".rs", "")),
+                    true,
+                )
+                .unwrap();
+            tmp_repo
+                .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+                .unwrap();
+            tmp_repo
+                .commit_with_message(&format!("Add {}", file))
+                .unwrap();
+        }
+
+        tmp_repo.switch_branch(&main_branch).unwrap();
+        tmp_repo.write_file("other.txt", "other\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Unrelated change").unwrap();
+        main_branch
+    }
+
+    fn has_ai_line(repo: &Repository, commit_sha: &str, file: &str) -> bool {
+        let note = show_authorship_note(repo, commit_sha).expect("commit should have a note");
+        AuthorshipLog::deserialize_from_string(&note)
+            .unwrap()
+            .get_line_prompt_hash(file, 1)
+            .is_some()
+    }
+
+    #[test]
+    fn test_reconcile_squash_merge() {
+        if !git_can_reconcile() {
+            return;
+        }
+        let tmp_repo = TmpRepo::new().unwrap();
+        pr_with_ai_commits(&tmp_repo, &["one.rs", "two.rs"]);
+
+        // Squashed the way a hosting service does: no hooks, so no note
+        tmp_repo
+            .git_command(&["merge", "--squash", "feature"])
+            .unwrap();
+        tmp_repo
+            .git_command(&["commit", "-m", "Add feature (#7)"])
+            .unwrap();
+        let squash_sha = tmp_repo.get_head_commit_sha().unwrap();
+        tmp_repo
+            .git_command(&["update-ref", "refs/pull/7/head", "feature"])
+            .unwrap();
+        let repo = tmp_repo.gitai_repo();
+        let pr_head = repo.revparse_single("feature").unwrap().id();
+        assert!(show_authorship_note(repo, &squash_sha).is_none());
+
+        let report = run(repo, "HEAD", &ReconcileOptions::default()).unwrap();
+        assert_eq!(report.strategy, MergeStrategy::Squash);
+        assert_eq!(report.pr_head, Some(pr_head));
+        assert_eq!(report.rewritten, vec![squash_sha.clone()]);
+        assert_eq!(report.pushed_to, None);
+        assert!(has_ai_line(repo, &squash_sha, "one.rs"));
+        assert!(has_ai_line(repo, &squash_sha, "two.rs"));
+    }
+
+    #[test]
+    fn test_reconcile_rebase_merge() {
+        if !git_can_reconcile() {
+            return;
+        }
+        let tmp_repo = TmpRepo::new().unwrap();
+        pr_with_ai_commits(&tmp_repo, &["one.rs", "two.rs"]);
+
+        tmp_repo
+            .git_command(&["cherry-pick", "feature~1", "feature"])
+            .unwrap();
+        let repo = tmp_repo.gitai_repo();
+        let first_sha = repo.revparse_single("HEAD~1").unwrap().id();
+        let second_sha = repo.revparse_single("HEAD").unwrap().id();
+
+        let options = ReconcileOptions {
+            pr_head: Some("feature".to_string()),
+            ..Default::default()
+        };
+        let report = run(repo, "HEAD", &options).unwrap();
+        assert_eq!(report.strategy, MergeStrategy::RebaseMerge);
+        assert_eq!(
+            report.rewritten,
+            vec![first_sha.clone(), second_sha.clone()]
+        );
+        assert!(has_ai_line(repo, &first_sha, "one.rs"));
+        assert!(has_ai_line(repo, &second_sha, "two.rs"));
+    }
+
+    #[test]
+    fn test_reconcile_push_failure_is_an_error() {
+        if !git_can_reconcile() {
+            return;
+        }
+        let tmp_repo = TmpRepo::new().unwrap();
+        pr_with_ai_commits(&tmp_repo, &["one.rs"]);
+        tmp_repo
+            .git_command(&["merge", "--squash", "feature"])
+            .unwrap();
+        tmp_repo
+            .git_command(&["commit", "-m", "Add feature"])
+            .unwrap();
+        let squash_sha = tmp_repo.get_head_commit_sha().unwrap();
+        let repo = tmp_repo.gitai_repo();
+
+        let options = ReconcileOptions {
+            pr_head: Some("feature".to_string()),
+            remote: Some("missing".to_string()),
+            push: true,
+            ..Default::default()
+        };
+        let err = run(repo, "HEAD", &options).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("pushing their notes to missing failed"),
+            "unexpected error: {}",
+            err
+        );
+        // The note is still reconstructed locally
+        assert!(has_ai_line(repo, &squash_sha, "one.rs"));
+    }
+}
@@ -0,0 +1,335 @@
+use crate::authorship::rebase_authorship::{
+    rewrite_authorship_after_squash_or_rebase, rewrite_single_commit_authorship,
+};
+use crate::error::GitAiError;
+use crate::git::refs::{list_authorship_notes, show_authorship_note};
+use crate::git::repository::{Repository, exec_git, exec_git_stdin};
+use std::collections::{HashMap, HashSet};
+
+const ZERO_SHA: &str = "0000000000000000000000000000000000000000";
+
+/// One `<old-sha> <new-sha> <ref-name>` line from a post-receive hook's stdin
+#[derive(Debug, Clone, PartialEq)]
+pub struct RefUpdate {
+    pub old_sha: String,
+    pub new_sha: String,
+    pub ref_name: String,
+}
+
+#[derive(Debug, Default)]
+pub struct PostReceiveReport {
+    /// Pushed commits that received a note, with the commit it was rebuilt from
+    pub reconstructed: Vec<(String, String)>,
+    /// Pushed commits without a note whose tree matches no annotated commit
+    pub unmatched: usize,
+    /// Commits whose reconstruction failed, with the error
+    pub failed: Vec<(String, String)>,
+}
+
+pub fn parse_ref_updates(input: &str) -> Vec<RefUpdate> {
+    input
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some(RefUpdate {
+                old_sha: parts.next()?.to_string(),
+                new_sha: parts.next()?.to_string(),
+                ref_name: parts.next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Attach notes to pushed commits that arrived without one because they were rebased or
+/// squashed without git-ai-tracker. A commit is rebuilt from an annotated commit with the
+/// same tree: its note is copied when both commits make the same change, otherwise the
+/// authorship is reconstructed as for `squash-authorship`. Works in bare repositories.
+pub fn post_receive(
+    repo: &Repository,
+    updates: &[RefUpdate],
+) -> Result<PostReceiveReport, GitAiError> {
+    let mut report = PostReceiveReport::default();
+
+    let pushed = pushed_commits(repo, updates)?;
+    if pushed.is_empty() {
+        return Ok(report);
+    }
+    let annotated = list_authorship_notes(repo)?;
+    if annotated.is_empty() {
+        return Ok(report);
+    }
+
+    let annotated_trees = commit_trees(repo, &annotated)?;
+    let mut annotated_by_tree: HashMap<&str, &str> = HashMap::new();
+    for (commit_sha, tree_sha) in &annotated_trees {
+        annotated_by_tree.insert(tree_sha.as_str(), commit_sha.as_str());
+    }
+    let pushed_trees: HashMap<String, String> = commit_trees(repo, &pushed)?.into_iter().collect();
+
+    for commit_sha in &pushed {
+        if show_authorship_note(repo, commit_sha).is_some() {
+            continue;
+        }
+        let commit = repo.find_commit(commit_sha.clone())?;
+        if commit.parent_count()? > 1 {
+            continue;
+        }
+        let Some(source_sha) = pushed_trees
+            .get(commit_sha)
+            .and_then(|tree_sha| annotated_by_tree.get(tree_sha.as_str()))
+            .map(|sha| sha.to_string())
+        else {
+            report.unmatched += 1;
+            continue;
+        };
+
+        let result = if same_change(repo, &source_sha, commit_sha)? {
+            rewrite_single_commit_authorship(repo, &source_sha, commit_sha, "")
+        } else {
+            rewrite_authorship_after_squash_or_rebase(repo, "", &source_sha, commit_sha, false)
+                .map(|_| ())
+        };
+        match result {
+            Ok(()) => report.reconstructed.push((commit_sha.clone(), source_sha)),
+            Err(e) => report.failed.push((commit_sha.clone(), e.to_string())),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Commits introduced by the ref updates, oldest first. Deleted refs and notes refs are
+/// skipped; a new ref contributes the commits no ref had before the push.
+fn pushed_commits(repo: &Repository, updates: &[RefUpdate]) -> Result<Vec<String>, GitAiError> {
+    let mut seen = HashSet::new();
+    let mut commits = Vec::new();
+
+    // The refs already point at the pushed commits, so for a new ref every updated ref is
+    // left out of `--all` and the existing ones are stood in for by their old values
+    let mut before_push = Vec::new();
+    for update in updates {
+        before_push.push(format!("--exclude={}", update.ref_name));
+    }
+    before_push.push("--exclude=refs/notes/*".to_string());
+    before_push.push("--all".to_string());
+    for update in updates {
+        if update.old_sha != ZERO_SHA {
+            before_push.push(update.old_sha.clone());
+        }
+    }
+
+    for update in updates {
+        if update.new_sha == ZERO_SHA || update.ref_name.starts_with("refs/notes/") {
+            continue;
+        }
+
+        let mut args = repo.global_args_for_exec();
+        args.push("rev-list".to_string());
+        args.push("--reverse".to_string());
+        args.push(update.new_sha.clone());
+        if update.old_sha == ZERO_SHA {
+            args.push("--not".to_string());
+            args.extend(before_push.iter().cloned());
+        } else {
+            args.push(format!("^{}", update.old_sha));
+        }
+        args.push("--".to_string());
+
+        let output = exec_git(&args)?;
+        for sha in String::from_utf8(output.stdout)?.lines() {
+            if seen.insert(sha.to_string()) {
+                commits.push(sha.to_string());
+            }
+        }
+    }
+
+    Ok(commits)
+}
+
+/// (commit, tree) pairs in one `git log` call; commits that no longer exist are skipped
+fn commit_trees(
+    repo: &Repository,
+    commits: &[String],
+) -> Result<Vec<(String, String)>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("log".to_string());
+    args.push("--no-walk=unsorted".to_string());
+    args.push("--ignore-missing".to_string());
+    args.push("--format=%H %T".to_string());
+    args.push("--stdin".to_string());
+
+    let output = exec_git_stdin(&args, commits.join("\n").as_bytes())?;
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .filter_map(|line| {
+            let (commit_sha, tree_sha) = line.split_once(' ')?;
+            Some((commit_sha.to_string(), tree_sha.to_string()))
+        })
+        .collect())
+}
+
+/// Whether two commits with the same tree also start from the same tree, i.e. one is the
+/// other with a new message, author or parent commit
+fn same_change(repo: &Repository, source_sha: &str, commit_sha: &str) -> Result<bool, GitAiError> {
+    let source = repo.find_commit(source_sha.to_string())?;
+    let commit = repo.find_commit(commit_sha.to_string())?;
+    if source.parent_count()? != 1 || commit.parent_count()? != 1 {
+        return Ok(false);
+    }
+    Ok(source.parent(0)?.tree()?.id() == commit.parent(0)?.tree()?.id())
+}
+
+pub fn write_report_to_terminal(report: &PostReceiveReport) {
+    for (commit_sha, source_sha) in &report.reconstructed {
+        println!(
+            "git-ai-tracker: rebuilt authorship for {} from {}",
+            &commit_sha[..8.min(commit_sha.len())],
+            &source_sha[..8.min(source_sha.len())]
+        );
+    }
+    for (commit_sha, error) in &report.failed {
+        println!(
+            "git-ai-tracker: could not rebuild authorship for {}: {}",
+            &commit_sha[..8.min(commit_sha.len())],
+            error
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::find_repository_in_path;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_parse_ref_updates() {
+        let updates = parse_ref_updates(&format!(
+            "{} abc123 refs/heads/main\n\ndef456 {} refs/heads/old\n",
+            ZERO_SHA, ZERO_SHA
+        ));
+        assert_eq!(
+            updates,
+            vec![
+                RefUpdate {
+                    old_sha: ZERO_SHA.to_string(),
+                    new_sha: "abc123".to_string(),
+                    ref_name: "refs/heads/main".to_string(),
+                },
+                RefUpdate {
+                    old_sha: "def456".to_string(),
+                    new_sha: ZERO_SHA.to_string(),
+                    ref_name: "refs/heads/old".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_post_receive_rebuilds_notes_in_bare_repo() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("README.md", "hello\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_author("test_user")
+            .unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+        let base_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        tmp_repo
+            .write_file("feature.rs", "fn one() {}\nfn two() {}\n", true)
+            .unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", Some("claude-3-sonnet"), Some("cursor"))
+            .unwrap();
+        tmp_repo.commit_with_message("Add feature").unwrap();
+        let original_sha = tmp_repo.get_head_commit_sha().unwrap();
+        tmp_repo.git_command(&["branch", "pr"]).unwrap();
+
+        // Reworded without hooks, as a hosting service's rebase would
+        tmp_repo
+            .git_command(&["commit", "--amend", "-m", "Add feature (reworded)"])
+            .unwrap();
+        let rewritten_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let server_path = tmp_repo.path().join("server.git");
+        let server_path = server_path.to_str().unwrap();
+        tmp_repo
+            .git_command(&["clone", "--bare", "--no-local", ".", server_path])
+            .unwrap();
+        tmp_repo
+            .git_command(&["push", server_path, "refs/notes/ai:refs/notes/ai"])
+            .unwrap();
+
+        // Notes are commits, so the server needs an identity to write them
+        for (key, value) in [("user.name", "Git Server"), ("user.email", "git@server")] {
+            tmp_repo
+                .git_command(&["-C", server_path, "config", key, value])
+                .unwrap();
+        }
+
+        let server = find_repository_in_path(server_path).unwrap();
+        assert!(server.workdir().is_err());
+        assert!(show_authorship_note(&server, &rewritten_sha).is_none());
+
+        let updates = vec![RefUpdate {
+            old_sha: base_sha,
+            new_sha: rewritten_sha.clone(),
+            ref_name: "refs/heads/main".to_string(),
+        }];
+        let report = post_receive(&server, &updates).unwrap();
+        assert_eq!(
+            report.reconstructed,
+            vec![(rewritten_sha.clone(), original_sha)]
+        );
+        assert_eq!(report.unmatched, 0);
+        assert!(report.failed.is_empty());
+
+        let note = show_authorship_note(&server, &rewritten_sha).unwrap();
+        assert!(note.contains("feature.rs"));
+    }
+
+    #[test]
+    fn test_pushed_commits_of_new_refs_sharing_history() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("README.md", "hello\n", true).unwrap();
+        tmp_repo.commit_with_message("Initial commit").unwrap();
+
+        let server_path = tmp_repo.path().join("server.git");
+        let server_path = server_path.to_str().unwrap();
+        tmp_repo
+            .git_command(&["clone", "--bare", "--no-local", ".", server_path])
+            .unwrap();
+
+        tmp_repo.write_file("one.txt", "one\n", true).unwrap();
+        tmp_repo.commit_with_message("One").unwrap();
+        let first_sha = tmp_repo.get_head_commit_sha().unwrap();
+        tmp_repo.git_command(&["branch", "first"]).unwrap();
+        tmp_repo.write_file("two.txt", "two\n", true).unwrap();
+        tmp_repo.commit_with_message("Two").unwrap();
+        let second_sha = tmp_repo.get_head_commit_sha().unwrap();
+        tmp_repo.git_command(&["branch", "second"]).unwrap();
+
+        // Both branches are new and the second one contains the first
+        tmp_repo
+            .git_command(&["push", server_path, "first", "second"])
+            .unwrap();
+
+        let server = find_repository_in_path(server_path).unwrap();
+        let updates = vec![
+            RefUpdate {
+                old_sha: ZERO_SHA.to_string(),
+                new_sha: first_sha.clone(),
+                ref_name: "refs/heads/first".to_string(),
+            },
+            RefUpdate {
+                old_sha: ZERO_SHA.to_string(),
+                new_sha: second_sha.clone(),
+                ref_name: "refs/heads/second".to_string(),
+            },
+        ];
+        assert_eq!(
+            pushed_commits(&server, &updates).unwrap(),
+            vec![first_sha, second_sha]
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::authorship::authorship_log_serialization::AuthorshipLog;
 use crate::error::GitAiError;
-use crate::git::refs::notes_add;
+use crate::git::refs::notes_add_authorship;
 use crate::git::refs::show_authorship_note;
 use crate::git::repo_storage::RepoStorage;
 use crate::git::repository::Repository;
@@ -122,13 +122,8 @@ pub fn run(repo: &Repository, json_output: bool) -> Result<(), GitAiError> {
                         None,
                     );
 
-                // Serialize the authorship log
-                let authorship_json = authorship_log.serialize_to_string().map_err(|_| {
-                    GitAiError::Generic("Failed to serialize authorship log".to_string())
-                })?;
-
                 // Create the authorship log note
-                notes_add(repo, child_commit, &authorship_json)?;
+                notes_add_authorship(repo, child_commit, &authorship_log)?;
 
                 if json_output {
                     // Store the authorship log for JSON output
@@ -0,0 +1,66 @@
+use crate::error::GitAiError;
+use crate::git::repository::Repository;
+use crate::git::signing::{SignatureStatus, verify_authorship_note};
+
+/// Check the authorship note signatures for a commit, or for every commit in a revision
+/// range (`<base>..<head>`). Results are newest first.
+pub fn run(repo: &Repository, spec: &str) -> Result<Vec<(String, SignatureStatus)>, GitAiError> {
+    let commits = if spec.contains("..") {
+        repo.rev_list(spec)?
+    } else {
+        vec![repo.revparse_single(spec)?.peel_to_commit()?.id()]
+    };
+
+    commits
+        .into_iter()
+        .map(|commit_sha| {
+            let status = verify_authorship_note(repo, &commit_sha)?;
+            Ok((commit_sha, status))
+        })
+        .collect()
+}
+
+/// Whether every note that exists carries a valid, trusted signature
+pub fn all_signed(results: &[(String, SignatureStatus)]) -> bool {
+    results
+        .iter()
+        .all(|(_, status)| matches!(status, SignatureStatus::NoNote) || status.is_valid())
+}
+
+pub fn write_report_to_terminal(results: &[(String, SignatureStatus)]) {
+    for (commit_sha, status) in results {
+        let short_sha = &commit_sha[..8.min(commit_sha.len())];
+        match status {
+            SignatureStatus::NoNote => println!("{}  no authorship note", short_sha),
+            SignatureStatus::Unsigned => println!("{}  UNSIGNED", short_sha),
+            SignatureStatus::Valid { signer } => {
+                println!("{}  good signature from {}", short_sha, signer)
+            }
+            SignatureStatus::Unverified { signer, reason } => match signer {
+                Some(signer) => {
+                    println!(
+                        "{}  UNVERIFIED signature from {}: {}",
+                        short_sha, signer, reason
+                    )
+                }
+                None => println!("{}  UNVERIFIED signature: {}", short_sha, reason),
+            },
+            SignatureStatus::Invalid { reason } => {
+                println!(
+                    "{}  BAD signature (note was modified?): {}",
+                    short_sha, reason
+                )
+            }
+        }
+    }
+
+    let count = |f: fn(&SignatureStatus) -> bool| results.iter().filter(|(_, s)| f(s)).count();
+    println!(
+        "{} valid, {} unsigned, {} unverified, {} bad, {} without a note",
+        count(|s| s.is_valid()),
+        count(|s| matches!(s, SignatureStatus::Unsigned)),
+        count(|s| matches!(s, SignatureStatus::Unverified { .. })),
+        count(|s| matches!(s, SignatureStatus::Invalid { .. })),
+        count(|s| matches!(s, SignatureStatus::NoNote)),
+    );
+}
@@ -12,6 +12,11 @@ use crate::git::repository::Repository;
 pub struct Config {
     git_path: String,
     ignore_prompts: bool,
+    redact_prompts: bool,
+    redaction_patterns: Vec<String>,
+    sign_authorship: bool,
+    formatting_tolerant_attribution: bool,
+    working_log_max_age_days: u64,
     allow_repositories: HashSet<String>,
 }
 #[derive(Deserialize)]
@@ -21,6 +26,16 @@ struct FileConfig {
     #[serde(default)]
     ignore_prompts: Option<bool>,
     #[serde(default)]
+    redact_prompts: Option<bool>,
+    #[serde(default)]
+    redaction_patterns: Option<Vec<String>>,
+    #[serde(default)]
+    sign_authorship: Option<bool>,
+    #[serde(default)]
+    formatting_tolerant_attribution: Option<bool>,
+    #[serde(default)]
+    working_log_max_age_days: Option<u64>,
+    #[serde(default)]
     allow_repositories: Option<Vec<String>>,
 }
 
@@ -48,6 +63,32 @@ impl Config {
         self.ignore_prompts
     }
 
+    /// Whether secrets and PII are masked in transcripts before they are stored in notes.
+    pub fn get_redact_prompts(&self) -> bool {
+        self.redact_prompts
+    }
+
+    /// Extra user-configured regexes to mask in stored transcripts.
+    pub fn get_redaction_patterns(&self) -> &[String] {
+        &self.redaction_patterns
+    }
+
+    /// Whether authorship notes are signed with the user's git signing key when written.
+    pub fn get_sign_authorship(&self) -> bool {
+        self.sign_authorship
+    }
+
+    /// Whether lines that only changed formatting (whitespace, quotes, trailing commas) keep
+    /// their author between checkpoints.
+    pub fn get_formatting_tolerant_attribution(&self) -> bool {
+        self.formatting_tolerant_attribution
+    }
+
+    /// Days a working log may go untouched before `git-ai-tracker gc` removes it (0 disables).
+    pub fn get_working_log_max_age_days(&self) -> u64 {
+        self.working_log_max_age_days
+    }
+
     pub fn is_allowed_repository(&self, repository: &Option<Repository>) -> bool {
         // If allowlist is empty, allow everything
         if self.allow_repositories.is_empty() {
@@ -80,6 +121,26 @@ fn build_config() -> Config {
         .as_ref()
         .and_then(|c| c.ignore_prompts)
         .unwrap_or(false);
+    let redact_prompts = file_cfg
+        .as_ref()
+        .and_then(|c| c.redact_prompts)
+        .unwrap_or(true);
+    let redaction_patterns = file_cfg
+        .as_ref()
+        .and_then(|c| c.redaction_patterns.clone())
+        .unwrap_or_default();
+    let sign_authorship = file_cfg
+        .as_ref()
+        .and_then(|c| c.sign_authorship)
+        .unwrap_or(false);
+    let formatting_tolerant_attribution = file_cfg
+        .as_ref()
+        .and_then(|c| c.formatting_tolerant_attribution)
+        .unwrap_or(true);
+    let working_log_max_age_days = file_cfg
+        .as_ref()
+        .and_then(|c| c.working_log_max_age_days)
+        .unwrap_or(30);
     let allow_repositories = file_cfg
         .as_ref()
         .and_then(|c| c.allow_repositories.clone())
@@ -92,6 +153,11 @@ fn build_config() -> Config {
     Config {
         git_path,
         ignore_prompts,
+        redact_prompts,
+        redaction_patterns,
+        sign_authorship,
+        formatting_tolerant_attribution,
+        working_log_max_age_days,
         allow_repositories,
     }
 }
@@ -1,6 +1,6 @@
 use crate::error::GitAiError;
 use crate::git::repository::{Repository, Tree, exec_git};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 #[allow(dead_code)]
@@ -61,7 +61,6 @@ pub struct DiffDelta {
     status: DiffStatus,
     old_file: DiffFile,
     new_file: DiffFile,
-    #[allow(dead_code)]
     similarity: u32,
 }
 
@@ -74,12 +73,10 @@ impl DiffDelta {
         &self.new_file
     }
 
-    #[allow(dead_code)]
     pub fn status(&self) -> DiffStatus {
         self.status
     }
 
-    #[allow(dead_code)]
     pub fn similarity(&self) -> u32 {
         self.similarity
     }
@@ -98,6 +95,19 @@ impl Diff {
     pub fn len(&self) -> usize {
         self.deltas.len()
     }
+
+    /// Map of old path -> new path for every rename detected in this diff
+    pub fn renames(&self) -> HashMap<String, String> {
+        self.deltas
+            .iter()
+            .filter(|delta| delta.status() == DiffStatus::Renamed && delta.similarity() > 0)
+            .filter_map(|delta| {
+                let old_path = delta.old_file().path()?.to_string_lossy().to_string();
+                let new_path = delta.new_file().path()?.to_string_lossy().to_string();
+                Some((old_path, new_path))
+            })
+            .collect()
+    }
 }
 
 impl Repository {
@@ -145,11 +155,13 @@ impl Repository {
         // --raw: generate diff in raw format
         // -z: NUL-separated output
         // --no-abbrev: show full object names
+        // -M: detect renames regardless of the user's diff.renames config
         let mut args = self.global_args_for_exec();
         args.push("diff".to_string());
         args.push("--raw".to_string());
         args.push("-z".to_string());
         args.push("--no-abbrev".to_string());
+        args.push("-M".to_string());
         args.push(old_oid);
         args.push(new_oid);
 
@@ -166,6 +178,18 @@ impl Repository {
 
         Ok(Diff { deltas })
     }
+
+    /// Files renamed between two commits, as a map of old path -> new path
+    pub fn renamed_files(
+        &self,
+        from_commit: &str,
+        to_commit: &str,
+    ) -> Result<HashMap<String, String>, GitAiError> {
+        let from_tree = self.find_commit(from_commit.to_string())?.tree()?;
+        let to_tree = self.find_commit(to_commit.to_string())?.tree()?;
+        let diff = self.diff_tree_to_tree(Some(&from_tree), Some(&to_tree), None, None)?;
+        Ok(diff.renames())
+    }
 }
 
 /// Parse the raw output from git diff --raw -z
@@ -174,7 +198,7 @@ impl Repository {
 /// :<old_mode> <new_mode> <old_hash> <new_hash> <status>\0<path>\0
 ///
 /// For renames/copies:
-/// :<old_mode> <new_mode> <old_hash> <new_hash> R<score>\0<path>\0<old_path>\0
+/// :<old_mode> <new_mode> <old_hash> <new_hash> R<score>\0<old_path>\0<new_path>\0
 fn parse_diff_raw(data: &[u8]) -> Result<Vec<DiffDelta>, GitAiError> {
     let mut deltas = Vec::new();
     let mut parts = data
@@ -236,13 +260,13 @@ fn parse_diff_raw(data: &[u8]) -> Result<Vec<DiffDelta>, GitAiError> {
             0
         };
 
-        // For renames and copies, there are two paths
+        // For renames and copies, there are two paths: the source comes first, then the destination
         let (new_path, old_path) = if matches!(status, DiffStatus::Renamed | DiffStatus::Copied) {
-            let old_path_bytes = parts
+            let new_path_bytes = parts
                 .next()
-                .ok_or_else(|| GitAiError::Generic("Missing old path for rename/copy".into()))?;
-            let old_path_str = std::str::from_utf8(old_path_bytes)?;
-            (path.to_string(), Some(old_path_str.to_string()))
+                .ok_or_else(|| GitAiError::Generic("Missing new path for rename/copy".into()))?;
+            let new_path_str = std::str::from_utf8(new_path_bytes)?;
+            (new_path_str.to_string(), Some(path.to_string()))
         } else {
             (path.to_string(), None)
         };
@@ -299,7 +323,7 @@ mod tests {
         raw.extend_from_slice(b":100644 000000 1234567890abcdef1234567890abcdef12345678 0000000000000000000000000000000000000000 D\0src/old.rs\0");
 
         // Renamed file with 95% similarity
-        raw.extend_from_slice(b":100644 100644 abcdef1234567890abcdef1234567890abcdef12 abcdef1234567890abcdef1234567890abcdef12 R95\0src/renamed.rs\0src/original.rs\0");
+        raw.extend_from_slice(b":100644 100644 abcdef1234567890abcdef1234567890abcdef12 abcdef1234567890abcdef1234567890abcdef12 R95\0src/original.rs\0src/renamed.rs\0");
 
         let deltas = parse_diff_raw(&raw).expect("parse should succeed");
 
@@ -329,4 +353,22 @@ mod tests {
             Path::new("src/original.rs")
         );
     }
+
+    #[test]
+    fn test_diff_renames() {
+        let mut raw = Vec::new();
+        raw.extend_from_slice(b":100644 100644 5716ca5987cbf97d6bb54920bea6adde242d87e6 8f94139338f9404f26296befa88755fc2598c289 M\0src/lib.rs\0");
+        raw.extend_from_slice(b":100644 100644 abcdef1234567890abcdef1234567890abcdef12 1234567890abcdef1234567890abcdef12345678 R087\0src/original.rs\0src/renamed.rs\0");
+
+        let diff = Diff {
+            deltas: parse_diff_raw(&raw).expect("parse should succeed"),
+        };
+        let renames = diff.renames();
+
+        assert_eq!(renames.len(), 1);
+        assert_eq!(
+            renames.get("src/original.rs").map(String::as_str),
+            Some("src/renamed.rs")
+        );
+    }
 }
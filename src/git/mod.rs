@@ -5,6 +5,7 @@ pub mod repository;
 pub use repository::{find_repository, find_repository_in_path};
 pub mod repo_storage;
 pub mod rewrite_log;
+pub mod signing;
 pub mod status;
 #[cfg(feature = "test-support")]
 pub mod test_utils;
@@ -1,14 +1,27 @@
-use crate::authorship::authorship_log_serialization::{AUTHORSHIP_LOG_VERSION, AuthorshipLog};
+use crate::authorship::authorship_log_serialization::{
+    AuthorshipLog, SUPPORTED_AUTHORSHIP_LOG_VERSIONS,
+};
+use crate::authorship::transcript::Message;
 use crate::authorship::working_log::Checkpoint;
+use crate::config::Config;
 use crate::error::GitAiError;
 use crate::git::repository::{Repository, exec_git, exec_git_stdin};
+use crate::git::signing::sign_note;
 use crate::utils::debug_log;
 use serde_json;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
 
 // Modern refspecs without force to enable proper merging
 pub const AI_AUTHORSHIP_REFNAME: &str = "ai";
 pub const AI_AUTHORSHIP_PUSH_REFSPEC: &str = "refs/notes/ai:refs/notes/ai";
 
+// Content-addressed transcript store. Each transcript blob is attached as a note to itself,
+// which keeps it reachable and lets it sync (and merge) exactly like refs/notes/ai.
+pub const AI_PROMPTS_REFNAME: &str = "ai-prompts";
+pub const AI_PROMPTS_REF: &str = "refs/notes/ai-prompts";
+pub const AI_PROMPTS_PUSH_REFSPEC: &str = "refs/notes/ai-prompts:refs/notes/ai-prompts";
+
 pub fn notes_add(
     repo: &Repository,
     commit_sha: &str,
@@ -28,6 +41,19 @@ pub fn notes_add(
     Ok(())
 }
 
+/// Remove the authorship note for `commit_sha`, if there is one
+pub fn notes_remove(repo: &Repository, commit_sha: &str) -> Result<(), GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("notes".to_string());
+    args.push("--ref=ai".to_string());
+    args.push("remove".to_string());
+    args.push("--ignore-missing".to_string());
+    args.push(commit_sha.to_string());
+
+    exec_git(&args)?;
+    Ok(())
+}
+
 // Show an authorship note and return its JSON content if found, or None if it doesn't exist.
 pub fn show_authorship_note(repo: &Repository, commit_sha: &str) -> Option<String> {
     let mut args = repo.global_args_for_exec();
@@ -46,10 +72,49 @@ pub fn show_authorship_note(repo: &Repository, commit_sha: &str) -> Option<Strin
     }
 }
 
+/// Serialize an authorship log and store it as the note for `commit_sha`, moving prompt
+/// transcripts into the prompt store so the note only carries a reference to them, and
+/// fingerprinting every attributed line against the commit's contents. The note is signed
+/// when `sign_authorship` is enabled.
+pub fn notes_add_authorship(
+    repo: &Repository,
+    commit_sha: &str,
+    authorship_log: &AuthorshipLog,
+) -> Result<(), GitAiError> {
+    let mut authorship_log = authorship_log.clone();
+    store_prompt_transcripts(repo, &mut authorship_log)?;
+    authorship_log.compute_fingerprints(|file_path| {
+        repo.file_content_at_commit(commit_sha, file_path)
+            .ok()
+            .flatten()
+    });
+
+    let authorship_json = authorship_log
+        .serialize_to_string()
+        .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
+    if !Config::get().get_sign_authorship() {
+        return notes_add(repo, commit_sha, &authorship_json);
+    }
+
+    // A signing failure (e.g. no key configured) must not lose the attribution itself; the
+    // note is stored unsigned and shows up as such in verify-authorship
+    match sign_note(repo, &authorship_json) {
+        Ok(signed) => notes_add(repo, commit_sha, &signed),
+        Err(e) => {
+            eprintln!(
+                "Warning: failed to sign authorship note for {}: {}",
+                commit_sha, e
+            );
+            notes_add(repo, commit_sha, &authorship_json)
+        }
+    }
+}
+
 // Show an authorship note and return its JSON content if found, or None if it doesn't exist.
 pub fn get_authorship(repo: &Repository, commit_sha: &str) -> Option<AuthorshipLog> {
     let content = show_authorship_note(repo, commit_sha)?;
-    let authorship_log = AuthorshipLog::deserialize_from_string(&content).ok()?;
+    let mut authorship_log = AuthorshipLog::deserialize_from_string(&content).ok()?;
+    resolve_prompt_transcripts(repo, &mut authorship_log);
     Some(authorship_log)
 }
 
@@ -81,17 +146,142 @@ pub fn get_reference_as_authorship_log_v3(
         }
     };
 
-    // Check version compatibility
-    if authorship_log.metadata.schema_version != AUTHORSHIP_LOG_VERSION {
+    // Check version compatibility (older versions are upgraded on read)
+    if !SUPPORTED_AUTHORSHIP_LOG_VERSIONS.contains(&authorship_log.metadata.schema_version.as_str())
+    {
         return Err(GitAiError::Generic(format!(
-            "Unsupported authorship log version: {} (expected: {})",
-            authorship_log.metadata.schema_version, AUTHORSHIP_LOG_VERSION
+            "Unsupported authorship log version: {} (supported: {})",
+            authorship_log.metadata.schema_version,
+            SUPPORTED_AUTHORSHIP_LOG_VERSIONS.join(", ")
         )));
     }
 
+    let mut authorship_log = authorship_log;
+    resolve_prompt_transcripts(repo, &mut authorship_log);
     Ok(authorship_log)
 }
 
+/// List the commits that have a note in refs/notes/ai
+pub fn list_authorship_notes(repo: &Repository) -> Result<Vec<String>, GitAiError> {
+    if !ref_exists(repo, &format!("refs/notes/{}", AI_AUTHORSHIP_REFNAME)) {
+        return Ok(Vec::new());
+    }
+
+    let mut args = repo.global_args_for_exec();
+    args.push("notes".to_string());
+    args.push(format!("--ref={}", AI_AUTHORSHIP_REFNAME));
+    args.push("list".to_string());
+
+    // Each line is "<note blob> <annotated object>"
+    let output = exec_git(&args)?;
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(|sha| sha.to_string())
+        .collect())
+}
+
+/// Write every inline transcript in `authorship_log` to the prompt store and replace it
+/// with a `messages_ref`. Identical transcripts map to the same blob, so a session that
+/// spans many commits is only stored once.
+pub fn store_prompt_transcripts(
+    repo: &Repository,
+    authorship_log: &mut AuthorshipLog,
+) -> Result<(), GitAiError> {
+    for prompt_record in authorship_log.metadata.prompts.values_mut() {
+        if prompt_record.messages.is_empty() {
+            continue;
+        }
+        let blob_sha = prompt_store_put(repo, &prompt_record.messages)?;
+        prompt_record.messages_ref = Some(blob_sha);
+        prompt_record.messages.clear();
+    }
+    Ok(())
+}
+
+/// Fill in `messages` for prompt records that reference the prompt store. Transcripts
+/// that cannot be found (e.g. the store hasn't been fetched) are left empty, and the
+/// reference is kept so rewriting the note doesn't drop it.
+pub fn resolve_prompt_transcripts(repo: &Repository, authorship_log: &mut AuthorshipLog) {
+    for prompt_record in authorship_log.metadata.prompts.values_mut() {
+        if !prompt_record.messages.is_empty() {
+            continue;
+        }
+        if let Some(blob_sha) = &prompt_record.messages_ref {
+            match prompt_store_get(repo, blob_sha) {
+                Some(messages) => prompt_record.messages = messages,
+                None => debug_log(&format!("prompt transcript {} not found", blob_sha)),
+            }
+        }
+    }
+}
+
+/// Whether a `messages_ref` can be resolved from the prompt store
+pub fn prompt_transcript_exists(repo: &Repository, blob_sha: &str) -> bool {
+    prompt_store_get(repo, blob_sha).is_some()
+}
+
+fn prompt_store_put(repo: &Repository, messages: &[Message]) -> Result<String, GitAiError> {
+    let content = serde_json::to_string(messages)?;
+
+    let mut args = repo.global_args_for_exec();
+    args.push("hash-object".to_string());
+    args.push("-w".to_string());
+    args.push("--stdin".to_string());
+    let output = exec_git_stdin(&args, content.as_bytes())?;
+    let blob_sha = String::from_utf8(output.stdout)?.trim().to_string();
+
+    // Attach the blob to itself under refs/notes/ai-prompts (reusing the blob as the note
+    // content) so it stays reachable and travels with the notes.
+    let mut list_args = repo.global_args_for_exec();
+    list_args.push("notes".to_string());
+    list_args.push(format!("--ref={}", AI_PROMPTS_REFNAME));
+    list_args.push("list".to_string());
+    list_args.push(blob_sha.clone());
+    if exec_git(&list_args).is_err() {
+        let mut add_args = repo.global_args_for_exec();
+        add_args.push("notes".to_string());
+        add_args.push(format!("--ref={}", AI_PROMPTS_REFNAME));
+        add_args.push("add".to_string());
+        add_args.push("-f".to_string());
+        add_args.push("-C".to_string());
+        add_args.push(blob_sha.clone());
+        add_args.push(blob_sha.clone());
+        exec_git(&add_args)?;
+    }
+
+    prompt_store_cache()
+        .lock()
+        .unwrap()
+        .insert(blob_sha.clone(), messages.to_vec());
+    Ok(blob_sha)
+}
+
+fn prompt_store_get(repo: &Repository, blob_sha: &str) -> Option<Vec<Message>> {
+    if let Some(messages) = prompt_store_cache().lock().unwrap().get(blob_sha) {
+        return Some(messages.clone());
+    }
+
+    let mut args = repo.global_args_for_exec();
+    args.push("cat-file".to_string());
+    args.push("blob".to_string());
+    args.push(blob_sha.to_string());
+    let output = exec_git(&args).ok()?;
+    let messages: Vec<Message> = serde_json::from_slice(&output.stdout).ok()?;
+
+    prompt_store_cache()
+        .lock()
+        .unwrap()
+        .insert(blob_sha.to_string(), messages.clone());
+    Some(messages)
+}
+
+/// Transcripts are content-addressed, so one cache is valid across repositories.
+fn prompt_store_cache() -> &'static Mutex<HashMap<String, Vec<Message>>> {
+    static CACHE: OnceLock<Mutex<HashMap<String, Vec<Message>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -133,6 +323,187 @@ mod tests {
         );
         assert!(non_existent_content.is_none());
     }
+
+    #[test]
+    fn test_merge_notes_from_ref_combines_conflicting_notes() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\ntwo\n", true).unwrap();
+        tmp_repo.commit_with_message("Add a.txt").unwrap();
+        let first_sha = tmp_repo.get_head_commit_sha().unwrap();
+        tmp_repo.write_file("b.txt", "three\n", true).unwrap();
+        tmp_repo.commit_with_message("Add b.txt").unwrap();
+        let second_sha = tmp_repo.get_head_commit_sha().unwrap();
+        let repo = tmp_repo.gitai_repo();
+
+        let note = |lines: &str| {
+            format!(
+                "a.txt\n  {}\n---\n{{\n  \"schema_version\": \"authorship/4.0.0\",\n  \"base_commit_sha\": \"\",\n  \"prompts\": {{}}\n}}",
+                lines
+            )
+        };
+        notes_add(repo, &first_sha, &note("abc1234 1")).unwrap();
+        notes_add(repo, &second_sha, &note("abc1234 1")).unwrap();
+
+        // Both sides start from the same notes, then diverge
+        let remote_ref = "refs/notes/ai-remote/origin";
+        copy_ref(repo, "refs/notes/ai", remote_ref).unwrap();
+        notes_add(repo, &first_sha, &note("abc1234 1 t=100")).unwrap();
+        for (commit_sha, lines) in [(&first_sha, "def5678 2 t=200"), (&second_sha, "abc1234 2")] {
+            tmp_repo
+                .git_command(&[
+                    "notes",
+                    &format!("--ref={}", remote_ref),
+                    "add",
+                    "-f",
+                    "-m",
+                    &note(lines),
+                    commit_sha,
+                ])
+                .unwrap();
+        }
+
+        merge_notes_from_ref(repo, remote_ref).unwrap();
+
+        let attributions = |commit_sha: &str| -> Vec<(String, Vec<u32>)> {
+            let note = show_authorship_note(repo, commit_sha).unwrap();
+            AuthorshipLog::deserialize_from_string(&note)
+                .unwrap()
+                .attestations[0]
+                .entries
+                .iter()
+                .map(|entry| (entry.hash.clone(), entry.lines()))
+                .collect()
+        };
+
+        // Changed on both sides: the attributions are combined
+        assert_eq!(
+            attributions(&first_sha),
+            vec![
+                ("abc1234".to_string(), vec![1]),
+                ("def5678".to_string(), vec![2])
+            ]
+        );
+        // Changed only on the remote: taken as-is
+        assert_eq!(
+            attributions(&second_sha),
+            vec![("abc1234".to_string(), vec![2])]
+        );
+    }
+
+    #[test]
+    fn test_notes_commit_for_objects_selects_notes() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo.commit_with_message("Add a.txt").unwrap();
+        let first_sha = tmp_repo.get_head_commit_sha().unwrap();
+        tmp_repo.write_file("b.txt", "two\n", true).unwrap();
+        tmp_repo.commit_with_message("Add b.txt").unwrap();
+        let second_sha = tmp_repo.get_head_commit_sha().unwrap();
+        let repo = tmp_repo.gitai_repo();
+
+        notes_add(repo, &first_sha, "first note").unwrap();
+        notes_add(repo, &second_sha, "second note").unwrap();
+
+        let selected = HashSet::from([first_sha.clone()]);
+        let notes_commit = notes_commit_for_objects(repo, "refs/notes/ai", None, &selected)
+            .unwrap()
+            .unwrap();
+        let notes = note_blobs(repo, &notes_commit).unwrap();
+        assert_eq!(notes.len(), 1);
+        assert!(notes.contains_key(&first_sha));
+
+        // Nothing new on top of a base that already has the selected notes
+        assert_eq!(
+            notes_commit_for_objects(repo, "refs/notes/ai", Some(&notes_commit), &selected)
+                .unwrap(),
+            None
+        );
+
+        // Extending the base keeps its notes and makes it the parent
+        let selected = HashSet::from([second_sha.clone()]);
+        let extended =
+            notes_commit_for_objects(repo, "refs/notes/ai", Some(&notes_commit), &selected)
+                .unwrap()
+                .unwrap();
+        assert_eq!(note_blobs(repo, &extended).unwrap().len(), 2);
+        assert_eq!(
+            repo.find_commit(extended).unwrap().parent(0).unwrap().id(),
+            notes_commit
+        );
+    }
+
+    #[test]
+    fn test_prompt_transcripts_are_stored_once_and_resolved() {
+        use crate::authorship::authorship_log::PromptRecord;
+        use crate::authorship::working_log::AgentId;
+
+        let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
+        tmp_repo.commit_with_message("First commit").unwrap();
+        let first_sha = tmp_repo.get_head_commit_sha().unwrap();
+        tmp_repo
+            .write_file("a.txt", "hello\n", true)
+            .expect("Failed to write file");
+        tmp_repo.commit_with_message("Second commit").unwrap();
+        let second_sha = tmp_repo.get_head_commit_sha().unwrap();
+
+        let messages = vec![
+            Message::user("Add a greeting".to_string(), None),
+            Message::assistant("Added hello to a.txt".to_string(), None),
+        ];
+        let mut log = AuthorshipLog::new();
+        log.metadata.prompts.insert(
+            "abc1234".to_string(),
+            PromptRecord {
+                agent_id: AgentId {
+                    tool: "cursor".to_string(),
+                    id: "session_1".to_string(),
+                    model: "gpt-4".to_string(),
+                },
+                human_author: None,
+                messages: messages.clone(),
+                messages_ref: None,
+                total_additions: 1,
+                total_deletions: 0,
+                accepted_lines: 1,
+                overriden_lines: 0,
+                redactions: 0,
+            },
+        );
+
+        let repo = tmp_repo.gitai_repo();
+        notes_add_authorship(repo, &first_sha, &log).unwrap();
+        notes_add_authorship(repo, &second_sha, &log).unwrap();
+
+        // The notes only carry a reference, and both point at the same blob
+        let first_note = show_authorship_note(repo, &first_sha).unwrap();
+        let second_note = show_authorship_note(repo, &second_sha).unwrap();
+        assert!(!first_note.contains("Add a greeting"));
+        let first_raw = AuthorshipLog::deserialize_from_string(&first_note).unwrap();
+        let second_raw = AuthorshipLog::deserialize_from_string(&second_note).unwrap();
+        let blob_sha = first_raw.metadata.prompts["abc1234"]
+            .messages_ref
+            .clone()
+            .expect("messages_ref should be set");
+        assert!(first_raw.metadata.prompts["abc1234"].messages.is_empty());
+        assert_eq!(
+            second_raw.metadata.prompts["abc1234"].messages_ref,
+            Some(blob_sha.clone())
+        );
+
+        // The transcript is stored exactly once in the prompt store
+        let mut args = repo.global_args_for_exec();
+        args.push("notes".to_string());
+        args.push(format!("--ref={}", AI_PROMPTS_REFNAME));
+        args.push("list".to_string());
+        let output = exec_git(&args).unwrap();
+        let listing = String::from_utf8(output.stdout).unwrap();
+        assert_eq!(listing.lines().count(), 1);
+        assert!(listing.contains(&blob_sha));
+
+        // Readers resolve the reference back into messages
+        let resolved = get_authorship(repo, &second_sha).unwrap();
+        assert_eq!(resolved.metadata.prompts["abc1234"].messages, messages);
+    }
 }
 
 /// Sanitize a remote name to create a safe ref name
@@ -163,6 +534,25 @@ pub fn tracking_ref_for_remote(remote_name: &str) -> String {
     format!("refs/notes/ai-remote/{}", sanitize_remote_name(remote_name))
 }
 
+/// Generate a tracking ref name for the prompt store from a specific remote
+/// Returns a ref like "refs/notes/ai-prompts-remote/origin" (same push safety as above)
+pub fn prompts_tracking_ref_for_remote(remote_name: &str) -> String {
+    format!(
+        "refs/notes/ai-prompts-remote/{}",
+        sanitize_remote_name(remote_name)
+    )
+}
+
+/// The remote authorship notes are synced with when code is pushed to or fetched from
+/// `remote`: the repository's `git-ai-tracker.notesRemote` setting, or `remote` itself
+pub fn notes_remote_for(repo: &Repository, remote: &str) -> String {
+    repo.config_get_str("git-ai-tracker.notesRemote")
+        .ok()
+        .flatten()
+        .filter(|notes_remote| !notes_remote.is_empty())
+        .unwrap_or_else(|| remote.to_string())
+}
+
 /// Check if a ref exists in the repository
 pub fn ref_exists(repo: &Repository, ref_name: &str) -> bool {
     let mut args = repo.global_args_for_exec();
@@ -174,12 +564,155 @@ pub fn ref_exists(repo: &Repository, ref_name: &str) -> bool {
     exec_git(&args).is_ok()
 }
 
-/// Merge notes from a source ref into refs/notes/ai
-/// Uses the 'ours' strategy to combine notes without data loss
+/// Merge notes from a source ref into refs/notes/ai.
+/// Notes changed on only one side since the two refs diverged are taken as-is. When both
+/// sides changed the note for the same commit, the two authorship logs are combined with
+/// `AuthorshipLog::merge` rather than keeping ours and dropping theirs.
 pub fn merge_notes_from_ref(repo: &Repository, source_ref: &str) -> Result<(), GitAiError> {
+    let local_ref = format!("refs/notes/{}", AI_AUTHORSHIP_REFNAME);
+    let conflicts = conflicting_notes(repo, &local_ref, source_ref)?;
+
+    // Records the source as merged; conflicting notes keep our side until rewritten below
+    merge_notes_into(repo, AI_AUTHORSHIP_REFNAME, source_ref)?;
+
+    for (commit_sha, our_blob, their_blob) in conflicts {
+        let read_log = |blob_sha: &str| {
+            let content = repo.find_blob(blob_sha.to_string()).ok()?.content().ok()?;
+            AuthorshipLog::deserialize_from_string(&String::from_utf8_lossy(&content)).ok()
+        };
+        let merged = match (read_log(&our_blob), read_log(&their_blob)) {
+            (Some(ours), Some(theirs)) => AuthorshipLog::merge(&ours, &theirs),
+            // An unreadable side can't contribute; keep the readable one
+            (None, Some(theirs)) => theirs,
+            _ => continue,
+        };
+        debug_log(&format!(
+            "Merging conflicting authorship notes for {}",
+            commit_sha
+        ));
+        notes_add_authorship(repo, &commit_sha, &merged)?;
+    }
+    Ok(())
+}
+
+/// Commits whose note differs between two notes refs and was changed on both sides since
+/// their merge base, as (commit, our note blob, their note blob)
+fn conflicting_notes(
+    repo: &Repository,
+    our_ref: &str,
+    their_ref: &str,
+) -> Result<Vec<(String, String, String)>, GitAiError> {
+    if !ref_exists(repo, our_ref) || !ref_exists(repo, their_ref) {
+        return Ok(Vec::new());
+    }
+    let ours = note_blobs(repo, our_ref)?;
+    let theirs = note_blobs(repo, their_ref)?;
+    let base = match repo.merge_base(our_ref.to_string(), their_ref.to_string()) {
+        Ok(base_sha) => note_blobs(repo, &base_sha)?,
+        Err(_) => HashMap::new(),
+    };
+
+    let mut conflicts: Vec<(String, String, String)> = ours
+        .into_iter()
+        .filter_map(|(commit_sha, our_blob)| {
+            let their_blob = theirs.get(&commit_sha)?;
+            let base_blob = base.get(&commit_sha);
+            (our_blob != *their_blob
+                && base_blob != Some(&our_blob)
+                && base_blob != Some(their_blob))
+            .then(|| (commit_sha, our_blob, their_blob.clone()))
+        })
+        .collect();
+    conflicts.sort();
+    Ok(conflicts)
+}
+
+/// Annotated commit -> note blob for every note in a notes commit
+fn note_blobs(
+    repo: &Repository,
+    notes_commit: &str,
+) -> Result<HashMap<String, String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("ls-tree".to_string());
+    args.push("-r".to_string());
+    args.push(notes_commit.to_string());
+
+    // Each line is "<mode> blob <note blob>\t<annotated object, possibly fanned out as ab/cdef...>"
+    let output = exec_git(&args)?;
+    Ok(String::from_utf8(output.stdout)?
+        .lines()
+        .filter_map(|line| {
+            let (meta, path) = line.split_once('\t')?;
+            let blob_sha = meta.split_whitespace().nth(2)?;
+            Some((path.replace('/', ""), blob_sha.to_string()))
+        })
+        .collect())
+}
+
+/// Create a commit for a notes ref that extends `base_commit` (the remote's notes, if any)
+/// with the local notes in `notes_ref` on `objects` only, so notes on anything else stay
+/// local. Returns None when the local notes add nothing to the base.
+pub fn notes_commit_for_objects(
+    repo: &Repository,
+    notes_ref: &str,
+    base_commit: Option<&str>,
+    objects: &HashSet<String>,
+) -> Result<Option<String>, GitAiError> {
+    let mut entries = match base_commit {
+        Some(base_commit) => note_blobs(repo, base_commit)?,
+        None => HashMap::new(),
+    };
+    let mut changed = false;
+    if ref_exists(repo, notes_ref) {
+        for (object, blob_sha) in note_blobs(repo, notes_ref)? {
+            if objects.contains(&object) && entries.get(&object) != Some(&blob_sha) {
+                entries.insert(object, blob_sha);
+                changed = true;
+            }
+        }
+    }
+    if !changed {
+        return Ok(None);
+    }
+
+    // A flat (un-fanned) notes tree is valid; git fans it out again on its next write
+    let mut tree_entries: Vec<String> = entries
+        .iter()
+        .map(|(object, blob_sha)| format!("100644 blob {}\t{}", blob_sha, object))
+        .collect();
+    tree_entries.sort();
+    let mut mktree_args = repo.global_args_for_exec();
+    mktree_args.push("mktree".to_string());
+    let output = exec_git_stdin(
+        &mktree_args,
+        format!("{}\n", tree_entries.join("\n")).as_bytes(),
+    )?;
+    let tree_sha = String::from_utf8(output.stdout)?.trim().to_string();
+
+    let mut commit_args = repo.global_args_for_exec();
+    commit_args.push("commit-tree".to_string());
+    commit_args.push(tree_sha);
+    if let Some(base_commit) = base_commit {
+        commit_args.push("-p".to_string());
+        commit_args.push(base_commit.to_string());
+    }
+    commit_args.push("-m".to_string());
+    commit_args.push("Notes added by 'git-ai-tracker push'".to_string());
+    let output = exec_git(&commit_args)?;
+    Ok(Some(String::from_utf8(output.stdout)?.trim().to_string()))
+}
+
+/// Merge notes from a source ref into refs/notes/<notes_refname> using the 'ours' strategy.
+/// Fine for the content-addressed prompt store; authorship notes go through
+/// `merge_notes_from_ref` so conflicting notes are combined.
+pub fn merge_notes_into(
+    repo: &Repository,
+    notes_refname: &str,
+    source_ref: &str,
+) -> Result<(), GitAiError> {
     let mut args = repo.global_args_for_exec();
     args.push("notes".to_string());
-    args.push(format!("--ref={}", AI_AUTHORSHIP_REFNAME));
+    args.push(format!("--ref={}", notes_refname));
     args.push("merge".to_string());
     args.push("-s".to_string());
     args.push("ours".to_string());
@@ -187,8 +720,8 @@ pub fn merge_notes_from_ref(repo: &Repository, source_ref: &str) -> Result<(), G
     args.push(source_ref.to_string());
 
     debug_log(&format!(
-        "Merging notes from {} into refs/notes/ai",
-        source_ref
+        "Merging notes from {} into refs/notes/{}",
+        source_ref, notes_refname
     ));
     exec_git(&args)?;
     Ok(())
@@ -8,19 +8,26 @@ use std::path::{Path, PathBuf};
 pub struct RepoStorage {
     pub repo_path: PathBuf,
     pub working_logs: PathBuf,
+    pub stashes: PathBuf,
     pub rewrite_log: PathBuf,
+    /// Private directory for short-lived files handed to other programs (e.g. signatures)
+    pub scratch: PathBuf,
 }
 
 impl RepoStorage {
     pub fn for_repo_path(repo_path: &Path) -> RepoStorage {
         let ai_dir = repo_path.join("ai");
         let working_logs_dir = ai_dir.join("working_logs");
+        let stashes_dir = ai_dir.join("stashes");
         let rewrite_log_file = ai_dir.join("rewrite_log");
+        let scratch_dir = ai_dir.join("tmp");
 
         let config = RepoStorage {
             repo_path: repo_path.to_path_buf(),
             working_logs: working_logs_dir,
+            stashes: stashes_dir,
             rewrite_log: rewrite_log_file,
+            scratch: scratch_dir,
         };
 
         // TODO: Consider making this lazy on a read or write.
@@ -74,6 +81,36 @@ impl RepoStorage {
         Ok(())
     }
 
+    /* Stash Snapshot Persistance */
+
+    /// Working log snapshot saved alongside a stash commit. Uses the same layout
+    /// as a regular working log (checkpoints.jsonl + blobs) so it can be restored as-is.
+    pub fn stash_log_for_commit(&self, stash_sha: &str) -> PersistedWorkingLog {
+        let stash_log_dir = self.stashes.join(stash_sha);
+        fs::create_dir_all(&stash_log_dir).unwrap();
+        let repo_root = self.repo_path.parent().unwrap().to_path_buf();
+        PersistedWorkingLog::new(stash_log_dir, stash_sha, repo_root)
+    }
+
+    pub fn has_stash_log(&self, stash_sha: &str) -> bool {
+        self.stashes.join(stash_sha).exists()
+    }
+
+    pub fn delete_stash_log(&self, stash_sha: &str) -> Result<(), GitAiError> {
+        let stash_log_dir = self.stashes.join(stash_sha);
+        if stash_log_dir.exists() {
+            fs::remove_dir_all(&stash_log_dir)?;
+        }
+        Ok(())
+    }
+
+    pub fn delete_all_stash_logs(&self) -> Result<(), GitAiError> {
+        if self.stashes.exists() {
+            fs::remove_dir_all(&self.stashes)?;
+        }
+        Ok(())
+    }
+
     /* Rewrite Log Persistance */
 
     /// Append a rewrite event to the rewrite log file and return the full log
@@ -170,6 +207,16 @@ impl PersistedWorkingLog {
         Ok(())
     }
 
+    /// Replace all checkpoints, keeping the persisted file versions they refer to
+    pub fn write_all_checkpoints(&self, checkpoints: &[Checkpoint]) -> Result<(), GitAiError> {
+        let checkpoints_file = self.dir.join("checkpoints.jsonl");
+        fs::write(&checkpoints_file, "")?;
+        for checkpoint in checkpoints {
+            self.append_checkpoint(checkpoint)?;
+        }
+        Ok(())
+    }
+
     pub fn read_all_checkpoints(&self) -> Result<Vec<Checkpoint>, GitAiError> {
         let checkpoints_file = self.dir.join("checkpoints.jsonl");
 
@@ -1031,6 +1031,38 @@ impl Repository {
         Ok(files)
     }
 
+    /// Read a file's contents as of a commit. Returns None if the path doesn't exist there.
+    pub fn file_content_at_commit(
+        &self,
+        commit_sha: &str,
+        file_path: &str,
+    ) -> Result<Option<String>, GitAiError> {
+        let mut args = self.global_args_for_exec();
+        args.push("show".to_string());
+        args.push(format!("{}:{}", commit_sha, file_path));
+
+        match exec_git(&args) {
+            Ok(output) => Ok(Some(String::from_utf8_lossy(&output.stdout).to_string())),
+            Err(GitAiError::GitCliError { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// List the commits in a revision range (e.g. `main..HEAD`), newest first
+    pub fn rev_list(&self, range: &str) -> Result<Vec<String>, GitAiError> {
+        let mut args = self.global_args_for_exec();
+        args.push("rev-list".to_string());
+        args.push(range.to_string());
+        args.push("--".to_string());
+
+        let output = exec_git(&args)?;
+        Ok(String::from_utf8(output.stdout)?
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| line.to_string())
+            .collect())
+    }
+
     /// Get added line ranges from git diff between two commits
     /// Returns a HashMap of file paths to vectors of added line numbers
     ///
@@ -1146,6 +1178,28 @@ pub fn exec_git(args: &[String]) -> Result<Output, GitAiError> {
     Ok(output)
 }
 
+/// (major, minor) version of the git binary commands are run with
+pub fn git_version() -> Result<(u32, u32), GitAiError> {
+    let output = exec_git(&["version".to_string()])?;
+    let stdout = String::from_utf8(output.stdout)?;
+    parse_git_version(&stdout).ok_or_else(|| {
+        GitAiError::Generic(format!(
+            "Could not parse git version from '{}'",
+            stdout.trim()
+        ))
+    })
+}
+
+/// Parses `git version` output such as "git version 2.39.5" or
+/// "git version 2.40.0.windows.1"
+fn parse_git_version(output: &str) -> Option<(u32, u32)> {
+    let version = output.trim().strip_prefix("git version ")?;
+    let mut parts = version.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next()?.parse().ok()?;
+    Some((major, minor))
+}
+
 /// Helper to execute a git command with data provided on stdin
 pub fn exec_git_stdin(args: &[String], stdin_data: &[u8]) -> Result<Output, GitAiError> {
     // TODO Make sure to handle process signals, etc.
@@ -135,7 +135,6 @@ impl RewriteLogEvent {
         }
     }
 
-    #[allow(dead_code)]
     pub fn stash(event: StashEvent) -> Self {
         Self::Stash { stash: event }
     }
@@ -399,7 +398,6 @@ pub struct StashEvent {
 }
 
 impl StashEvent {
-    #[allow(dead_code)]
     pub fn new(
         operation: StashOperation,
         stash_ref: Option<String>,
@@ -447,6 +445,8 @@ pub enum StashOperation {
     Pop,
     /// Drop stash
     Drop,
+    /// Drop all stashes
+    Clear,
     /// List stashes
     List,
 }
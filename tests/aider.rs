@@ -0,0 +1,86 @@
+mod test_utils;
+
+use git_ai_tracker::authorship::transcript::Message;
+use git_ai_tracker::commands::checkpoint_agent::agent_preset::AiderPreset;
+use test_utils::load_fixture;
+
+#[test]
+fn aider_history_parsing_empty() {
+    let (tx, model) = AiderPreset::transcript_and_model_from_aider_history("", None);
+    assert!(tx.messages.is_empty());
+    assert!(model.is_none());
+    assert!(AiderPreset::session_id_from_aider_chat_history("").is_none());
+}
+
+#[test]
+fn aider_history_parsing_latest_session() {
+    let chat_history = load_fixture("aider.chat.history.md");
+
+    let (tx, model) = AiderPreset::transcript_and_model_from_aider_history(&chat_history, None);
+
+    assert_eq!(model.as_deref(), Some("anthropic/claude-sonnet-4-20250514"));
+    assert_eq!(
+        AiderPreset::session_id_from_aider_chat_history(&chat_history).as_deref(),
+        Some("2025-10-07 14:03:11")
+    );
+
+    // Only the latest session is included
+    let expected_messages = vec![
+        Message::User {
+            text: "add a function that prints hello world\nand call it from main".to_string(),
+            timestamp: None,
+        },
+        Message::Assistant {
+            text: "Here is the new function, called from `main`:\n\nmain.py\n```python\n<<<<<<< SEARCH\ndef main():\n=======\ndef hello_world():\n    print(\"hello world\")\n\n\ndef main():\n    hello_world()\n>>>>>>> REPLACE\n```".to_string(),
+            timestamp: None,
+        },
+        Message::ToolUse {
+            name: "applied_edit".to_string(),
+            input: serde_json::Value::String("main.py".to_string()),
+            timestamp: None,
+        },
+        Message::User {
+            text: "thanks".to_string(),
+            timestamp: None,
+        },
+        Message::Assistant {
+            text: "You're welcome!".to_string(),
+            timestamp: None,
+        },
+    ];
+    assert_eq!(tx.messages, expected_messages);
+}
+
+#[test]
+fn aider_history_parsing_with_input_timestamps() {
+    let chat_history = load_fixture("aider.chat.history.md");
+    let input_history = load_fixture("aider.input.history");
+
+    let (tx, _model) =
+        AiderPreset::transcript_and_model_from_aider_history(&chat_history, Some(&input_history));
+
+    let user_timestamps: Vec<Option<String>> = tx
+        .messages
+        .iter()
+        .filter_map(|message| match message {
+            Message::User { timestamp, .. } => Some(timestamp.clone()),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(user_timestamps.len(), 2);
+    assert!(user_timestamps.iter().all(|ts| ts.is_some()));
+    // Local time is preserved, only the offset depends on the machine's timezone
+    assert!(
+        user_timestamps[0]
+            .as_ref()
+            .unwrap()
+            .starts_with("2025-10-07T14:03:30.118032")
+    );
+    assert!(
+        user_timestamps[1]
+            .as_ref()
+            .unwrap()
+            .starts_with("2025-10-07T14:04:02.554410")
+    );
+}
@@ -0,0 +1,140 @@
+mod test_utils;
+
+use git_ai_tracker::authorship::transcript::Message;
+use git_ai_tracker::commands::checkpoint_agent::agent_preset::{
+    AgentCheckpointFlags, AgentCheckpointPreset, CodexPreset,
+};
+use serde_json::json;
+use test_utils::{fixture_path, load_fixture};
+
+const TEST_SESSION_ID: &str = "0199c2a1-7c3e-7a52-9f4e-3b8d2f1e6a90";
+const TEST_ROLLOUT: &str = "rollout-2025-10-08T10-15-02-0199c2a1-7c3e-7a52-9f4e-3b8d2f1e6a90.jsonl";
+
+#[test]
+fn codex_rollout_parsing_empty() {
+    let (tx, model) = CodexPreset::transcript_and_model_from_codex_rollout_jsonl("").unwrap();
+    assert!(tx.messages.is_empty());
+    assert!(model.is_none());
+}
+
+#[test]
+fn codex_rollout_parsing_invalid_json() {
+    let result = CodexPreset::transcript_and_model_from_codex_rollout_jsonl("{not json");
+    assert!(result.is_err());
+}
+
+#[test]
+fn codex_rollout_parsing_fixture() {
+    let jsonl = load_fixture(TEST_ROLLOUT);
+
+    let (tx, model) = CodexPreset::transcript_and_model_from_codex_rollout_jsonl(&jsonl).unwrap();
+
+    // The model of the latest turn wins
+    assert_eq!(model.as_deref(), Some("gpt-5"));
+    assert_eq!(
+        CodexPreset::session_id_from_codex_rollout_jsonl(&jsonl).as_deref(),
+        Some(TEST_SESSION_ID)
+    );
+
+    // Environment context, reasoning, tool outputs and events are skipped
+    let expected_messages = vec![
+        Message::User {
+            text: "Add a greet function to main.py".to_string(),
+            timestamp: Some("2025-10-08T10:15:09.533Z".to_string()),
+        },
+        Message::ToolUse {
+            name: "shell".to_string(),
+            input: json!({
+                "command": ["bash", "-lc", "cat main.py"],
+                "workdir": "/Users/dev/projects/hello"
+            }),
+            timestamp: Some("2025-10-08T10:15:13.004Z".to_string()),
+        },
+        Message::ToolUse {
+            name: "apply_patch".to_string(),
+            input: json!(
+                "*** Begin Patch\n*** Update File: main.py\n@@\n+def greet(name):\n+    return f\"Hello, {name}!\"\n+\n+\n def main():\n*** End Patch"
+            ),
+            timestamp: Some("2025-10-08T10:15:16.455Z".to_string()),
+        },
+        Message::Assistant {
+            text: "Added `greet(name)` to `main.py`; it returns a greeting for the given name."
+                .to_string(),
+            timestamp: Some("2025-10-08T10:15:18.990Z".to_string()),
+        },
+        Message::User {
+            text: "Now call it from main".to_string(),
+            timestamp: Some("2025-10-08T10:16:40.319Z".to_string()),
+        },
+        Message::ToolUse {
+            name: "apply_patch".to_string(),
+            input: json!(
+                "*** Begin Patch\n*** Update File: main.py\n@@\n def main():\n-    pass\n+    print(greet(\"world\"))\n*** End Patch"
+            ),
+            timestamp: Some("2025-10-08T10:16:44.102Z".to_string()),
+        },
+        Message::Assistant {
+            text: "`main()` now prints `greet(\"world\")`.".to_string(),
+            timestamp: Some("2025-10-08T10:16:46.777Z".to_string()),
+        },
+    ];
+    assert_eq!(tx.messages, expected_messages);
+}
+
+#[test]
+fn codex_find_rollout_file_by_thread_id() {
+    let fixtures_dir = fixture_path("");
+
+    let found = CodexPreset::find_rollout_file(&fixtures_dir, Some(TEST_SESSION_ID)).unwrap();
+    assert_eq!(found, Some(fixture_path(TEST_ROLLOUT)));
+
+    // Without a thread id, falls back to the most recent rollout
+    let fallback = CodexPreset::find_rollout_file(&fixtures_dir, None).unwrap();
+    assert_eq!(fallback, Some(fixture_path(TEST_ROLLOUT)));
+
+    // An unknown thread id never picks up another session's rollout
+    let missing = CodexPreset::find_rollout_file(&fixtures_dir, Some("missing")).unwrap();
+    assert!(missing.is_none());
+
+    let none = CodexPreset::find_rollout_file(&fixture_path("does-not-exist"), None).unwrap();
+    assert!(none.is_none());
+}
+
+#[test]
+fn codex_preset_run_from_notify_payload() {
+    let hook_input = json!({
+        "type": "agent-turn-complete",
+        "thread-id": TEST_SESSION_ID,
+        "turn-id": "1",
+        "cwd": "/Users/dev/projects/hello",
+        "input-messages": ["Now call it from main"],
+        "last-assistant-message": "`main()` now prints `greet(\"world\")`.",
+        "transcript_path": fixture_path(TEST_ROLLOUT),
+    });
+
+    let result = CodexPreset
+        .run(AgentCheckpointFlags {
+            prompt_id: None,
+            hook_input: Some(hook_input.to_string()),
+        })
+        .unwrap();
+
+    assert!(!result.is_human);
+    assert_eq!(result.agent_id.tool, "codex");
+    assert_eq!(result.agent_id.id, TEST_SESSION_ID);
+    assert_eq!(result.agent_id.model, "gpt-5");
+    assert_eq!(
+        result.repo_working_dir.as_deref(),
+        Some("/Users/dev/projects/hello")
+    );
+    assert_eq!(result.transcript.unwrap().messages.len(), 7);
+}
+
+#[test]
+fn codex_preset_requires_hook_input() {
+    let result = CodexPreset.run(AgentCheckpointFlags {
+        prompt_id: None,
+        hook_input: None,
+    });
+    assert!(result.is_err());
+}
@@ -72,7 +72,7 @@ fn test_cursor_database_has_bubble_data() {
 
 #[test]
 fn test_fetch_composer_payload_from_test_db() {
-    use git_ai::commands::checkpoint_agent::agent_preset::CursorPreset;
+    use git_ai_tracker::commands::checkpoint_agent::agent_preset::CursorPreset;
 
     let db_path = fixture_path("cursor_test.vscdb");
 
@@ -109,7 +109,7 @@ fn test_fetch_composer_payload_from_test_db() {
 
 #[test]
 fn test_fetch_bubble_content_from_test_db() {
-    use git_ai::commands::checkpoint_agent::agent_preset::CursorPreset;
+    use git_ai_tracker::commands::checkpoint_agent::agent_preset::CursorPreset;
 
     let db_path = fixture_path("cursor_test.vscdb");
 
@@ -142,7 +142,7 @@ fn test_fetch_bubble_content_from_test_db() {
 
 #[test]
 fn test_extract_transcript_from_test_conversation() {
-    use git_ai::commands::checkpoint_agent::agent_preset::CursorPreset;
+    use git_ai_tracker::commands::checkpoint_agent::agent_preset::CursorPreset;
 
     let db_path = fixture_path("cursor_test.vscdb");
 
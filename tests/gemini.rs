@@ -0,0 +1,152 @@
+mod test_utils;
+
+use git_ai_tracker::authorship::transcript::Message;
+use git_ai_tracker::commands::checkpoint_agent::agent_preset::{
+    AgentCheckpointFlags, AgentCheckpointPreset, GeminiPreset,
+};
+use serde_json::json;
+use test_utils::{fixture_path, load_fixture};
+
+const TEST_SESSION_ID: &str = "3f8a2c1e-5b7d-4e9f-a1c3-9d2e4f6a8b0c";
+
+fn replace_args() -> serde_json::Value {
+    json!({
+        "file_path": "/home/dev/calc/calc.py",
+        "old_string": "def add(a, b):\n    return a + b\n",
+        "new_string": "def add(a, b):\n    return a + b\n\n\ndef subtract(a, b):\n    return a - b\n"
+    })
+}
+
+#[test]
+fn gemini_parsing_invalid_json() {
+    assert!(GeminiPreset::transcript_and_model_from_gemini_json("{not json").is_err());
+    assert!(GeminiPreset::transcript_and_model_from_gemini_json("{}").is_err());
+}
+
+#[test]
+fn gemini_chat_recording_parsing() {
+    let json = load_fixture("gemini_session.json");
+
+    let (tx, model) = GeminiPreset::transcript_and_model_from_gemini_json(&json).unwrap();
+
+    // The model of the latest response wins
+    assert_eq!(model.as_deref(), Some("gemini-2.5-pro"));
+    assert_eq!(
+        GeminiPreset::session_id_from_gemini_json(&json).as_deref(),
+        Some(TEST_SESSION_ID)
+    );
+
+    // Thoughts, tool results and info messages are skipped
+    let expected_messages = vec![
+        Message::User {
+            text: "Add a subtract function to calc.py".to_string(),
+            timestamp: Some("2025-10-09T08:30:25.117Z".to_string()),
+        },
+        Message::Assistant {
+            text: "I'll add `subtract` next to `add`.".to_string(),
+            timestamp: Some("2025-10-09T08:30:31.642Z".to_string()),
+        },
+        Message::ToolUse {
+            name: "replace".to_string(),
+            input: replace_args(),
+            timestamp: Some("2025-10-09T08:30:33.870Z".to_string()),
+        },
+        Message::User {
+            text: "Now add tests for it".to_string(),
+            timestamp: Some("2025-10-09T08:31:02.395Z".to_string()),
+        },
+        Message::Assistant {
+            text: "Added `test_subtract` to `test_calc.py`.".to_string(),
+            timestamp: Some("2025-10-09T08:31:47.918Z".to_string()),
+        },
+        Message::ToolUse {
+            name: "write_file".to_string(),
+            input: json!({
+                "file_path": "/home/dev/calc/test_calc.py",
+                "content": "from calc import subtract\n\n\ndef test_subtract():\n    assert subtract(3, 1) == 2\n"
+            }),
+            timestamp: Some("2025-10-09T08:31:47.511Z".to_string()),
+        },
+    ];
+    assert_eq!(tx.messages, expected_messages);
+}
+
+#[test]
+fn gemini_checkpoint_parsing() {
+    let json = load_fixture("gemini_checkpoint.json");
+
+    let (tx, model) = GeminiPreset::transcript_and_model_from_gemini_json(&json).unwrap();
+
+    assert!(model.is_none());
+    assert!(GeminiPreset::session_id_from_gemini_json(&json).is_none());
+
+    let expected_messages = vec![
+        Message::User {
+            text: "Add a subtract function to calc.py".to_string(),
+            timestamp: None,
+        },
+        Message::Assistant {
+            text: "I'll add `subtract` next to `add`.".to_string(),
+            timestamp: None,
+        },
+        Message::ToolUse {
+            name: "replace".to_string(),
+            input: replace_args(),
+            timestamp: None,
+        },
+        Message::Assistant {
+            text: "Done.".to_string(),
+            timestamp: None,
+        },
+    ];
+    assert_eq!(tx.messages, expected_messages);
+}
+
+#[test]
+fn gemini_preset_after_tool_is_ai() {
+    let hook_input = json!({
+        "session_id": TEST_SESSION_ID,
+        "transcript_path": fixture_path("gemini_session.json"),
+        "cwd": "/home/dev/calc",
+        "hook_event_name": "AfterTool",
+        "timestamp": "2025-10-09T08:31:47.600Z",
+        "tool_name": "write_file",
+        "tool_input": {"file_path": "/home/dev/calc/test_calc.py"}
+    });
+
+    let result = GeminiPreset
+        .run(AgentCheckpointFlags {
+            prompt_id: None,
+            hook_input: Some(hook_input.to_string()),
+        })
+        .unwrap();
+
+    assert!(!result.is_human);
+    assert_eq!(result.agent_id.tool, "gemini");
+    assert_eq!(result.agent_id.id, TEST_SESSION_ID);
+    assert_eq!(result.agent_id.model, "gemini-2.5-pro");
+    assert_eq!(result.repo_working_dir.as_deref(), Some("/home/dev/calc"));
+    assert_eq!(result.transcript.unwrap().messages.len(), 6);
+}
+
+#[test]
+fn gemini_preset_before_tool_is_human() {
+    let hook_input = json!({
+        "session_id": TEST_SESSION_ID,
+        "transcript_path": fixture_path("gemini_session.json"),
+        "cwd": "/home/dev/calc",
+        "hook_event_name": "BeforeTool",
+        "tool_name": "replace"
+    });
+
+    let result = GeminiPreset
+        .run(AgentCheckpointFlags {
+            prompt_id: None,
+            hook_input: Some(hook_input.to_string()),
+        })
+        .unwrap();
+
+    assert!(result.is_human);
+    assert!(result.transcript.is_none());
+    assert_eq!(result.repo_working_dir.as_deref(), Some("/home/dev/calc"));
+}
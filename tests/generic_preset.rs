@@ -0,0 +1,200 @@
+#[macro_use]
+mod repos;
+use git_ai_tracker::commands::checkpoint_agent::agent_preset::{
+    AgentCheckpointFlags, AgentCheckpointPreset, AgentRunResult, EditedFile, GenericPreset,
+};
+use git_ai_tracker::error::GitAiError;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use serde_json::json;
+
+fn run_generic(hook_input: serde_json::Value) -> Result<AgentRunResult, GitAiError> {
+    GenericPreset.run(AgentCheckpointFlags {
+        prompt_id: None,
+        hook_input: Some(hook_input.to_string()),
+    })
+}
+
+fn assert_preset_error(hook_input: serde_json::Value, expected: &str) {
+    match run_generic(hook_input) {
+        Err(GitAiError::PresetError(message)) => assert!(
+            message.contains(expected),
+            "expected error containing {:?}, got {:?}",
+            expected,
+            message
+        ),
+        Err(e) => panic!("expected PresetError, got {}", e),
+        Ok(_) => panic!("expected PresetError containing {:?}", expected),
+    }
+}
+
+#[test]
+fn test_generic_preset_parses_full_payload() {
+    let result = run_generic(json!({
+        "version": 1,
+        "tool": "acme-agent",
+        "session_id": "run-42",
+        "model": "acme-large-2",
+        "cwd": "/work/repo",
+        "messages": [
+            {"role": "user", "text": "Fix the flaky test", "timestamp": "2025-10-10T09:00:00Z"},
+            {"role": "assistant", "text": "Added a retry", "timestamp": "2025-10-10T09:00:05.120+02:00"},
+            {"role": "tool_use", "name": "edit_file", "input": {"path": "tests/flaky.rs"}}
+        ],
+        "edited_files": [
+            {"path": "tests/flaky.rs", "line_ranges": [[10, 14], [20, 20]]},
+            {"path": "./README.md"}
+        ]
+    }))
+    .unwrap();
+
+    assert!(!result.is_human);
+    assert_eq!(result.agent_id.tool, "acme-agent");
+    assert_eq!(result.agent_id.id, "run-42");
+    assert_eq!(result.agent_id.model, "acme-large-2");
+    assert_eq!(result.repo_working_dir.as_deref(), Some("/work/repo"));
+    assert_eq!(result.transcript.unwrap().messages.len(), 3);
+    assert_eq!(
+        result.edited_files,
+        Some(vec![
+            EditedFile {
+                path: "tests/flaky.rs".to_string(),
+                line_ranges: vec![(10, 14), (20, 20)],
+            },
+            EditedFile {
+                path: "README.md".to_string(),
+                line_ranges: vec![],
+            },
+        ])
+    );
+}
+
+#[test]
+fn test_generic_preset_minimal_payload() {
+    let result = run_generic(json!({
+        "version": 1,
+        "tool": "script",
+        "session_id": "nightly"
+    }))
+    .unwrap();
+
+    assert_eq!(result.agent_id.model, "unknown");
+    assert!(result.transcript.unwrap().messages.is_empty());
+    assert!(result.edited_files.is_none());
+    assert!(result.repo_working_dir.is_none());
+}
+
+#[test]
+fn test_generic_preset_validation_errors() {
+    assert_preset_error(json!({"tool": "a", "session_id": "b"}), "version not found");
+    assert_preset_error(
+        json!({"version": 2, "tool": "a", "session_id": "b"}),
+        "Unsupported generic preset schema version 2",
+    );
+    assert_preset_error(
+        json!({"version": 1, "session_id": "b"}),
+        "missing field `tool`",
+    );
+    assert_preset_error(
+        json!({"version": 1, "tool": " ", "session_id": "b"}),
+        "tool must not be empty",
+    );
+    assert_preset_error(
+        json!({"version": 1, "tool": "a", "session_id": "b", "sesion": "typo"}),
+        "unknown field `sesion`",
+    );
+    assert_preset_error(
+        json!({"version": 1, "tool": "a", "session_id": "b",
+               "messages": [{"role": "system", "text": "hi"}]}),
+        "unknown variant `system`",
+    );
+    assert_preset_error(
+        json!({"version": 1, "tool": "a", "session_id": "b",
+               "messages": [{"role": "user", "text": "hi", "timestamp": "yesterday"}]}),
+        "messages[0].timestamp is not an RFC 3339 timestamp",
+    );
+    assert_preset_error(
+        json!({"version": 1, "tool": "a", "session_id": "b",
+               "edited_files": [{"path": "src/lib.rs", "line_ranges": [[5, 2]]}]}),
+        "Invalid line range [5, 2]",
+    );
+    assert_preset_error(
+        json!({"version": 1, "tool": "a", "session_id": "b",
+               "edited_files": [{"path": "../outside.rs"}]}),
+        "Invalid edited file path",
+    );
+}
+
+#[test]
+fn test_generic_preset_absolute_edited_file_paths() {
+    let repo = TestRepo::new();
+    std::fs::create_dir_all(repo.path().join("src")).unwrap();
+    std::fs::write(repo.path().join("src/lib.rs"), "").unwrap();
+    let cwd = repo.path().to_str().unwrap().to_string();
+
+    assert_preset_error(
+        json!({"version": 1, "tool": "a", "session_id": "b", "cwd": cwd,
+               "edited_files": [{"path": "/etc/passwd"}]}),
+        "outside the repository",
+    );
+
+    let result = run_generic(json!({
+        "version": 1,
+        "tool": "a",
+        "session_id": "b",
+        "cwd": cwd,
+        "edited_files": [{"path": repo.path().join("src/lib.rs"), "line_ranges": [[1, 2]]}]
+    }))
+    .unwrap();
+    assert_eq!(
+        result.edited_files,
+        Some(vec![EditedFile {
+            path: "src/lib.rs".to_string(),
+            line_ranges: vec![(1, 2)],
+        }])
+    );
+}
+
+/// Only the files and lines the agent reports are attributed to it
+#[test]
+fn test_generic_checkpoint_attributes_reported_lines() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("notes.txt");
+    let mut other = repo.filename("other.txt");
+
+    file.set_contents(lines!["line 1", "line 2"]);
+    other.set_contents(lines!["other 1"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    std::fs::write(
+        repo.path().join("notes.txt"),
+        "line 1\nhuman edit\nagent line 1\nagent line 2\nline 2\n",
+    )
+    .unwrap();
+    std::fs::write(repo.path().join("other.txt"), "other 1\nother 2\n").unwrap();
+
+    let hook_input = json!({
+        "version": 1,
+        "tool": "some-ai",
+        "session_id": "session-1",
+        "messages": [{"role": "user", "text": "add two lines"}],
+        "edited_files": [{"path": "notes.txt", "line_ranges": [[3, 4]]}]
+    });
+    repo.git_ai(&[
+        "checkpoint",
+        "generic",
+        "--hook-input",
+        &hook_input.to_string(),
+    ])
+    .unwrap();
+    repo.stage_all_and_commit("Agent edits").unwrap();
+
+    file.assert_lines_and_blame(lines![
+        "line 1".human(),
+        "human edit".human(),
+        "agent line 1".ai(),
+        "agent line 2".ai(),
+        "line 2".human(),
+    ]);
+    other.assert_lines_and_blame(lines!["other 1".human(), "other 2".human()]);
+}
@@ -0,0 +1,109 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+/// Test git mv of a file with committed AI lines: attribution follows the file
+#[test]
+fn test_git_mv_preserves_committed_ai_authorship() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("original.rs");
+
+    file.set_contents(lines!["fn main() {", "    // AI: helper".ai(), "}"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    repo.git(&["mv", "original.rs", "renamed.rs"])
+        .expect("git mv should succeed");
+    repo.stage_all_and_commit("Rename file").unwrap();
+
+    file = repo.filename("renamed.rs");
+    file.assert_lines_and_blame(lines![
+        "fn main() {".human(),
+        "    // AI: helper".ai(),
+        "}".human(),
+    ]);
+}
+
+/// Test uncommitted AI lines followed by git mv and a commit
+#[test]
+fn test_git_mv_with_uncommitted_ai_lines() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("original.txt");
+
+    file.set_contents(lines!["line 1", "line 2", "line 3", "line 4"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    file.insert_at(1, lines!["// AI line".ai()]);
+
+    repo.git(&["mv", "original.txt", "renamed.txt"])
+        .expect("git mv should succeed");
+    repo.stage_all_and_commit("Rename with AI edits").unwrap();
+
+    file = repo.filename("renamed.txt");
+    file.assert_lines_and_blame(lines![
+        "line 1".human(),
+        "// AI line".ai(),
+        "line 2".human(),
+        "line 3".human(),
+        "line 4".human(),
+    ]);
+}
+
+/// Test a rename with additional edits that is still detected as a rename
+#[test]
+fn test_rename_with_edits_preserves_ai_authorship() {
+    let repo = TestRepo::new();
+    std::fs::create_dir_all(repo.path().join("src")).unwrap();
+    let mut file = repo.filename("src/lib.rs");
+
+    file.set_contents(lines![
+        "pub fn one() {}",
+        "pub fn two() {}",
+        "// AI: three".ai(),
+        "pub fn four() {}",
+        "pub fn five() {}",
+    ]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    repo.git(&["mv", "src/lib.rs", "src/core.rs"])
+        .expect("git mv should succeed");
+    file = repo.filename("src/core.rs");
+    file.insert_at(5, lines!["pub fn six() {}"]);
+    repo.stage_all_and_commit("Rename and extend").unwrap();
+
+    file = repo.filename("src/core.rs");
+    file.assert_lines_and_blame(lines![
+        "pub fn one() {}".human(),
+        "pub fn two() {}".human(),
+        "// AI: three".ai(),
+        "pub fn four() {}".human(),
+        "pub fn five() {}".human(),
+        "pub fn six() {}".human(),
+    ]);
+}
+
+/// Test blame on a renamed file reports AI lines from commits made before the rename
+#[test]
+fn test_blame_follows_rename() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("before.txt");
+
+    file.set_contents(lines!["human 1", "// AI 1".ai(), "human 2"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    repo.git(&["mv", "before.txt", "after.txt"])
+        .expect("git mv should succeed");
+    repo.stage_all_and_commit("Rename file").unwrap();
+
+    file = repo.filename("after.txt");
+    file.insert_at(3, lines!["// AI 2".ai()]);
+    repo.stage_all_and_commit("Add more AI lines").unwrap();
+
+    file = repo.filename("after.txt");
+    file.assert_lines_and_blame(lines![
+        "human 1".human(),
+        "// AI 1".ai(),
+        "human 2".human(),
+        "// AI 2".ai(),
+    ]);
+}
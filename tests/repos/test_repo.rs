@@ -1,13 +1,12 @@
-use git_ai::authorship::authorship_log_serialization::AuthorshipLog;
-use git_ai::git::repo_storage::PersistedWorkingLog;
-use git_ai::git::repository as GitAiRepository;
+use git_ai_tracker::authorship::authorship_log_serialization::AuthorshipLog;
+use git_ai_tracker::git::repo_storage::PersistedWorkingLog;
+use git_ai_tracker::git::repository as GitAiRepository;
 use git2::Repository;
 use insta::assert_debug_snapshot;
 use rand::Rng;
 use std::fs;
 use std::path::PathBuf;
 use std::process::Command;
-use std::sync::OnceLock;
 
 use super::test_file::TestFile;
 
@@ -80,7 +79,7 @@ impl TestRepo {
 
         let output = Command::new(binary_path)
             .args(&full_args)
-            .env("GIT_AI", "git")
+            .env("GIT_AI_TRACKER", "git")
             .output()
             .expect(&format!("Failed to execute git command: {:?}", args));
 
@@ -109,7 +108,7 @@ impl TestRepo {
         full_args.extend(args);
 
         let mut command = Command::new(binary_path);
-        command.args(&full_args).env("GIT_AI", "git");
+        command.args(&full_args).env("GIT_AI_TRACKER", "git");
 
         // Add custom environment variables
         for (key, value) in envs {
@@ -183,7 +182,7 @@ impl TestRepo {
                 .map_err(|e| format!("Failed to get HEAD target: {}", e))?;
 
             // Get the authorship log for the new commit
-            let authorship_log = match git_ai::git::refs::show_authorship_note(&repo, &head_commit)
+            let authorship_log = match git_ai_tracker::git::refs::show_authorship_note(&repo, &head_commit)
             {
                 Some(content) => AuthorshipLog::deserialize_from_string(&content)
                     .map_err(|e| format!("Failed to parse authorship log: {}", e))?,
@@ -232,32 +231,9 @@ impl NewCommit {
     }
 }
 
-static COMPILED_BINARY: OnceLock<PathBuf> = OnceLock::new();
-
-fn compile_binary() -> PathBuf {
-    println!("Compiling git-ai-tracker binary for tests...");
-
-    let manifest_dir = env!("CARGO_MANIFEST_DIR");
-    let output = Command::new("cargo")
-        .args(&["build", "--bin", "git-ai-tracker"])
-        .current_dir(manifest_dir)
-        .output()
-        .expect("Failed to compile git-ai-tracker binary");
-
-    if !output.status.success() {
-        panic!(
-            "Failed to compile git-ai-tracker:\nstdout: {}\nstderr: {}",
-            String::from_utf8_lossy(&output.stdout),
-            String::from_utf8_lossy(&output.stderr)
-        );
-    }
-
-    let binary_path = PathBuf::from(manifest_dir).join("target/debug/git-ai-tracker");
-    binary_path
-}
-
-fn get_binary_path() -> &'static PathBuf {
-    COMPILED_BINARY.get_or_init(compile_binary)
+/// The binary cargo builds for integration tests
+fn get_binary_path() -> &'static str {
+    env!("CARGO_BIN_EXE_git-ai-tracker")
 }
 
 #[cfg(test)]
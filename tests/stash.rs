@@ -0,0 +1,160 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+/// Test git stash + git stash pop on the same commit: AI authorship is restored
+#[test]
+fn test_stash_pop_restores_ai_authorship() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("test.txt");
+
+    file.set_contents(lines!["line 1", "line 2"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    file.insert_at(2, lines!["// AI line 1".ai(), "// AI line 2".ai()]);
+
+    repo.git(&["stash"]).expect("stash should succeed");
+
+    // Working directory is clean after stash
+    file = repo.filename("test.txt");
+    file.assert_lines_and_blame(lines!["line 1", "line 2"]);
+
+    repo.git(&["stash", "pop"])
+        .expect("stash pop should succeed");
+
+    repo.stage_all_and_commit("After stash pop").unwrap();
+
+    file = repo.filename("test.txt");
+    file.assert_lines_and_blame(lines![
+        "line 1".human(),
+        "line 2".human(),
+        "// AI line 1".ai(),
+        "// AI line 2".ai(),
+    ]);
+}
+
+/// Test git stash pop onto a different commit: AI lines are shifted to their new position
+#[test]
+fn test_stash_pop_onto_new_commit_shifts_lines() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("main.rs");
+
+    file.set_contents(lines!["fn main() {", "}"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    file.insert_at(1, lines!["    // AI: Added logging".ai()]);
+
+    repo.git(&["stash", "push"])
+        .expect("stash push should succeed");
+
+    // Human adds lines above the stashed change and commits
+    file = repo.filename("main.rs");
+    file.insert_at(0, lines!["// header", "// more header"]);
+    repo.stage_all_and_commit("Add header").unwrap();
+
+    repo.git(&["stash", "pop"])
+        .expect("stash pop should succeed");
+
+    repo.stage_all_and_commit("After stash pop").unwrap();
+
+    file = repo.filename("main.rs");
+    file.assert_lines_and_blame(lines![
+        "// header".human(),
+        "// more header".human(),
+        "fn main() {".human(),
+        "    // AI: Added logging".ai(),
+        "}".human(),
+    ]);
+}
+
+/// Test git stash with multiple files, including an untracked one (-u)
+#[test]
+fn test_stash_include_untracked_multiple_files() {
+    let repo = TestRepo::new();
+    let mut tracked = repo.filename("tracked.txt");
+
+    tracked.set_contents(lines!["a", "b", "c"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    tracked.insert_at(1, lines!["// AI tracked".ai()]);
+    let mut untracked = repo.filename("untracked.txt");
+    untracked.set_contents(lines!["// AI untracked".ai(), "human line"]);
+
+    repo.git(&["stash", "-u"]).expect("stash -u should succeed");
+    assert!(repo.read_file("untracked.txt").is_none());
+
+    repo.git(&["stash", "pop"])
+        .expect("stash pop should succeed");
+
+    repo.stage_all_and_commit("After stash pop").unwrap();
+
+    tracked = repo.filename("tracked.txt");
+    tracked.assert_lines_and_blame(lines![
+        "a".human(),
+        "// AI tracked".ai(),
+        "b".human(),
+        "c".human(),
+    ]);
+
+    untracked = repo.filename("untracked.txt");
+    untracked.assert_lines_and_blame(lines!["// AI untracked".ai(), "human line".human()]);
+}
+
+/// Test git stash apply keeps the snapshot, and drop cleans it up
+#[test]
+fn test_stash_apply_then_drop() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("test.txt");
+
+    file.set_contents(lines!["line 1"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    file.insert_at(1, lines!["// AI line".ai()]);
+
+    repo.git(&["stash"]).expect("stash should succeed");
+    let stash_sha = repo
+        .git(&["rev-parse", "stash@{0}"])
+        .unwrap()
+        .trim()
+        .to_string();
+    let snapshot_dir = repo.path().join(".git/ai/stashes").join(&stash_sha);
+    assert!(snapshot_dir.exists(), "stash snapshot should be saved");
+
+    repo.git(&["stash", "apply"])
+        .expect("stash apply should succeed");
+    assert!(snapshot_dir.exists(), "apply should keep the snapshot");
+
+    repo.git(&["stash", "drop"])
+        .expect("stash drop should succeed");
+    assert!(!snapshot_dir.exists(), "drop should delete the snapshot");
+
+    repo.stage_all_and_commit("After stash apply").unwrap();
+
+    file = repo.filename("test.txt");
+    file.assert_lines_and_blame(lines!["line 1".human(), "// AI line".ai()]);
+}
+
+/// Test git stash clear removes all snapshots
+#[test]
+fn test_stash_clear_deletes_snapshots() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("test.txt");
+
+    file.set_contents(lines!["line 1"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    file.insert_at(1, lines!["// AI line".ai()]);
+    repo.git(&["stash"]).expect("stash should succeed");
+
+    file = repo.filename("test.txt");
+    file.insert_at(1, lines!["// Another AI line".ai()]);
+    repo.git(&["stash"]).expect("stash should succeed");
+
+    let stashes_dir = repo.path().join(".git/ai/stashes");
+    assert_eq!(std::fs::read_dir(&stashes_dir).unwrap().count(), 2);
+
+    repo.git(&["stash", "clear"])
+        .expect("stash clear should succeed");
+    assert!(!stashes_dir.exists() || std::fs::read_dir(&stashes_dir).unwrap().count() == 0);
+}
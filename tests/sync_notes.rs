@@ -0,0 +1,121 @@
+#[macro_use]
+mod repos;
+use rand::Rng;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::fs;
+use std::process::Command;
+
+fn authorship_note(repo_path: &std::path::Path, commit_sha: &str) -> Option<String> {
+    let output = Command::new("git")
+        .args(["-C", repo_path.to_str().unwrap()])
+        .args(["notes", "--ref=ai", "show", commit_sha])
+        .output()
+        .expect("failed to run git notes show");
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn repo_with_ai_commit() -> (TestRepo, String) {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("test.txt");
+    file.set_contents(lines!["Line 1", "Line 2".ai(), "Line 3".ai()]);
+    let commit = repo.stage_all_and_commit("AI commit").unwrap();
+    (repo, commit.commit_sha)
+}
+
+#[test]
+fn test_clone_fetches_authorship_notes() {
+    let (source, commit_sha) = repo_with_ai_commit();
+
+    let n: u64 = rand::thread_rng().gen_range(0..10000000000);
+    let clone_path = std::env::temp_dir().join(format!("clone-{}", n));
+
+    // The second -C leaves the source repository, so clone runs outside any repository
+    source
+        .git(&[
+            "-C",
+            std::env::temp_dir().to_str().unwrap(),
+            "clone",
+            source.path().to_str().unwrap(),
+            clone_path.to_str().unwrap(),
+        ])
+        .unwrap();
+
+    let note = authorship_note(&clone_path, &commit_sha);
+    fs::remove_dir_all(&clone_path).expect("failed to remove clone");
+    assert!(note.unwrap().contains("test.txt"));
+}
+
+#[test]
+fn test_pull_fetches_authorship_notes() {
+    let (source, commit_sha) = repo_with_ai_commit();
+
+    let repo = TestRepo::new();
+    repo.git(&["remote", "add", "origin", source.path().to_str().unwrap()])
+        .unwrap();
+    assert!(authorship_note(repo.path(), &commit_sha).is_none());
+
+    repo.git(&["pull", "origin", &source.current_branch()])
+        .unwrap();
+
+    assert!(
+        authorship_note(repo.path(), &commit_sha)
+            .unwrap()
+            .contains("test.txt")
+    );
+}
+
+fn bare_remote() -> std::path::PathBuf {
+    let n: u64 = rand::thread_rng().gen_range(0..10000000000);
+    let path = std::env::temp_dir().join(format!("notes-remote-{}.git", n));
+    let status = Command::new("git")
+        .args(["init", "--bare", "--quiet", path.to_str().unwrap()])
+        .status()
+        .expect("failed to run git init");
+    assert!(status.success());
+    path
+}
+
+#[test]
+fn test_push_reachable_notes_to_notes_remote() {
+    let (repo, pushed_sha) = repo_with_ai_commit();
+    let branch = repo.current_branch();
+
+    // An unpublished local branch whose note must stay local
+    repo.git(&["checkout", "-b", "local-only"]).unwrap();
+    let mut file = repo.filename("local.txt");
+    file.set_contents(lines!["Local".ai()]);
+    let local_sha = repo
+        .stage_all_and_commit("Local commit")
+        .unwrap()
+        .commit_sha;
+    repo.git(&["checkout", &branch]).unwrap();
+
+    let code_remote = bare_remote();
+    let notes_remote = bare_remote();
+    repo.git(&["remote", "add", "fork", code_remote.to_str().unwrap()])
+        .unwrap();
+    repo.git(&[
+        "config",
+        "git-ai-tracker.notesRemote",
+        notes_remote.to_str().unwrap(),
+    ])
+    .unwrap();
+    repo.git(&["config", "git-ai-tracker.notesPush", "reachable"])
+        .unwrap();
+
+    repo.git(&["push", "fork", &branch]).unwrap();
+
+    let pushed_note = authorship_note(&notes_remote, &pushed_sha);
+    let local_note = authorship_note(&notes_remote, &local_sha);
+    let code_remote_note = authorship_note(&code_remote, &pushed_sha);
+    fs::remove_dir_all(&code_remote).expect("failed to remove remote");
+    fs::remove_dir_all(&notes_remote).expect("failed to remove remote");
+
+    assert!(pushed_note.unwrap().contains("test.txt"));
+    assert!(local_note.is_none());
+    assert!(code_remote_note.is_none());
+}